@@ -1,5 +1,5 @@
 use poker_core::Card;
-use poker_eval::equity_heads_up;
+use poker_eval::{equity_heads_up, Evaluator};
 use serde::Serialize;
 use tauri::State;
 
@@ -15,10 +15,7 @@ pub struct HandResult {
 
 /// Evaluate a poker hand (5 or 7 cards).
 #[tauri::command]
-pub fn evaluate_hand(
-    cards: Vec<Card>,
-    state: State<'_, AppState>,
-) -> Result<HandResult, AppError> {
+pub fn evaluate_hand(cards: Vec<Card>, state: State<'_, AppState>) -> Result<HandResult, AppError> {
     match cards.len() {
         5 => {
             let hand: [Card; 5] = cards
@@ -77,8 +74,7 @@ pub fn equity_calculation(
         .try_into()
         .map_err(|_| AppError::Eval("Villain hand conversion failed".into()))?;
 
-    let (eq_hero, eq_villain) =
-        equity_heads_up(&state.evaluator, hand_arr, villain_arr, &board);
+    let (eq_hero, eq_villain) = equity_heads_up(state.evaluator, hand_arr, villain_arr, &board);
 
     Ok(EquityResult {
         equity: eq_hero,
@@ -1,3 +1,5 @@
+use poker_core::{Card, ComboRange, ComboWeight, Range};
+use poker_eval::equity_range_vs_range;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -7,23 +9,80 @@ use crate::state::AppState;
 #[derive(Serialize)]
 pub struct RangeEquityResult {
     pub equity: f64,
+    pub win: f64,
+    pub tie: f64,
+    /// Per hand class equity of each range (169 entries, null if not in range).
+    pub hand_equity1: Vec<Option<f64>>,
+    pub hand_equity2: Vec<Option<f64>>,
 }
 
-/// Compute equity for a range vs range matchup (placeholder — full implementation in M2).
+/// Compute exact equity for a range vs range matchup.
+/// Range weights are 0-100 per canonical hand class; board cards use "Ah" notation.
+/// Preflop enumeration takes seconds, so the work runs on a blocking worker
+/// thread instead of the UI thread.
 #[tauri::command]
-pub fn compute_equity(
+pub async fn compute_equity(
     range1: Vec<f64>,
     range2: Vec<f64>,
-    _board: Vec<u8>,
-    _state: State<'_, AppState>,
+    board: Vec<String>,
+    state: State<'_, AppState>,
 ) -> Result<RangeEquityResult, AppError> {
     if range1.len() != 169 || range2.len() != 169 {
         return Err(AppError::Range(
             "Range arrays must have exactly 169 elements".into(),
         ));
     }
-    // Placeholder: return 50% equity until full range-vs-range enumeration in M2
-    Ok(RangeEquityResult { equity: 0.5 })
+    let board = board
+        .iter()
+        .map(|s| Card::from_str_notation(s))
+        .collect::<Result<Vec<Card>, _>>()
+        .map_err(|e| AppError::Eval(e.to_string()))?;
+
+    let combos1 = ComboRange::from_range(&range_from_percent(&range1));
+    let combos2 = ComboRange::from_range(&range_from_percent(&range2));
+    let evaluator = state.evaluator;
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        equity_range_vs_range(evaluator, &combos1, &combos2, &board)
+    })
+    .await
+    .map_err(|e| AppError::Eval(e.to_string()))?
+    .map_err(|e| AppError::Eval(e.to_string()))?;
+
+    Ok(RangeEquityResult {
+        equity: result.equity,
+        win: result.win,
+        tie: result.tie,
+        hand_equity1: result.hand_equity1,
+        hand_equity2: result.hand_equity2,
+    })
+}
+
+fn range_from_percent(weights: &[f64]) -> Range {
+    let mut range = Range::empty();
+    for (idx, &w) in weights.iter().enumerate() {
+        range.set_weight(idx, (w / 100.0) as f32);
+    }
+    range
+}
+
+/// List the specific suit combos of one matrix cell (0..168) for the SuitExpander.
+/// `weights` holds 1326 per-combo weights indexed by `Hand::combo_index`, on
+/// the same 0-100 scale as the range matrix; returned weights use it too.
+#[tauri::command]
+pub fn group_combos(weights: Vec<f32>, index: usize) -> Result<Vec<ComboWeight>, AppError> {
+    if index >= 169 {
+        return Err(AppError::Range(format!("Invalid matrix index: {index}")));
+    }
+    let fractions = weights.iter().map(|w| w / 100.0).collect();
+    let combos = ComboRange::from_weights(fractions).map_err(|e| AppError::Range(e.to_string()))?;
+    Ok(combos
+        .group_combos(index)
+        .into_iter()
+        .map(|combo| ComboWeight {
+            weight: combo.weight * 100.0,
+            ..combo
+        })
+        .collect())
 }
 
 #[derive(Serialize)]
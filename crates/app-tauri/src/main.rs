@@ -18,6 +18,7 @@ fn main() {
             commands::eval::evaluate_hand,
             commands::eval::equity_calculation,
             commands::range::compute_equity,
+            commands::range::group_combos,
             commands::range::load_preset,
             commands::range::save_preset,
             commands::settings::get_config,
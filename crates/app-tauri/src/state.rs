@@ -1,17 +1,16 @@
-use poker_eval::LookupTableEvaluator;
-use std::sync::Arc;
+use poker_eval::{Evaluator, SevenCardEvaluator};
 
 /// Shared application state managed by Tauri.
-/// Holds the hand evaluator (expensive to create, shared across commands).
+/// Holds the process-wide hand evaluator shared across commands.
 pub struct AppState {
-    pub evaluator: Arc<LookupTableEvaluator>,
+    pub evaluator: &'static dyn Evaluator,
     pub data_dir: std::path::PathBuf,
 }
 
 impl AppState {
     pub fn new(data_dir: std::path::PathBuf) -> Self {
         Self {
-            evaluator: Arc::new(LookupTableEvaluator::new()),
+            evaluator: SevenCardEvaluator::global(),
             data_dir,
         }
     }
@@ -1,18 +1,20 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::chips::Chips;
+
 /// A poker action.
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Action {
     Fold,
     Check,
     Call,
     /// Bet a specific amount (opening bet).
-    Bet(f64),
+    Bet(Chips),
     /// Raise to a specific amount.
-    Raise(f64),
+    Raise(Chips),
     /// All-in for remaining stack.
-    AllIn(f64),
+    AllIn(Chips),
 }
 
 impl fmt::Display for Action {
@@ -21,9 +23,9 @@ impl fmt::Display for Action {
             Action::Fold => write!(f, "Fold"),
             Action::Check => write!(f, "Check"),
             Action::Call => write!(f, "Call"),
-            Action::Bet(amt) => write!(f, "Bet {:.2}", amt),
-            Action::Raise(amt) => write!(f, "Raise {:.2}", amt),
-            Action::AllIn(amt) => write!(f, "All-In {:.2}", amt),
+            Action::Bet(amt) => write!(f, "Bet {}", amt),
+            Action::Raise(amt) => write!(f, "Raise {}", amt),
+            Action::AllIn(amt) => write!(f, "All-In {}", amt),
         }
     }
 }
@@ -38,13 +40,22 @@ mod tests {
             Action::Fold,
             Action::Check,
             Action::Call,
-            Action::Bet(100.0),
-            Action::Raise(250.0),
-            Action::AllIn(1000.0),
+            Action::Bet(Chips::from_units(100)),
+            Action::Raise(Chips::from_units(250)),
+            Action::AllIn(Chips::from_units(1000)),
         ];
         assert_eq!(actions.len(), 6);
         assert_eq!(Action::Fold, Action::Fold);
         assert_ne!(Action::Fold, Action::Check);
-        assert_eq!(format!("{}", Action::Bet(50.0)), "Bet 50.00");
+        assert_eq!(
+            format!("{}", Action::Bet(Chips::from_units(50))),
+            "Bet 50.00"
+        );
+
+        // Amounts stay plain numbers in JSON
+        let json = serde_json::to_string(&Action::Raise(Chips::from(2.5))).expect("serialize");
+        assert_eq!(json, r#"{"Raise":2.5}"#);
+        let back: Action = serde_json::from_str(r#"{"Bet":33.0}"#).expect("deserialize");
+        assert_eq!(back, Action::Bet(Chips::from_units(33)));
     }
 }
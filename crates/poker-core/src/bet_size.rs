@@ -1,34 +1,229 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+
+use crate::chips::Chips;
+use crate::error::CoreError;
 
 /// Bet sizing specification.
+///
+/// Sizes on top of a facing bet are raise increments: after calling, a
+/// `PotFraction(1.0)` raise adds the pot after the call. Resolved amounts
+/// never fall below a legal minimum bet or raise.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum BetSize {
     /// Fraction of the pot (e.g., 0.5 = half pot, 1.0 = pot).
     PotFraction(f64),
     /// Absolute chip amount.
-    Absolute(f64),
+    Absolute(Chips),
+    /// Amount in big blinds.
+    BigBlinds(f64),
+    /// Multiple of the facing bet as a total ("raise to 2.5x"), or of the
+    /// big blind with no bet to face.
+    RaiseMultiple(f64),
+    /// Same pot fraction on this and the remaining streets gets the
+    /// effective stack in on the last of this many streets.
+    Geometric(u32),
     /// All-in (entire remaining stack).
     AllIn,
+    /// All-in, offered only when the stack-to-pot ratio after calling is
+    /// below this threshold.
+    AllInBelowSpr(f64),
+}
+
+/// Largest pot fraction, big-blind count, raise multiple or SPR threshold a
+/// size may use. Anything bigger is a typo, and would saturate `Chips`.
+pub const MAX_SIZE_FACTOR: f64 = 1e6;
+
+/// Betting situation a size is resolved against.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BetContext {
+    /// Chips in the middle, including bets on the current street.
+    pub pot: Chips,
+    /// Largest street commitment, which the player faces.
+    pub facing: Chips,
+    /// The player's own street commitment.
+    pub committed: Chips,
+    /// Size of the last bet or raise increment this street.
+    pub last_raise: Chips,
+    /// The player's chips behind.
+    pub stack: Chips,
+    /// Chips behind for the deepest opponent still able to call.
+    pub opponent_stack: Chips,
+    pub big_blind: Chips,
+}
+
+impl BetContext {
+    fn call_amount(&self) -> Chips {
+        (self.facing - self.committed)
+            .max(Chips::ZERO)
+            .min(self.stack)
+    }
+
+    fn pot_after_call(&self) -> Chips {
+        self.pot + self.call_amount()
+    }
+
+    /// Chips both players still have behind once the player calls.
+    fn effective_behind(&self) -> Chips {
+        (self.stack - self.call_amount()).min(self.opponent_stack)
+    }
 }
 
 impl BetSize {
-    /// Calculate the actual bet amount given pot size and stack.
-    pub fn resolve(self, pot: f64, stack: f64) -> f64 {
+    /// The player's total street commitment after betting or raising this
+    /// size, clamped between the minimum bet or raise and all-in. `None`
+    /// when the size does not apply, including geometric sizing into an
+    /// empty pot.
+    pub fn resolve(self, ctx: &BetContext) -> Option<Chips> {
+        let pot = ctx.pot_after_call();
+        let total = match self {
+            BetSize::PotFraction(frac) => ctx.facing.saturating_add(pot.mul_f64(frac)),
+            BetSize::Absolute(amt) => ctx.facing.saturating_add(amt),
+            BetSize::BigBlinds(bb) => ctx.facing.saturating_add(ctx.big_blind.mul_f64(bb)),
+            BetSize::RaiseMultiple(x) => {
+                if ctx.facing.is_positive() {
+                    ctx.facing.mul_f64(x)
+                } else {
+                    ctx.big_blind.mul_f64(x)
+                }
+            }
+            BetSize::Geometric(streets) => {
+                if !pot.is_positive() {
+                    return None;
+                }
+                let behind = ctx.effective_behind().ratio(pot);
+                let frac = ((1.0 + 2.0 * behind).powf(1.0 / streets.max(1) as f64) - 1.0) / 2.0;
+                ctx.facing.saturating_add(pot.mul_f64(frac))
+            }
+            BetSize::AllIn => ctx.committed + ctx.stack,
+            BetSize::AllInBelowSpr(spr) => {
+                if ctx.effective_behind().ratio(pot) >= spr {
+                    return None;
+                }
+                ctx.committed + ctx.stack
+            }
+        };
+        let min = ctx.facing + ctx.last_raise.max(ctx.big_blind);
+        Some(total.max(min).min(ctx.committed + ctx.stack))
+    }
+
+    /// Whether the size is positive, well-formed and no larger than
+    /// `MAX_SIZE_FACTOR`.
+    pub fn is_valid(self) -> bool {
         match self {
-            BetSize::PotFraction(frac) => (pot * frac).min(stack),
-            BetSize::Absolute(amt) => amt.min(stack),
-            BetSize::AllIn => stack,
+            BetSize::PotFraction(f) | BetSize::BigBlinds(f) | BetSize::AllInBelowSpr(f) => {
+                f > 0.0 && f <= MAX_SIZE_FACTOR
+            }
+            BetSize::RaiseMultiple(x) => x > 1.0 && x <= MAX_SIZE_FACTOR,
+            BetSize::Absolute(a) => a.is_positive(),
+            BetSize::Geometric(n) => n > 0,
+            BetSize::AllIn => true,
         }
     }
+
+    /// Parse a comma-separated list such as `"33%, 75%, 2.5x, a, g2"`.
+    pub fn parse_list(s: &str) -> Result<Vec<BetSize>, CoreError> {
+        let mut sizes = Vec::new();
+        let mut offset = 0;
+        for raw in s.split(',') {
+            let lead = raw.len() - raw.trim_start().len();
+            let token = raw.trim();
+            if !token.is_empty() {
+                let size = parse_size(token).map_err(|reason| CoreError::InvalidBetSize {
+                    token: token.to_string(),
+                    offset: offset + lead,
+                    reason,
+                })?;
+                sizes.push(size);
+            }
+            offset += raw.len() + 1;
+        }
+        Ok(sizes)
+    }
+}
+
+impl FromStr for BetSize {
+    type Err = CoreError;
+
+    /// Parse one size: `75%` of the pot, `2.5x` the facing bet, `10bb`,
+    /// `g2` geometric over two streets, `a` all-in, `a<0.3` all-in when the
+    /// SPR is below 0.3, or a bare chip amount like `25`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let token = s.trim();
+        parse_size(token).map_err(|reason| CoreError::InvalidBetSize {
+            token: token.to_string(),
+            offset: 0,
+            reason,
+        })
+    }
+}
+
+fn parse_size(token: &str) -> Result<BetSize, String> {
+    let lower = token.to_ascii_lowercase();
+    let number = |s: &str| -> Result<f64, String> {
+        s.trim()
+            .parse::<f64>()
+            .map_err(|_| format!("'{}' is not a number", s.trim()))
+    };
+
+    let size = if matches!(lower.as_str(), "a" | "allin" | "all-in") {
+        BetSize::AllIn
+    } else if let Some(spr) = lower.strip_prefix("a<") {
+        BetSize::AllInBelowSpr(number(spr)?)
+    } else if let Some(pct) = lower.strip_suffix('%') {
+        BetSize::PotFraction(number(pct)? / 100.0)
+    } else if let Some(bb) = lower.strip_suffix("bb") {
+        BetSize::BigBlinds(number(bb)?)
+    } else if let Some(x) = lower.strip_suffix('x') {
+        BetSize::RaiseMultiple(number(x)?)
+    } else if let Some(n) = lower.strip_prefix('g') {
+        let streets = n
+            .parse::<u32>()
+            .map_err(|_| format!("'{n}' is not a street count"))?;
+        BetSize::Geometric(streets)
+    } else {
+        let amount = number(&lower)?;
+        if !amount.is_finite() {
+            return Err("amount must be finite".to_string());
+        }
+        BetSize::Absolute(Chips::from_f64(amount))
+    };
+
+    if !size.is_valid() {
+        return Err(match size {
+            BetSize::PotFraction(f)
+            | BetSize::BigBlinds(f)
+            | BetSize::RaiseMultiple(f)
+            | BetSize::AllInBelowSpr(f)
+                if f > MAX_SIZE_FACTOR =>
+            {
+                "size is too large".to_string()
+            }
+            BetSize::RaiseMultiple(_) => "raise multiple must be above 1".to_string(),
+            BetSize::Geometric(_) => "geometric sizing needs at least one street".to_string(),
+            _ => "size must be positive".to_string(),
+        });
+    }
+    Ok(size)
+}
+
+/// Up to two decimals without trailing zeros.
+fn trimmed(value: f64) -> String {
+    let s = format!("{value:.2}");
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
 }
 
 impl fmt::Display for BetSize {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            BetSize::PotFraction(frac) => write!(f, "{:.0}%", frac * 100.0),
-            BetSize::Absolute(amt) => write!(f, "{:.2}", amt),
+            BetSize::PotFraction(frac) => write!(f, "{}%", trimmed(frac * 100.0)),
+            BetSize::Absolute(amt) => write!(f, "{}", amt),
+            BetSize::BigBlinds(bb) => write!(f, "{}bb", trimmed(*bb)),
+            BetSize::RaiseMultiple(x) => write!(f, "{}x", trimmed(*x)),
+            BetSize::Geometric(n) => write!(f, "g{n}"),
             BetSize::AllIn => write!(f, "All-In"),
+            BetSize::AllInBelowSpr(spr) => write!(f, "a<{}", trimmed(*spr)),
         }
     }
 }
@@ -37,22 +232,159 @@ impl fmt::Display for BetSize {
 mod tests {
     use super::*;
 
+    fn c(amount: f64) -> Chips {
+        Chips::from(amount)
+    }
+
+    /// Opening bet into `pot` with `stack` behind for both players.
+    fn open(pot: f64, stack: f64) -> BetContext {
+        BetContext {
+            pot: c(pot),
+            facing: Chips::ZERO,
+            committed: Chips::ZERO,
+            last_raise: Chips::ZERO,
+            stack: c(stack),
+            opponent_stack: c(stack),
+            big_blind: c(1.0),
+        }
+    }
+
     #[test]
     fn test_bet_size_variants() {
+        let ctx = open(100.0, 500.0);
         let half_pot = BetSize::PotFraction(0.5);
-        assert_eq!(half_pot.resolve(100.0, 500.0), 50.0);
+        assert_eq!(half_pot.resolve(&ctx), Some(c(50.0)));
 
         let pot = BetSize::PotFraction(1.0);
-        assert_eq!(pot.resolve(100.0, 500.0), 100.0);
+        assert_eq!(pot.resolve(&ctx), Some(c(100.0)));
 
         // Capped at stack
         let two_pot = BetSize::PotFraction(2.0);
-        assert_eq!(two_pot.resolve(300.0, 500.0), 500.0);
+        assert_eq!(two_pot.resolve(&open(300.0, 500.0)), Some(c(500.0)));
 
-        let absolute = BetSize::Absolute(75.0);
-        assert_eq!(absolute.resolve(100.0, 500.0), 75.0);
+        let absolute = BetSize::Absolute(c(75.0));
+        assert_eq!(absolute.resolve(&ctx), Some(c(75.0)));
 
         let all_in = BetSize::AllIn;
-        assert_eq!(all_in.resolve(100.0, 500.0), 500.0);
+        assert_eq!(all_in.resolve(&ctx), Some(c(500.0)));
+
+        // Pot fractions round to the nearest hundredth
+        let third = BetSize::PotFraction(1.0 / 3.0);
+        assert_eq!(third.resolve(&ctx), Some(c(33.33)));
+        assert_eq!(third.resolve(&open(99.0, 500.0)), Some(c(33.0)));
+
+        assert_eq!(BetSize::BigBlinds(2.5).resolve(&ctx), Some(c(2.5)));
+        // Never below the big blind
+        assert_eq!(BetSize::PotFraction(0.001).resolve(&ctx), Some(c(1.0)));
+    }
+
+    #[test]
+    fn test_resolve_against_a_bet() {
+        // Facing a 30 bet into 100 with 20 already in from an earlier bet
+        let ctx = BetContext {
+            pot: c(150.0),
+            facing: c(30.0),
+            committed: c(20.0),
+            last_raise: c(10.0),
+            stack: c(200.0),
+            opponent_stack: c(300.0),
+            big_blind: c(1.0),
+        };
+        // Pot after calling 10 is 160: a pot raise goes to 30 + 160
+        assert_eq!(BetSize::PotFraction(1.0).resolve(&ctx), Some(c(190.0)));
+        assert_eq!(BetSize::RaiseMultiple(2.5).resolve(&ctx), Some(c(75.0)));
+        // Min-raise: 30 + last raise of 10
+        assert_eq!(BetSize::Absolute(c(2.0)).resolve(&ctx), Some(c(40.0)));
+        assert_eq!(BetSize::AllIn.resolve(&ctx), Some(c(220.0)));
+
+        // Preflop open with no bet to face uses the big blind
+        let preflop = open(1.5, 100.0);
+        assert_eq!(BetSize::RaiseMultiple(2.5).resolve(&preflop), Some(c(2.5)));
+    }
+
+    #[test]
+    fn test_geometric_and_spr_all_in() {
+        // Pot 100, 400 behind: two pot-sized bets get exactly all-in
+        let ctx = open(100.0, 400.0);
+        assert_eq!(BetSize::Geometric(2).resolve(&ctx), Some(c(100.0)));
+        assert_eq!(BetSize::Geometric(1).resolve(&ctx), Some(c(400.0)));
+        let deeper_hero = BetContext {
+            stack: c(1000.0),
+            ..ctx
+        };
+        // Geometric sizing uses the effective stack
+        assert_eq!(BetSize::Geometric(2).resolve(&deeper_hero), Some(c(100.0)));
+
+        assert_eq!(BetSize::AllInBelowSpr(0.3).resolve(&ctx), None);
+        assert_eq!(
+            BetSize::AllInBelowSpr(0.3).resolve(&open(100.0, 25.0)),
+            Some(c(25.0))
+        );
+        // No pot to grow geometrically
+        assert_eq!(BetSize::Geometric(2).resolve(&open(0.0, 400.0)), None);
+    }
+
+    #[test]
+    fn test_huge_sizes() {
+        assert!("1e300%".parse::<BetSize>().is_err());
+        match "1e7x".parse::<BetSize>() {
+            Err(CoreError::InvalidBetSize { reason, .. }) => {
+                assert_eq!(reason, "size is too large")
+            }
+            other => panic!("expected InvalidBetSize, got {other:?}"),
+        }
+        assert!(BetSize::parse_list("50%, 1e300bb").is_err());
+        assert!(!BetSize::PotFraction(f64::INFINITY).is_valid());
+        assert!(!BetSize::PotFraction(f64::NAN).is_valid());
+
+        // Sizes that saturate `Chips` still clamp to all-in
+        let ctx = BetContext {
+            facing: c(30.0),
+            ..open(100.0, 500.0)
+        };
+        assert_eq!(BetSize::PotFraction(1e300).resolve(&ctx), Some(c(500.0)));
+        let huge = BetSize::Absolute(Chips::from_f64(1e300));
+        assert_eq!(huge.resolve(&ctx), Some(c(500.0)));
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let sizes = BetSize::parse_list("33%, 75%, 2.5x, 150%, a, g2").expect("valid");
+        assert_eq!(
+            sizes,
+            vec![
+                BetSize::PotFraction(0.33),
+                BetSize::PotFraction(0.75),
+                BetSize::RaiseMultiple(2.5),
+                BetSize::PotFraction(1.5),
+                BetSize::AllIn,
+                BetSize::Geometric(2),
+            ]
+        );
+        let sizes = BetSize::parse_list("10bb,A<0.3, 25").expect("valid");
+        assert_eq!(
+            sizes,
+            vec![
+                BetSize::BigBlinds(10.0),
+                BetSize::AllInBelowSpr(0.3),
+                BetSize::Absolute(c(25.0)),
+            ]
+        );
+        for size in &sizes {
+            assert_eq!(size.to_string().parse::<BetSize>().ok(), Some(*size));
+        }
+        assert_eq!(BetSize::PotFraction(0.33).to_string(), "33%");
+        assert_eq!(BetSize::AllIn.to_string(), "All-In");
+
+        match BetSize::parse_list("50%, 1x, a") {
+            Err(CoreError::InvalidBetSize { token, offset, .. }) => {
+                assert_eq!(token, "1x");
+                assert_eq!(offset, 5);
+            }
+            other => panic!("expected InvalidBetSize, got {other:?}"),
+        }
+        assert!("g0".parse::<BetSize>().is_err());
+        assert!("-5%".parse::<BetSize>().is_err());
+        assert!("pot".parse::<BetSize>().is_err());
     }
 }
@@ -0,0 +1,253 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// A chip amount in fixed point: an integer count of hundredths of the
+/// table's unit (big blinds for cash games, chips for tournaments).
+///
+/// The unit is chosen by the caller, but the hundredths are fixed: `SCALE`
+/// is a constant so that `Chips` stays a plain `i64` with const
+/// constructors. Rooms whose smallest chip is coarser than a hundredth set
+/// it through `OddChip::unit` when splitting pots.
+///
+/// Serializes as a plain JSON number in whole units, so `2.5` round-trips
+/// as 2.5 big blinds. Values read from floats are rounded to the nearest
+/// hundredth, and out-of-range ones clamp to the representable range.
+///
+/// The arithmetic operators saturate instead of overflowing, so a clamped
+/// amount from hostile input can't panic a sum in debug builds or wrap it
+/// in release.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Chips(i64);
+
+impl Chips {
+    /// Hundredths per whole unit.
+    pub const SCALE: i64 = 100;
+    pub const ZERO: Chips = Chips(0);
+    /// Smallest representable amount.
+    pub const CENT: Chips = Chips(1);
+
+    /// From a count of hundredths.
+    pub const fn from_cents(cents: i64) -> Self {
+        Chips(cents)
+    }
+
+    /// From whole units.
+    pub const fn from_units(units: i64) -> Self {
+        Chips(units * Self::SCALE)
+    }
+
+    /// From a floating-point amount of units, rounded to the nearest
+    /// hundredth. Values beyond the `i64` range of hundredths clamp to it;
+    /// non-finite values become zero.
+    pub fn from_f64(amount: f64) -> Self {
+        if amount.is_finite() {
+            Chips((amount * Self::SCALE as f64).round() as i64)
+        } else {
+            Chips::ZERO
+        }
+    }
+
+    pub const fn cents(self) -> i64 {
+        self.0
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    /// `+` usable in const contexts; like the operator, it stops at the
+    /// largest representable amount instead of overflowing.
+    pub const fn saturating_add(self, rhs: Chips) -> Self {
+        Chips(self.0.saturating_add(rhs.0))
+    }
+
+    /// Scale by a ratio such as a pot fraction, rounding to the nearest
+    /// hundredth.
+    pub fn mul_f64(self, ratio: f64) -> Self {
+        Chips::from_f64(self.to_f64() * ratio)
+    }
+
+    /// Ratio of two amounts, e.g. a stack-to-pot ratio. Infinite (with the
+    /// sign of `self`) when `other` is zero, and zero when both are.
+    pub fn ratio(self, other: Chips) -> f64 {
+        if self.0 == 0 {
+            return 0.0;
+        }
+        self.0 as f64 / other.0 as f64
+    }
+}
+
+impl Add for Chips {
+    type Output = Chips;
+    fn add(self, rhs: Chips) -> Chips {
+        Chips(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for Chips {
+    type Output = Chips;
+    fn sub(self, rhs: Chips) -> Chips {
+        Chips(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Neg for Chips {
+    type Output = Chips;
+    fn neg(self) -> Chips {
+        Chips(self.0.saturating_neg())
+    }
+}
+
+impl AddAssign for Chips {
+    fn add_assign(&mut self, rhs: Chips) {
+        self.0 = self.0.saturating_add(rhs.0);
+    }
+}
+
+impl SubAssign for Chips {
+    fn sub_assign(&mut self, rhs: Chips) {
+        self.0 = self.0.saturating_sub(rhs.0);
+    }
+}
+
+impl Mul<i64> for Chips {
+    type Output = Chips;
+    fn mul(self, rhs: i64) -> Chips {
+        Chips(self.0.saturating_mul(rhs))
+    }
+}
+
+/// Integer division, rounding toward zero.
+impl Div<i64> for Chips {
+    type Output = Chips;
+    fn div(self, rhs: i64) -> Chips {
+        Chips(self.0 / rhs)
+    }
+}
+
+impl Sum for Chips {
+    fn sum<I: Iterator<Item = Chips>>(iter: I) -> Chips {
+        iter.fold(Chips::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Chips> for Chips {
+    fn sum<I: Iterator<Item = &'a Chips>>(iter: I) -> Chips {
+        iter.copied().sum()
+    }
+}
+
+impl From<f64> for Chips {
+    fn from(amount: f64) -> Self {
+        Chips::from_f64(amount)
+    }
+}
+
+impl From<Chips> for f64 {
+    fn from(chips: Chips) -> f64 {
+        chips.to_f64()
+    }
+}
+
+impl fmt::Display for Chips {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let scale = Self::SCALE as u64;
+        write!(f, "{sign}{}.{:02}", abs / scale, abs % scale)
+    }
+}
+
+impl Serialize for Chips {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Chips {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let amount = f64::deserialize(deserializer)?;
+        if !amount.is_finite() {
+            return Err(serde::de::Error::custom("chip amount must be finite"));
+        }
+        Ok(Chips::from_f64(amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chips_arithmetic_is_exact() {
+        // Three thirds of a pot sum back to the pot
+        let pot = Chips::from_units(99);
+        let third = pot.mul_f64(1.0 / 3.0);
+        assert_eq!(third, Chips::from_units(33));
+        assert_eq!(third * 3, pot);
+        assert_eq!(Chips::from(0.1) + Chips::from(0.2), Chips::from(0.3));
+        assert_eq!(Chips::from(2.5) - Chips::from(3.0), -Chips::from(0.5));
+        assert_eq!(Chips::from_units(10) / 3, Chips::from_cents(333));
+        let total: Chips = [Chips::from(1.25), Chips::from(0.75)].iter().sum();
+        assert_eq!(total, Chips::from_units(2));
+        assert_eq!(Chips::from_units(50).ratio(Chips::from_units(100)), 0.5);
+        assert_eq!(Chips::from_units(50).ratio(Chips::ZERO), f64::INFINITY);
+        assert_eq!(
+            (-Chips::from_units(5)).ratio(Chips::ZERO),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(Chips::ZERO.ratio(Chips::ZERO), 0.0);
+        assert_eq!(Chips::from(f64::NAN), Chips::ZERO);
+    }
+
+    #[test]
+    fn test_chips_display() {
+        assert_eq!(Chips::from_units(50).to_string(), "50.00");
+        assert_eq!(Chips::from(2.5).to_string(), "2.50");
+        assert_eq!(Chips::from_cents(7).to_string(), "0.07");
+        assert_eq!((-Chips::from(1.05)).to_string(), "-1.05");
+    }
+
+    #[test]
+    fn test_chips_serde_as_numbers() {
+        let json = serde_json::to_string(&Chips::from(2.5)).expect("serialize");
+        assert_eq!(json, "2.5");
+        let back: Chips = serde_json::from_str("2.5").expect("deserialize");
+        assert_eq!(back, Chips::from_cents(250));
+        let int: Chips = serde_json::from_str("100").expect("integer JSON");
+        assert_eq!(int, Chips::from_units(100));
+        let amounts: Vec<Chips> = serde_json::from_str("[0.333, 1e2]").expect("deserialize");
+        assert_eq!(amounts, vec![Chips::from_cents(33), Chips::from_units(100)]);
+    }
+
+    #[test]
+    fn test_huge_amounts_saturate() {
+        let huge: Vec<Chips> = serde_json::from_str("[1e300, 1e300, -1e300]").expect("deserialize");
+        let max = Chips::from_cents(i64::MAX);
+        let min = Chips::from_cents(i64::MIN);
+        assert_eq!(huge[0], max);
+        assert_eq!(huge[2], min);
+        assert_eq!(huge[..2].iter().sum::<Chips>(), max);
+        assert_eq!(huge[0] + huge[1], max);
+        assert_eq!(huge[2] - huge[0], min);
+        assert_eq!(huge[0] * 3, max);
+        assert_eq!(-min, max);
+        let mut stack = huge[0];
+        stack += Chips::from_units(1);
+        stack -= min;
+        assert_eq!(stack, max);
+        // Everything still sums to something finite
+        let total: Chips = huge.iter().sum();
+        assert_eq!(total, Chips::from_cents(-1));
+    }
+}
@@ -0,0 +1,383 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::Board;
+use crate::card::{Card, Rank, Suit};
+use crate::error::CoreError;
+use crate::hand::{combo_count, Hand, NUM_COMBOS};
+use crate::range::{parse_notation, Range, RangeEntry};
+
+/// A range holding one weight (0.0 to 1.0) per specific hole-card combo.
+/// Index is `Hand::combo_index` (0..1325).
+///
+/// Unlike `Range`, this can express suit-specific holdings such as
+/// "AsKs only" and exact card removal against a board.
+///
+/// Deserialization goes through [`ComboRange::from_weights`], so a payload
+/// with the wrong length or out-of-range weights is rejected.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawComboRange")]
+pub struct ComboRange {
+    weights: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct RawComboRange {
+    weights: Vec<f32>,
+}
+
+impl TryFrom<RawComboRange> for ComboRange {
+    type Error = CoreError;
+
+    fn try_from(raw: RawComboRange) -> Result<Self, Self::Error> {
+        ComboRange::from_weights(raw.weights)
+    }
+}
+
+/// Weight of one specific combo inside a hand group, for suit-level editing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComboWeight {
+    pub hand: Hand,
+    /// Card notation like "AsKh".
+    pub label: String,
+    pub weight: f32,
+}
+
+impl ComboRange {
+    /// Create an empty range (all weights 0).
+    pub fn empty() -> Self {
+        ComboRange {
+            weights: vec![0.0; NUM_COMBOS],
+        }
+    }
+
+    /// Create a full range (all weights 1.0).
+    pub fn full() -> Self {
+        ComboRange {
+            weights: vec![1.0; NUM_COMBOS],
+        }
+    }
+
+    /// Build from 1326 weights indexed by `Hand::combo_index`.
+    /// Every weight must lie in 0.0..=1.0.
+    pub fn from_weights(weights: Vec<f32>) -> Result<Self, CoreError> {
+        if weights.len() != NUM_COMBOS {
+            return Err(CoreError::InvalidComboWeights(format!(
+                "expected {NUM_COMBOS} weights, got {}",
+                weights.len()
+            )));
+        }
+        if let Some(idx) = weights.iter().position(|w| !(0.0..=1.0).contains(w)) {
+            return Err(CoreError::InvalidComboWeights(format!(
+                "weight {} at index {idx} is outside 0..1",
+                weights[idx]
+            )));
+        }
+        Ok(ComboRange { weights })
+    }
+
+    /// Expand a 169-group range: every combo takes its group's weight.
+    pub fn from_range(range: &Range) -> Self {
+        let mut combos = Self::empty();
+        for (idx, w) in combos.weights.iter_mut().enumerate() {
+            if let Some(hand) = Hand::from_combo_index(idx) {
+                *w = range.weight(hand.canonical_index());
+            }
+        }
+        combos
+    }
+
+    /// Collapse to 169 groups. Each group gets the mean weight of its combos,
+    /// so combo counts are preserved.
+    pub fn to_range(&self) -> Range {
+        let mut sums = [0.0f32; 169];
+        for (hand, w) in self.iter() {
+            sums[hand.canonical_index()] += w;
+        }
+        let mut range = Range::empty();
+        for (idx, sum) in sums.iter().enumerate() {
+            range.set_weight(idx, sum / combo_count(idx / 13, idx % 13) as f32);
+        }
+        range
+    }
+
+    /// Get weight for a specific combo.
+    pub fn weight(&self, hand: Hand) -> f32 {
+        self.weights[hand.combo_index()]
+    }
+
+    /// Set weight for a specific combo.
+    pub fn set_weight(&mut self, hand: Hand, weight: f32) {
+        self.weights[hand.combo_index()] = weight.clamp(0.0, 1.0);
+    }
+
+    /// Get the raw weights slice, indexed by `Hand::combo_index`.
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Get mutable weights slice.
+    pub fn weights_mut(&mut self) -> &mut [f32] {
+        &mut self.weights
+    }
+
+    /// Iterate over combos with non-zero weight.
+    pub fn iter(&self) -> impl Iterator<Item = (Hand, f32)> + '_ {
+        self.weights.iter().enumerate().filter_map(|(idx, &w)| {
+            if w > 0.0 {
+                Hand::from_combo_index(idx).map(|hand| (hand, w))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The specific combos of a canonical hand group (0..168) with their weights.
+    /// Ordered by suit spades, hearts, diamonds, clubs, matching the SuitExpander.
+    pub fn group_combos(&self, index: usize) -> Vec<ComboWeight> {
+        group_hands(index)
+            .into_iter()
+            .map(|hand| ComboWeight {
+                hand,
+                label: hand.to_string(),
+                weight: self.weight(hand),
+            })
+            .collect()
+    }
+
+    /// Set every combo of a canonical hand group to the same weight.
+    pub fn set_group_weight(&mut self, index: usize, weight: f32) {
+        for hand in group_hands(index) {
+            self.set_weight(hand, weight);
+        }
+    }
+
+    /// Zero out every combo that shares a card with `dead_mask`.
+    pub fn remove_dead_cards(&mut self, dead_mask: u64) {
+        for (idx, w) in self.weights.iter_mut().enumerate() {
+            if let Some(hand) = Hand::from_combo_index(idx) {
+                if hand.mask() & dead_mask != 0 {
+                    *w = 0.0;
+                }
+            }
+        }
+    }
+
+    /// Zero out every combo blocked by the board.
+    pub fn remove_board(&mut self, board: &Board) {
+        self.remove_dead_cards(board.mask());
+    }
+
+    /// Weighted number of combos in this range.
+    pub fn combo_count(&self) -> f32 {
+        self.weights.iter().sum()
+    }
+
+    /// Fraction of all 1326 combos that are in this range.
+    pub fn density(&self) -> f32 {
+        self.combo_count() / NUM_COMBOS as f32
+    }
+}
+
+/// All specific combos of a canonical hand group, in SuitExpander order.
+fn group_hands(index: usize) -> Vec<Hand> {
+    if index >= 169 {
+        return Vec::new();
+    }
+    let (row, col) = (index / 13, index % 13);
+    let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+    let (r1, r2) = if row <= col {
+        (Rank::ALL[12 - row], Rank::ALL[12 - col])
+    } else {
+        (Rank::ALL[12 - col], Rank::ALL[12 - row])
+    };
+
+    let mut hands = Vec::with_capacity(12);
+    for (i, &s1) in suits.iter().enumerate() {
+        for (j, &s2) in suits.iter().enumerate() {
+            let keep = if row == col {
+                i < j
+            } else if row < col {
+                i == j
+            } else {
+                i != j
+            };
+            if keep {
+                hands.push(Hand::new(Card::new(r1, s1), Card::new(r2, s2)));
+            }
+        }
+    }
+    hands
+}
+
+impl Default for ComboRange {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl From<&Range> for ComboRange {
+    fn from(range: &Range) -> Self {
+        Self::from_range(range)
+    }
+}
+
+impl FromStr for ComboRange {
+    type Err = CoreError;
+
+    /// Parse the same notation as `Range`. Exact combos like `AhKh` set only
+    /// that combo.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut combos = ComboRange::empty();
+        for (entry, weight) in parse_notation(s)? {
+            match entry {
+                RangeEntry::Groups(indices) => {
+                    for idx in indices {
+                        combos.set_group_weight(idx, weight);
+                    }
+                }
+                RangeEntry::Combo(hand) => combos.set_weight(hand, weight),
+            }
+        }
+        Ok(combos)
+    }
+}
+
+impl fmt::Display for ComboRange {
+    /// Uniform groups use `Range` notation; the rest list their combos.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut uniform = Range::empty();
+        let mut exact = Vec::new();
+        for idx in 0..169 {
+            let combos = self.group_combos(idx);
+            let first = combos[0].weight;
+            if combos.iter().all(|c| c.weight == first) {
+                uniform.set_weight(idx, first);
+                continue;
+            }
+            for c in combos.into_iter().filter(|c| c.weight > 0.0) {
+                if c.weight == 1.0 {
+                    exact.push(c.label);
+                } else {
+                    exact.push(format!("{}:{}", c.label, c.weight));
+                }
+            }
+        }
+
+        let mut tokens = vec![uniform.to_string()];
+        tokens.retain(|t| !t.is_empty());
+        tokens.extend(exact);
+        write!(f, "{}", tokens.join(","))
+    }
+}
+
+impl fmt::Debug for ComboRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ComboRange({:.1}% / {:.0} combos)",
+            self.density() * 100.0,
+            self.combo_count()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(s: &str) -> Card {
+        Card::from_str_notation(s).expect("valid card")
+    }
+
+    fn hand(a: &str, b: &str) -> Hand {
+        Hand::new(card(a), card(b))
+    }
+
+    #[test]
+    fn test_range_round_trip() {
+        let range: Range = "22+,A2s+,KTo+,T9s:0.5".parse().expect("valid");
+        let combos = ComboRange::from_range(&range);
+        assert_eq!(combos.combo_count(), range.combo_count());
+        assert_eq!(combos.to_range(), range);
+        assert_eq!(ComboRange::full().combo_count(), 1326.0);
+    }
+
+    #[test]
+    fn test_single_combo() {
+        let combos: ComboRange = "AsKs".parse().expect("valid");
+        assert_eq!(combos.combo_count(), 1.0);
+        assert_eq!(combos.weight(hand("As", "Ks")), 1.0);
+        assert_eq!(combos.weight(hand("Ah", "Kh")), 0.0);
+        assert_eq!(combos.to_string(), "AsKs");
+
+        let collapsed = combos.to_range();
+        assert_eq!(collapsed.weight(hand("As", "Ks").canonical_index()), 0.25);
+    }
+
+    #[test]
+    fn test_group_combos_order_and_counts() {
+        let combos = ComboRange::full();
+        let aks = hand("As", "Ks").canonical_index();
+        let ako = hand("As", "Kh").canonical_index();
+        let aa = hand("As", "Ah").canonical_index();
+        assert_eq!(combos.group_combos(aks).len(), 4);
+        assert_eq!(combos.group_combos(ako).len(), 12);
+        assert_eq!(combos.group_combos(aa).len(), 6);
+
+        let labels: Vec<String> = combos
+            .group_combos(aks)
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        assert_eq!(labels, ["AsKs", "AhKh", "AdKd", "AcKc"]);
+        assert!(combos.group_combos(169).is_empty());
+    }
+
+    #[test]
+    fn test_remove_offsuit_combos() {
+        // AKo without the combos holding the As
+        let mut combos: ComboRange = "AKo".parse().expect("valid");
+        for c in combos.group_combos(hand("As", "Kh").canonical_index()) {
+            if c.hand.mask() & card("As").mask() != 0 {
+                combos.set_weight(c.hand, 0.0);
+            }
+        }
+        assert_eq!(combos.combo_count(), 9.0);
+        assert!(combos.to_string().split(',').all(|t| !t.starts_with("As")));
+        assert_eq!(
+            combos.to_string().parse::<ComboRange>().expect("valid"),
+            combos
+        );
+    }
+
+    #[test]
+    fn test_board_card_removal() {
+        let board = Board::from_cards(&[card("Ah"), card("Kd"), card("7c")]).expect("valid");
+        let mut combos: ComboRange = "AA,AKs,72o".parse().expect("valid");
+        combos.remove_board(&board);
+        // AA: 3 of 6 remain; AKs: 2 of 4 remain; 72o: 3 live sevens x 4 deuces, minus 3 suited
+        assert_eq!(combos.combo_count(), 3.0 + 2.0 + 9.0);
+        assert_eq!(combos.weight(hand("Ah", "As")), 0.0);
+
+        let mut full = ComboRange::full();
+        full.remove_board(&board);
+        assert_eq!(full.combo_count(), (49.0 * 48.0) / 2.0);
+    }
+
+    #[test]
+    fn test_deserialize_validates_weights() {
+        let combos: ComboRange = "AKs,AsKs:0.5".parse().expect("valid");
+        let json = serde_json::to_string(&combos).expect("serialize");
+        let back: ComboRange = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(back, combos);
+
+        assert!(serde_json::from_str::<ComboRange>(r#"{"weights":[0.5,1.0]}"#).is_err());
+        let mut weights = vec![0.0f32; NUM_COMBOS];
+        weights[7] = 50.0;
+        assert!(ComboRange::from_weights(weights.clone()).is_err());
+        let json = serde_json::json!({ "weights": weights }).to_string();
+        assert!(serde_json::from_str::<ComboRange>(&json).is_err());
+    }
+}
@@ -27,4 +27,43 @@ pub enum CoreError {
 
     #[error("board is full (5 cards)")]
     BoardFull,
+
+    #[error("invalid range token '{token}' at offset {offset}: {reason}")]
+    InvalidRangeToken {
+        token: String,
+        offset: usize,
+        reason: String,
+    },
+
+    #[error("invalid bet size '{token}' at offset {offset}: {reason}")]
+    InvalidBetSize {
+        token: String,
+        offset: usize,
+        reason: String,
+    },
+
+    #[error("invalid combo weights: {0}")]
+    InvalidComboWeights(String),
+
+    #[error("invalid tree config: {0}")]
+    InvalidTreeConfig(String),
+
+    #[error("invalid pot: {0}")]
+    InvalidPot(String),
+
+    #[error("invalid game state: {0}")]
+    InvalidGameState(String),
+
+    #[error("illegal action '{action}' for seat {seat}: {reason}")]
+    IllegalAction {
+        seat: usize,
+        action: String,
+        reason: String,
+    },
+
+    #[error("hand history line {line}: {message}")]
+    InvalidHandHistory { line: usize, message: String },
+
+    #[error("invalid OHH: {0}")]
+    InvalidOhh(String),
 }
@@ -0,0 +1,851 @@
+use serde::{Deserialize, Serialize};
+
+use crate::action::Action;
+use crate::bet_size::{BetContext, BetSize};
+use crate::board::{Board, Street};
+use crate::card::Card;
+use crate::chips::Chips;
+use crate::deck::Deck;
+use crate::error::CoreError;
+use crate::pot::{OddChip, Pots, Settlement};
+
+/// Forced bets posted at the start of every hand.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Blinds {
+    pub small: Chips,
+    pub big: Chips,
+    /// Paid by every seat into the pot before the blinds.
+    pub ante: Chips,
+}
+
+/// One player's chips during a hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Seat {
+    /// Chips behind.
+    pub stack: Chips,
+    /// Chips committed on the current street.
+    pub street_bet: Chips,
+    /// Chips put into the pot this hand, including antes and `street_bet`.
+    pub invested: Chips,
+    pub folded: bool,
+}
+
+impl Seat {
+    pub fn is_all_in(&self) -> bool {
+        !self.folded && self.stack.is_zero()
+    }
+
+    /// Still in the hand with chips left to bet.
+    pub fn can_act(&self) -> bool {
+        !self.folded && self.stack.is_positive()
+    }
+}
+
+/// Where the hand is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Phase {
+    /// A seat is to act.
+    Betting,
+    /// The betting round is over and the next street must be dealt.
+    Dealing,
+    /// Betting is over with the board complete; settle the pots.
+    Showdown,
+    /// Everyone but one seat folded.
+    Finished,
+}
+
+/// What the seat to act may do.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegalActions {
+    pub seat: usize,
+    pub can_fold: bool,
+    pub can_check: bool,
+    /// The street's bet to match. Zero when nothing has been bet, so a bet
+    /// rather than a raise opens the action; preflop the big blind counts.
+    #[serde(default)]
+    pub facing: Chips,
+    /// Chips added by calling; less than the bet when calling all-in.
+    pub call: Option<Chips>,
+    /// Smallest and largest total street commitment for a bet or raise. The
+    /// maximum is all-in, and the minimum drops to it when the stack can't
+    /// cover a full raise.
+    pub raise: Option<(Chips, Chips)>,
+}
+
+impl LegalActions {
+    /// One action per option: fold, check or call, the minimum bet or raise,
+    /// and all-in.
+    pub fn actions(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        if self.can_fold {
+            actions.push(Action::Fold);
+        }
+        if self.can_check {
+            actions.push(Action::Check);
+        }
+        if self.call.is_some() {
+            actions.push(Action::Call);
+        }
+        if let Some((min, max)) = self.raise {
+            if min < max {
+                actions.push(if self.facing.is_zero() {
+                    Action::Bet(min)
+                } else {
+                    Action::Raise(min)
+                });
+            }
+            actions.push(Action::AllIn(max));
+        }
+        actions
+    }
+}
+
+/// No-limit hold'em hand from the blinds to showdown.
+///
+/// Bet, raise and all-in amounts are the seat's total commitment on the
+/// street, as in the game tree. A seat that has already acted may only
+/// re-raise once the bet facing it has grown by at least a full raise, so
+/// an incomplete all-in raise does not reopen the betting on its own.
+#[derive(Debug, Clone)]
+pub struct GameState {
+    blinds: Blinds,
+    button: usize,
+    seats: Vec<Seat>,
+    street: Street,
+    board: Board,
+    deck: Deck,
+    phase: Phase,
+    to_act: usize,
+    /// Largest street commitment so far.
+    current_bet: Chips,
+    /// Size of the last full bet or raise this street: the minimum increment.
+    last_raise: Chips,
+    /// `current_bet` right after each seat last acted this street.
+    acted_at: Vec<Option<Chips>>,
+    needs_action: Vec<bool>,
+    history: Vec<(Street, usize, Action)>,
+}
+
+impl GameState {
+    /// Start a hand: post antes and blinds and put the first seat to act.
+    /// Heads-up the button posts the small blind. Board cards are dealt
+    /// from `deck` as it stands, so shuffle it and remove hole cards first.
+    pub fn new(
+        blinds: Blinds,
+        stacks: &[Chips],
+        button: usize,
+        deck: Deck,
+    ) -> Result<Self, CoreError> {
+        if stacks.len() < 2 {
+            return Err(CoreError::InvalidGameState(format!(
+                "need at least 2 seats, got {}",
+                stacks.len()
+            )));
+        }
+        if stacks.iter().any(|s| !s.is_positive()) {
+            return Err(CoreError::InvalidGameState(
+                "stacks must be positive".to_string(),
+            ));
+        }
+        if button >= stacks.len() {
+            return Err(CoreError::InvalidGameState(format!(
+                "button seat {button} out of range for {} seats",
+                stacks.len()
+            )));
+        }
+        if blinds.small < Chips::ZERO || blinds.ante < Chips::ZERO || !blinds.big.is_positive() {
+            return Err(CoreError::InvalidGameState(
+                "blinds must be non-negative with a positive big blind".to_string(),
+            ));
+        }
+
+        let n = stacks.len();
+        let mut state = GameState {
+            blinds,
+            button,
+            seats: stacks
+                .iter()
+                .map(|&stack| Seat {
+                    stack,
+                    street_bet: Chips::ZERO,
+                    invested: Chips::ZERO,
+                    folded: false,
+                })
+                .collect(),
+            street: Street::Preflop,
+            board: Board::new(),
+            deck,
+            phase: Phase::Betting,
+            to_act: 0,
+            current_bet: blinds.big,
+            last_raise: blinds.big,
+            acted_at: vec![None; n],
+            needs_action: vec![false; n],
+            history: Vec::new(),
+        };
+
+        for seat in &mut state.seats {
+            let ante = blinds.ante.min(seat.stack);
+            seat.stack -= ante;
+            seat.invested += ante;
+        }
+        let small = if n == 2 { button } else { (button + 1) % n };
+        let big = (small + 1) % n;
+        state.post(small, blinds.small);
+        state.post(big, blinds.big);
+
+        for (needs, seat) in state.needs_action.iter_mut().zip(&state.seats) {
+            *needs = seat.can_act();
+        }
+        state.start_round((big + 1) % n);
+        Ok(state)
+    }
+
+    pub fn blinds(&self) -> Blinds {
+        self.blinds
+    }
+
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    pub fn seats(&self) -> &[Seat] {
+        &self.seats
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Seat to act, while betting.
+    pub fn to_act(&self) -> Option<usize> {
+        (self.phase == Phase::Betting).then_some(self.to_act)
+    }
+
+    /// Largest street commitment so far.
+    pub fn current_bet(&self) -> Chips {
+        self.current_bet
+    }
+
+    /// Every action taken this hand, with its street and seat.
+    pub fn history(&self) -> &[(Street, usize, Action)] {
+        &self.history
+    }
+
+    /// Chips in the middle, including the current street's bets.
+    pub fn pot(&self) -> Chips {
+        self.seats.iter().map(|s| s.invested).sum()
+    }
+
+    /// Main pot and side pots from what each seat has put in.
+    pub fn pots(&self) -> Result<Pots, CoreError> {
+        let invested: Vec<Chips> = self.seats.iter().map(|s| s.invested).collect();
+        let folded: Vec<bool> = self.seats.iter().map(|s| s.folded).collect();
+        Pots::from_contributions(&invested, &folded)
+    }
+
+    /// Award the pots once the hand is over, splitting odd chips of size
+    /// `unit` from the button. `ranks` are as for [`Pots::settle`].
+    pub fn settle(&self, ranks: &[Option<u16>], unit: Chips) -> Result<Settlement, CoreError> {
+        if !matches!(self.phase, Phase::Showdown | Phase::Finished) {
+            return Err(CoreError::InvalidGameState(format!(
+                "cannot settle during {:?}",
+                self.phase
+            )));
+        }
+        self.pots()?.settle(
+            ranks,
+            OddChip {
+                unit,
+                button: self.button,
+            },
+        )
+    }
+
+    /// Options for the seat to act, or `None` outside a betting round.
+    pub fn legal_actions(&self) -> Option<LegalActions> {
+        if self.phase != Phase::Betting {
+            return None;
+        }
+        let seat = self.to_act;
+        let s = &self.seats[seat];
+        let to_call = (self.current_bet - s.street_bet).max(Chips::ZERO);
+        let can_check = to_call.is_zero();
+        let max = s.street_bet + s.stack;
+        let opponents_can_act = (0..self.seats.len()).any(|o| o != seat && self.seats[o].can_act());
+
+        let raise = (self.reopened(seat) && opponents_can_act && max > self.current_bet)
+            .then(|| (self.min_raise_to().min(max), max));
+
+        Some(LegalActions {
+            seat,
+            can_fold: !can_check,
+            can_check,
+            facing: self.current_bet,
+            call: (!can_check).then(|| to_call.min(s.stack)),
+            raise,
+        })
+    }
+
+    /// Sizing context for the seat to act, against the deepest opponent
+    /// still holding chips.
+    pub fn bet_context(&self) -> Option<BetContext> {
+        if self.phase != Phase::Betting {
+            return None;
+        }
+        let seat = self.to_act;
+        let s = &self.seats[seat];
+        let opponent_stack = (0..self.seats.len())
+            .filter(|&o| o != seat && !self.seats[o].folded)
+            .map(|o| self.seats[o].stack)
+            .max()
+            .unwrap_or(Chips::ZERO);
+        Some(BetContext {
+            pot: self.pot(),
+            facing: self.current_bet,
+            committed: s.street_bet,
+            last_raise: self.last_raise,
+            stack: s.stack,
+            opponent_stack,
+            big_blind: self.blinds.big,
+        })
+    }
+
+    /// The bet or raise `size` resolves to for the seat to act, or `None`
+    /// when it doesn't apply or the seat can't bet or raise.
+    pub fn sized_action(&self, size: BetSize) -> Option<Action> {
+        let legal = self.legal_actions()?;
+        let (_, max) = legal.raise?;
+        let total = size.resolve(&self.bet_context()?)?;
+        Some(if total >= max {
+            Action::AllIn(max)
+        } else if legal.facing.is_zero() {
+            Action::Bet(total)
+        } else {
+            Action::Raise(total)
+        })
+    }
+
+    /// Apply `action` for `seat`, who must be the seat to act.
+    pub fn apply(&mut self, seat: usize, action: Action) -> Result<(), CoreError> {
+        let legal = self.legal_actions().ok_or_else(|| {
+            CoreError::InvalidGameState(format!("no betting round in progress ({:?})", self.phase))
+        })?;
+        let illegal = |reason: String| CoreError::IllegalAction {
+            seat,
+            action: action.to_string(),
+            reason,
+        };
+        if seat != legal.seat {
+            return Err(illegal(format!("seat {} is to act", legal.seat)));
+        }
+
+        match action {
+            Action::Fold => {
+                if !legal.can_fold {
+                    return Err(illegal("nothing to call; check instead".to_string()));
+                }
+                self.seats[seat].folded = true;
+            }
+            Action::Check => {
+                if !legal.can_check {
+                    return Err(illegal(format!("facing a bet of {}", self.current_bet)));
+                }
+            }
+            Action::Call => match legal.call {
+                Some(amount) => self.put(seat, amount),
+                None => return Err(illegal("nothing to call; check instead".to_string())),
+            },
+            Action::Bet(total) => {
+                if self.current_bet.is_positive() {
+                    return Err(illegal(format!(
+                        "there is already a bet of {}; raise instead",
+                        self.current_bet
+                    )));
+                }
+                self.raise_to(seat, total, &legal).map_err(illegal)?;
+            }
+            Action::Raise(total) => {
+                if self.current_bet.is_zero() {
+                    return Err(illegal("there is no bet to raise; bet instead".to_string()));
+                }
+                self.raise_to(seat, total, &legal).map_err(illegal)?;
+            }
+            Action::AllIn(total) => {
+                let s = &self.seats[seat];
+                let all_in = s.street_bet + s.stack;
+                if total != all_in {
+                    return Err(illegal(format!("all-in is {all_in}")));
+                }
+                if total <= self.current_bet {
+                    self.put(seat, s.stack);
+                } else {
+                    self.raise_to(seat, total, &legal).map_err(illegal)?;
+                }
+            }
+        }
+
+        self.acted_at[seat] = Some(self.current_bet);
+        self.needs_action[seat] = false;
+        self.history.push((self.street, seat, action));
+
+        if self.seats.iter().filter(|s| !s.folded).count() == 1 {
+            self.phase = Phase::Finished;
+            return Ok(());
+        }
+        match self.next_to_act(seat + 1) {
+            Some(next) => self.to_act = next,
+            None => self.end_round(),
+        }
+        Ok(())
+    }
+
+    /// Deal the next street from the deck.
+    pub fn deal_next_street(&mut self) -> Result<(), CoreError> {
+        let count = self.cards_for_next_street()?;
+        if self.deck.remaining() < count {
+            return Err(CoreError::InvalidGameState(
+                "deck has too few cards left".to_string(),
+            ));
+        }
+        let cards: Vec<Card> = (0..count).filter_map(|_| self.deck.deal()).collect();
+        self.deal_street_cards(&cards)
+    }
+
+    /// Deal specific cards for the next street, e.g. when replaying a hand.
+    /// The deck is not touched.
+    pub fn deal_street_cards(&mut self, cards: &[Card]) -> Result<(), CoreError> {
+        let count = self.cards_for_next_street()?;
+        if cards.len() != count {
+            return Err(CoreError::InvalidGameState(format!(
+                "expected {count} cards for the next street, got {}",
+                cards.len()
+            )));
+        }
+        let mut mask = self.board.mask();
+        for card in cards {
+            if mask & card.mask() != 0 {
+                return Err(CoreError::InvalidGameState(format!(
+                    "{card} is already on the board"
+                )));
+            }
+            mask |= card.mask();
+        }
+        for &card in cards {
+            self.board.add_card(card)?;
+        }
+        self.street = self.board.street();
+
+        for seat in &mut self.seats {
+            seat.street_bet = Chips::ZERO;
+        }
+        self.current_bet = Chips::ZERO;
+        self.last_raise = self.blinds.big;
+        self.acted_at.iter_mut().for_each(|a| *a = None);
+        for (needs, seat) in self.needs_action.iter_mut().zip(&self.seats) {
+            *needs = seat.can_act();
+        }
+        self.phase = Phase::Betting;
+        self.start_round(self.button + 1);
+        Ok(())
+    }
+
+    fn cards_for_next_street(&self) -> Result<usize, CoreError> {
+        if self.phase != Phase::Dealing {
+            return Err(CoreError::InvalidGameState(format!(
+                "cannot deal during {:?}",
+                self.phase
+            )));
+        }
+        Ok(if self.street == Street::Preflop { 3 } else { 1 })
+    }
+
+    fn post(&mut self, seat: usize, amount: Chips) {
+        let amount = amount.min(self.seats[seat].stack);
+        self.put(seat, amount);
+    }
+
+    fn put(&mut self, seat: usize, amount: Chips) {
+        let s = &mut self.seats[seat];
+        s.stack -= amount;
+        s.street_bet += amount;
+        s.invested += amount;
+    }
+
+    fn min_raise_to(&self) -> Chips {
+        if self.current_bet.is_zero() {
+            self.blinds.big
+        } else {
+            self.current_bet + self.last_raise
+        }
+    }
+
+    /// Whether `seat` may raise: it hasn't acted this street, or the bet has
+    /// grown by a full raise since it did.
+    fn reopened(&self, seat: usize) -> bool {
+        match self.acted_at[seat] {
+            None => true,
+            Some(level) => self.current_bet >= level + self.last_raise,
+        }
+    }
+
+    fn raise_to(&mut self, seat: usize, total: Chips, legal: &LegalActions) -> Result<(), String> {
+        let (min, max) = legal.raise.ok_or_else(|| {
+            if !self.reopened(seat) {
+                "an incomplete all-in raise did not reopen the betting".to_string()
+            } else if self.seats[seat].street_bet + self.seats[seat].stack <= self.current_bet {
+                "stack only covers a call".to_string()
+            } else {
+                "every opponent is all-in".to_string()
+            }
+        })?;
+        if total > max {
+            return Err(format!("only {max} is available"));
+        }
+        if total < min {
+            return Err(format!("minimum is {min}"));
+        }
+        let increment = total - self.current_bet;
+        if increment >= self.last_raise {
+            self.last_raise = increment;
+        }
+        self.current_bet = total;
+        let added = total - self.seats[seat].street_bet;
+        self.put(seat, added);
+        for other in 0..self.seats.len() {
+            if other != seat {
+                self.needs_action[other] = self.seats[other].can_act();
+            }
+        }
+        Ok(())
+    }
+
+    /// First seat from `from` onwards, wrapping, that still has to act.
+    /// A lone seat with chips needn't act once it has matched the bet.
+    fn next_to_act(&self, from: usize) -> Option<usize> {
+        let n = self.seats.len();
+        let active = self.seats.iter().filter(|s| s.can_act()).count();
+        (0..n).map(|i| (from + i) % n).find(|&seat| {
+            self.needs_action[seat]
+                && self.seats[seat].can_act()
+                && !(active == 1 && self.seats[seat].street_bet >= self.current_bet)
+        })
+    }
+
+    fn start_round(&mut self, from: usize) {
+        match self.next_to_act(from % self.seats.len()) {
+            Some(seat) => self.to_act = seat,
+            None => self.end_round(),
+        }
+    }
+
+    fn end_round(&mut self) {
+        self.phase = if self.street == Street::River {
+            Phase::Showdown
+        } else {
+            Phase::Dealing
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLINDS: Blinds = Blinds {
+        small: Chips::from_cents(50),
+        big: Chips::from_units(1),
+        ante: Chips::ZERO,
+    };
+
+    fn c(amount: f64) -> Chips {
+        Chips::from(amount)
+    }
+
+    fn stacks(amounts: &[f64]) -> Vec<Chips> {
+        amounts.iter().map(|&a| c(a)).collect()
+    }
+
+    fn deck() -> Deck {
+        let mut deck = Deck::new();
+        deck.shuffle_with_seed(7);
+        deck
+    }
+
+    fn card(s: &str) -> Card {
+        Card::from_str_notation(s).expect("valid card")
+    }
+
+    #[test]
+    fn test_heads_up_blinds_and_order() {
+        let mut g = GameState::new(BLINDS, &stacks(&[100.0, 100.0]), 0, deck()).expect("valid");
+        assert_eq!(g.pot(), c(1.5));
+        // Button posts the small blind and acts first preflop
+        let legal = g.legal_actions().expect("betting");
+        assert_eq!(legal.seat, 0);
+        assert_eq!(legal.call, Some(c(0.5)));
+        assert_eq!(legal.raise, Some((c(2.0), c(100.0))));
+        assert_eq!(
+            legal.actions(),
+            vec![
+                Action::Fold,
+                Action::Call,
+                Action::Raise(c(2.0)),
+                Action::AllIn(c(100.0))
+            ]
+        );
+
+        g.apply(0, Action::Call).expect("limp");
+        // Big blind keeps the option to raise
+        let legal = g.legal_actions().expect("betting");
+        assert_eq!(legal.seat, 1);
+        assert!(legal.can_check && !legal.can_fold);
+        g.apply(1, Action::Check).expect("check");
+        assert_eq!(g.phase(), Phase::Dealing);
+
+        g.deal_next_street().expect("flop");
+        assert_eq!(g.street(), Street::Flop);
+        assert_eq!(g.board().len(), 3);
+        // Out of position acts first after the flop
+        assert_eq!(g.to_act(), Some(1));
+        assert_eq!(
+            g.legal_actions().and_then(|l| l.raise),
+            Some((c(1.0), c(99.0)))
+        );
+    }
+
+    #[test]
+    fn test_min_raise_and_errors() {
+        let mut g = GameState::new(BLINDS, &stacks(&[100.0; 3]), 0, deck()).expect("valid");
+        // Button is first to act three-handed
+        assert_eq!(g.to_act(), Some(0));
+        assert!(matches!(
+            g.apply(1, Action::Call),
+            Err(CoreError::IllegalAction { seat: 1, .. })
+        ));
+        assert!(g.apply(0, Action::Raise(c(1.5))).is_err());
+        assert!(g.apply(0, Action::Bet(c(3.0))).is_err());
+        assert!(g.apply(0, Action::Check).is_err());
+        assert!(g.apply(0, Action::AllIn(c(50.0))).is_err());
+        g.apply(0, Action::Raise(c(3.0))).expect("raise by 2");
+
+        // Next raise must be at least another 2
+        assert_eq!(
+            g.legal_actions().and_then(|l| l.raise),
+            Some((c(5.0), c(100.0)))
+        );
+        let err = g.apply(1, Action::Raise(c(4.0))).expect_err("too small");
+        assert!(err.to_string().contains("minimum is 5.00"));
+        g.apply(1, Action::Raise(c(9.0))).expect("raise by 6");
+        assert_eq!(
+            g.legal_actions().and_then(|l| l.raise),
+            Some((c(15.0), c(100.0)))
+        );
+        g.apply(2, Action::Fold).expect("fold");
+        g.apply(0, Action::Call).expect("call");
+        assert_eq!(g.phase(), Phase::Dealing);
+        assert_eq!(g.pot(), c(19.0));
+        assert!(g.apply(1, Action::Check).is_err());
+        assert!(g.deal_street_cards(&[card("Ah")]).is_err());
+        g.deal_street_cards(&[card("Ah"), card("Kd"), card("7c")])
+            .expect("flop");
+        // Small blind is first to act after the button
+        assert_eq!(g.to_act(), Some(1));
+        assert!(g.apply(1, Action::Raise(c(5.0))).is_err());
+        assert!(g.apply(1, Action::Fold).is_err());
+    }
+
+    #[test]
+    fn test_incomplete_all_in_does_not_reopen() {
+        let mut g =
+            GameState::new(BLINDS, &stacks(&[200.0, 200.0, 50.0]), 2, deck()).expect("valid");
+        // Seat 0 small blind, 1 big blind, 2 button
+        g.apply(2, Action::Call).expect("call");
+        g.apply(0, Action::Call).expect("call");
+        g.apply(1, Action::Check).expect("check");
+        g.deal_next_street().expect("flop");
+
+        g.apply(0, Action::Bet(c(40.0))).expect("bet");
+        g.apply(1, Action::Call).expect("call");
+        // 9 more is short of a full raise of 40
+        g.apply(2, Action::AllIn(c(49.0))).expect("all-in");
+        let legal = g.legal_actions().expect("betting");
+        assert_eq!(legal.seat, 0);
+        assert_eq!(legal.call, Some(c(9.0)));
+        assert_eq!(legal.raise, None);
+        let err = g
+            .apply(0, Action::Raise(c(100.0)))
+            .expect_err("not reopened");
+        assert!(err.to_string().contains("did not reopen"));
+        g.apply(0, Action::Call).expect("call");
+        g.apply(1, Action::Call).expect("call");
+        assert_eq!(g.phase(), Phase::Dealing);
+        assert_eq!(g.pot(), c(150.0));
+    }
+
+    #[test]
+    fn test_short_all_ins_add_up_to_full_raise() {
+        let mut g =
+            GameState::new(BLINDS, &stacks(&[200.0, 200.0, 60.0, 80.0]), 3, deck()).expect("valid");
+        // Seat 0 SB, 1 BB, 2 first to act, 3 button
+        g.apply(2, Action::Call).expect("call");
+        g.apply(3, Action::Call).expect("call");
+        g.apply(0, Action::Call).expect("call");
+        g.apply(1, Action::Check).expect("check");
+        g.deal_next_street().expect("flop");
+
+        g.apply(0, Action::Bet(c(40.0))).expect("bet");
+        g.apply(1, Action::Call).expect("call");
+        g.apply(2, Action::AllIn(c(59.0))).expect("short all-in");
+        // A raise from seat 3 may still be a full one
+        assert_eq!(
+            g.legal_actions().and_then(|l| l.raise),
+            Some((c(79.0), c(79.0)))
+        );
+        g.apply(3, Action::AllIn(c(79.0))).expect("short all-in");
+        // Together the two all-ins raised seat 0's bet by 39: still short
+        assert_eq!(g.legal_actions().and_then(|l| l.raise), None);
+
+        let mut g =
+            GameState::new(BLINDS, &stacks(&[200.0, 200.0, 70.0, 90.0]), 3, deck()).expect("valid");
+        g.apply(2, Action::Call).expect("call");
+        g.apply(3, Action::Call).expect("call");
+        g.apply(0, Action::Call).expect("call");
+        g.apply(1, Action::Check).expect("check");
+        g.deal_next_street().expect("flop");
+        g.apply(0, Action::Bet(c(40.0))).expect("bet");
+        g.apply(1, Action::Call).expect("call");
+        g.apply(2, Action::AllIn(c(69.0))).expect("short all-in");
+        g.apply(3, Action::AllIn(c(89.0))).expect("short all-in");
+        // 89 is a full raise over the 40 seat 0 bet, so it may raise again
+        assert_eq!(
+            g.legal_actions().and_then(|l| l.raise),
+            Some((c(129.0), c(199.0)))
+        );
+    }
+
+    #[test]
+    fn test_sized_actions() {
+        let mut g =
+            GameState::new(BLINDS, &stacks(&[100.0, 100.0, 100.0]), 0, deck()).expect("valid");
+        let open: BetSize = "2.5x".parse().expect("valid");
+        assert_eq!(g.sized_action(open), Some(Action::Raise(c(2.5))));
+        g.apply(0, Action::Raise(c(2.5))).expect("open");
+        // Pot 4, call 2 more from the small blind: pot raise to 2.5 + 6
+        assert_eq!(
+            g.sized_action(BetSize::PotFraction(1.0)),
+            Some(Action::Raise(c(8.5)))
+        );
+        assert_eq!(
+            g.sized_action(BetSize::Geometric(1)),
+            Some(Action::AllIn(c(100.0)))
+        );
+        assert_eq!(g.sized_action(BetSize::AllInBelowSpr(1.0)), None);
+    }
+
+    #[test]
+    fn test_big_blind_option_raises() {
+        let mut g = GameState::new(BLINDS, &stacks(&[100.0, 100.0]), 0, deck()).expect("valid");
+        g.apply(0, Action::Call).expect("limp");
+        // Nothing to call, but the big blind is already a bet
+        let legal = g.legal_actions().expect("betting");
+        assert!(legal.can_check);
+        assert_eq!(legal.facing, c(1.0));
+        assert_eq!(
+            legal.actions(),
+            vec![
+                Action::Check,
+                Action::Raise(c(2.0)),
+                Action::AllIn(c(100.0))
+            ]
+        );
+        for action in legal.actions() {
+            g.clone()
+                .apply(1, action)
+                .expect("enumerated actions are legal");
+        }
+        let sized = g.sized_action(BetSize::BigBlinds(3.0)).expect("can raise");
+        assert_eq!(sized, Action::Raise(c(4.0)));
+        g.apply(1, sized).expect("sized raise is legal");
+
+        // On the flop nothing is bet, so the same option is a bet
+        g.apply(0, Action::Call).expect("call");
+        g.deal_next_street().expect("flop");
+        let legal = g.legal_actions().expect("betting");
+        assert_eq!(legal.facing, Chips::ZERO);
+        assert_eq!(
+            legal.actions(),
+            vec![Action::Check, Action::Bet(c(1.0)), Action::AllIn(c(96.0))]
+        );
+        let sized = g.sized_action(BetSize::PotFraction(0.5)).expect("can bet");
+        assert_eq!(sized, Action::Bet(c(4.0)));
+        g.apply(1, sized).expect("sized bet is legal");
+    }
+
+    #[test]
+    fn test_fold_ends_hand() {
+        let mut g = GameState::new(
+            Blinds {
+                ante: c(0.25),
+                ..BLINDS
+            },
+            &stacks(&[100.0; 3]),
+            0,
+            deck(),
+        )
+        .expect("valid");
+        assert_eq!(g.pot(), c(2.25));
+        g.apply(0, Action::Raise(c(3.0))).expect("raise");
+        g.apply(1, Action::Fold).expect("fold");
+        g.apply(2, Action::Fold).expect("fold");
+        assert_eq!(g.phase(), Phase::Finished);
+        assert!(g.legal_actions().is_none());
+        assert!(g.deal_next_street().is_err());
+        let s = g.settle(&[None; 3], Chips::CENT).expect("settle");
+        assert_eq!(s.winnings, stacks(&[5.25, 0.0, 0.0]));
+        assert_eq!(g.history().len(), 3);
+    }
+
+    #[test]
+    fn test_all_in_runs_out_to_showdown() {
+        let mut g =
+            GameState::new(BLINDS, &stacks(&[30.0, 100.0, 100.0]), 0, deck()).expect("valid");
+        assert!(g.settle(&[None; 3], c(1.0)).is_err());
+        g.apply(0, Action::AllIn(c(30.0))).expect("shove");
+        g.apply(1, Action::AllIn(c(100.0))).expect("re-shove");
+        g.apply(2, Action::Call).expect("call");
+        for _ in 0..3 {
+            assert_eq!(g.phase(), Phase::Dealing);
+            assert_eq!(g.to_act(), None);
+            g.deal_next_street().expect("run out");
+        }
+        assert_eq!(g.phase(), Phase::Showdown);
+        assert_eq!(g.board().len(), 5);
+
+        // Short stack wins the main pot, seat 2 the side pot
+        let s = g
+            .settle(&[Some(1), Some(3), Some(2)], c(1.0))
+            .expect("settle");
+        assert_eq!(s.winnings, stacks(&[90.0, 0.0, 140.0]));
+    }
+
+    #[test]
+    fn test_invalid_setup() {
+        assert!(GameState::new(BLINDS, &stacks(&[100.0]), 0, deck()).is_err());
+        assert!(GameState::new(BLINDS, &stacks(&[100.0, 0.0]), 0, deck()).is_err());
+        assert!(GameState::new(BLINDS, &stacks(&[100.0, 100.0]), 2, deck()).is_err());
+        let no_big = Blinds {
+            big: Chips::ZERO,
+            ..BLINDS
+        };
+        assert!(GameState::new(no_big, &stacks(&[100.0, 100.0]), 0, deck()).is_err());
+    }
+}
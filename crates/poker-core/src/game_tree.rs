@@ -0,0 +1,612 @@
+use serde::{Deserialize, Serialize};
+
+use crate::action::Action;
+use crate::bet_size::{BetContext, BetSize};
+use crate::board::Street;
+use crate::chips::Chips;
+use crate::error::CoreError;
+
+/// Index of a node inside `GameTree::nodes`.
+pub type NodeId = usize;
+
+/// Out-of-position player (acts first on every postflop street).
+pub const OOP: usize = 0;
+/// In-position player.
+pub const IP: usize = 1;
+
+/// Bet sizing options for one street.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreetConfig {
+    /// Opening bet sizes. Pot fractions are of the current pot.
+    pub bet_sizes: Vec<BetSize>,
+    /// Raise sizes. Pot fractions are of the pot after calling, so
+    /// `PotFraction(1.0)` is a pot-sized raise.
+    pub raise_sizes: Vec<BetSize>,
+    /// Maximum number of raises per street (the opening bet is not a raise).
+    pub raise_cap: u32,
+}
+
+/// Configuration for a heads-up postflop betting tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeConfig {
+    /// Pot at the root, before any postflop betting.
+    pub starting_pot: Chips,
+    /// Stack behind for each player at the root.
+    pub effective_stack: Chips,
+    /// Big blind, for sizes given in big blinds and the minimum bet.
+    #[serde(default = "default_big_blind")]
+    pub big_blind: Chips,
+    /// Flop, Turn or River.
+    pub starting_street: Street,
+    pub flop: StreetConfig,
+    pub turn: StreetConfig,
+    pub river: StreetConfig,
+    /// A bet or raise committing at least this fraction of the remaining
+    /// stack becomes an all-in instead.
+    pub all_in_threshold: f64,
+    /// When the stack-to-pot ratio at a decision is below this, all-in is the
+    /// only bet or raise offered.
+    pub force_all_in_spr: f64,
+}
+
+fn default_big_blind() -> Chips {
+    Chips::from_units(1)
+}
+
+impl TreeConfig {
+    fn street(&self, street: Street) -> &StreetConfig {
+        match street {
+            Street::Preflop | Street::Flop => &self.flop,
+            Street::Turn => &self.turn,
+            Street::River => &self.river,
+        }
+    }
+
+    fn validate(&self) -> Result<(), CoreError> {
+        let invalid = |msg: &str| Err(CoreError::InvalidTreeConfig(msg.to_string()));
+        if !self.starting_pot.is_positive() {
+            return invalid("starting pot must be positive");
+        }
+        if self.effective_stack < Chips::ZERO {
+            return invalid("effective stack cannot be negative");
+        }
+        if self.big_blind < Chips::ZERO {
+            return invalid("big blind cannot be negative");
+        }
+        if self.starting_street == Street::Preflop {
+            return invalid("trees start on the flop, turn or river");
+        }
+        if self.all_in_threshold.is_nan() || self.all_in_threshold <= 0.0 {
+            return invalid("all-in threshold must be positive");
+        }
+        for street in [&self.flop, &self.turn, &self.river] {
+            let sizes = street.bet_sizes.iter().chain(&street.raise_sizes);
+            for size in sizes {
+                if !size.is_valid() {
+                    return invalid(&format!("bet size {size} must be positive"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What happens at a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeKind {
+    /// `OOP` or `IP` chooses one of the node's actions.
+    Player(usize),
+    /// The next street's card is dealt. The single child is shared by every
+    /// card; the solver iterates the cards themselves.
+    Chance,
+    /// A player folded; the other wins the pot.
+    Fold { winner: usize },
+    /// River action closed, or both players are all-in with the board complete.
+    Showdown,
+}
+
+/// One node of the betting tree.
+///
+/// Bet, raise and all-in amounts in `actions` are the acting player's total
+/// commitment on the current street after the action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeNode {
+    pub kind: NodeKind,
+    pub street: Street,
+    /// Chips in the middle, including bets on the current street.
+    pub pot: Chips,
+    /// Chips behind for OOP and IP.
+    pub stacks: [Chips; 2],
+    /// Chips each player has committed on the current street.
+    pub street_bets: [Chips; 2],
+    /// Legal actions at a player node, in the same order as `children`.
+    pub actions: Vec<Action>,
+    pub children: Vec<NodeId>,
+}
+
+impl TreeNode {
+    pub fn is_terminal(&self) -> bool {
+        matches!(self.kind, NodeKind::Fold { .. } | NodeKind::Showdown)
+    }
+
+    /// The acting player at a player node.
+    pub fn player(&self) -> Option<usize> {
+        match self.kind {
+            NodeKind::Player(p) => Some(p),
+            _ => None,
+        }
+    }
+}
+
+/// Node counts and a rough memory estimate for solving a tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeStats {
+    pub player_nodes: usize,
+    pub chance_nodes: usize,
+    pub terminal_nodes: usize,
+    pub total_nodes: usize,
+    /// Bytes needed by a CFR solver storing an f32 regret and strategy sum
+    /// per action per combo (1326 per player), with every turn and river
+    /// card expanded, plus the tree itself.
+    pub estimated_bytes: u64,
+}
+
+/// Combos per player assumed by the memory estimate.
+const ESTIMATE_COMBOS: u64 = 1326;
+
+/// A heads-up postflop betting tree stored as an arena; the root is node 0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameTree {
+    pub config: TreeConfig,
+    pub nodes: Vec<TreeNode>,
+}
+
+/// Betting state while the tree is being expanded.
+#[derive(Debug, Clone, Copy)]
+struct BettingState {
+    street: Street,
+    pot: Chips,
+    stacks: [Chips; 2],
+    street_bets: [Chips; 2],
+    to_act: usize,
+    raises: u32,
+    /// Size of the last bet or raise increment on this street.
+    last_raise: Chips,
+}
+
+impl GameTree {
+    /// Build the full tree for a config.
+    pub fn build(config: &TreeConfig) -> Result<Self, CoreError> {
+        config.validate()?;
+        let mut tree = GameTree {
+            config: config.clone(),
+            nodes: Vec::new(),
+        };
+        let root = BettingState {
+            street: config.starting_street,
+            pot: config.starting_pot,
+            stacks: [config.effective_stack; 2],
+            street_bets: [Chips::ZERO; 2],
+            to_act: OOP,
+            raises: 0,
+            last_raise: Chips::ZERO,
+        };
+        tree.start_street(root);
+        Ok(tree)
+    }
+
+    pub fn root(&self) -> &TreeNode {
+        &self.nodes[0]
+    }
+
+    pub fn node(&self, id: NodeId) -> &TreeNode {
+        &self.nodes[id]
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Node counts and estimated solver memory.
+    pub fn stats(&self) -> TreeStats {
+        let mut stats = TreeStats {
+            player_nodes: 0,
+            chance_nodes: 0,
+            terminal_nodes: 0,
+            total_nodes: self.nodes.len(),
+            estimated_bytes: (self.nodes.len() * std::mem::size_of::<TreeNode>()) as u64,
+        };
+        let root_board = match self.config.starting_street {
+            Street::Preflop | Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5,
+        };
+        for node in &self.nodes {
+            match node.kind {
+                NodeKind::Player(_) => {
+                    stats.player_nodes += 1;
+                    let board = match node.street {
+                        Street::Preflop | Street::Flop => 3,
+                        Street::Turn => 4,
+                        Street::River => 5,
+                    };
+                    // One copy of this node per possible card dealt since the root
+                    let copies: u64 = (root_board..board).map(|dealt| 52 - dealt as u64).product();
+                    let per_copy = node.actions.len() as u64 * ESTIMATE_COMBOS * 2 * 4;
+                    stats.estimated_bytes += copies * per_copy;
+                }
+                NodeKind::Chance => stats.chance_nodes += 1,
+                NodeKind::Fold { .. } | NodeKind::Showdown => stats.terminal_nodes += 1,
+            }
+        }
+        stats
+    }
+
+    fn push(&mut self, kind: NodeKind, s: &BettingState) -> NodeId {
+        self.nodes.push(TreeNode {
+            kind,
+            street: s.street,
+            pot: s.pot,
+            stacks: s.stacks,
+            street_bets: s.street_bets,
+            actions: Vec::new(),
+            children: Vec::new(),
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Open a street: OOP acts, unless someone is all-in and the board just runs out.
+    fn start_street(&mut self, s: BettingState) -> NodeId {
+        if s.stacks.iter().any(|st| st.is_zero()) {
+            self.end_street(s)
+        } else {
+            self.player_node(s)
+        }
+    }
+
+    /// Close a street: deal the next card, or show down after the river.
+    fn end_street(&mut self, s: BettingState) -> NodeId {
+        let next = match s.street {
+            Street::Preflop => Street::Flop,
+            Street::Flop => Street::Turn,
+            Street::Turn => Street::River,
+            Street::River => return self.push(NodeKind::Showdown, &s),
+        };
+        let id = self.push(NodeKind::Chance, &s);
+        let child = self.start_street(BettingState {
+            street: next,
+            street_bets: [Chips::ZERO; 2],
+            to_act: OOP,
+            raises: 0,
+            last_raise: Chips::ZERO,
+            ..s
+        });
+        self.nodes[id].children.push(child);
+        id
+    }
+
+    fn player_node(&mut self, s: BettingState) -> NodeId {
+        let p = s.to_act;
+        let opp = 1 - p;
+        let id = self.push(NodeKind::Player(p), &s);
+        let to_call = s.street_bets[opp] - s.street_bets[p];
+
+        let mut edges: Vec<(Action, NodeId)> = Vec::new();
+        if to_call.is_zero() {
+            let check = if p == IP {
+                self.end_street(s)
+            } else {
+                self.player_node(BettingState { to_act: opp, ..s })
+            };
+            edges.push((Action::Check, check));
+            for action in self.bet_options(&s) {
+                let child = self.player_node(apply_bet(&s, action));
+                edges.push((action, child));
+            }
+        } else {
+            let fold = self.push(NodeKind::Fold { winner: opp }, &s);
+            edges.push((Action::Fold, fold));
+
+            let paid = to_call.min(s.stacks[p]);
+            let mut called = s;
+            called.stacks[p] -= paid;
+            called.pot += paid;
+            called.street_bets[p] += paid;
+            let call = self.end_street(called);
+            edges.push((Action::Call, call));
+
+            for action in self.raise_options(&s, to_call) {
+                let child = self.player_node(apply_bet(&s, action));
+                edges.push((action, child));
+            }
+        }
+
+        let (actions, children) = edges.into_iter().unzip();
+        self.nodes[id].actions = actions;
+        self.nodes[id].children = children;
+        id
+    }
+
+    /// Opening bets for the player to act, sorted by size, all-in last.
+    fn bet_options(&self, s: &BettingState) -> Vec<Action> {
+        let stack = s.stacks[s.to_act];
+        let sizes = &self.config.street(s.street).bet_sizes;
+        if sizes.is_empty() || stack.is_zero() {
+            return Vec::new();
+        }
+        let amounts = if stack.ratio(s.pot) < self.config.force_all_in_spr {
+            vec![stack]
+        } else {
+            let ctx = self.context(s);
+            sizes
+                .iter()
+                .filter_map(|size| size.resolve(&ctx))
+                .map(|total| total - s.street_bets[s.to_act])
+                .collect()
+        };
+        self.to_actions(amounts, Chips::ZERO, stack, false)
+    }
+
+    /// Raises for a player facing `to_call`, respecting the cap and min-raise.
+    fn raise_options(&self, s: &BettingState, to_call: Chips) -> Vec<Action> {
+        let p = s.to_act;
+        let street = self.config.street(s.street);
+        let stack = s.stacks[p];
+        if street.raise_sizes.is_empty()
+            || s.raises >= street.raise_cap
+            || stack <= to_call
+            || s.stacks[1 - p].is_zero()
+        {
+            return Vec::new();
+        }
+        let behind = stack - to_call;
+        let amounts = if behind.ratio(s.pot + to_call) < self.config.force_all_in_spr {
+            vec![stack]
+        } else {
+            let ctx = self.context(s);
+            street
+                .raise_sizes
+                .iter()
+                .filter_map(|size| size.resolve(&ctx))
+                .map(|total| total - s.street_bets[p])
+                .collect()
+        };
+        self.to_actions(amounts, s.street_bets[p], stack, true)
+    }
+
+    /// Sizing context for the player to act.
+    fn context(&self, s: &BettingState) -> BetContext {
+        let p = s.to_act;
+        BetContext {
+            pot: s.pot,
+            facing: s.street_bets[1 - p],
+            committed: s.street_bets[p],
+            last_raise: s.last_raise,
+            stack: s.stacks[p],
+            opponent_stack: s.stacks[1 - p],
+            big_blind: self.config.big_blind,
+        }
+    }
+
+    /// Turn chip amounts put in by the player into deduplicated actions.
+    fn to_actions(
+        &self,
+        amounts: Vec<Chips>,
+        committed: Chips,
+        stack: Chips,
+        raise: bool,
+    ) -> Vec<Action> {
+        let all_in_at = stack.mul_f64(self.config.all_in_threshold);
+        let mut puts: Vec<Chips> = amounts
+            .into_iter()
+            .filter(|a| a.is_positive())
+            .map(|a| if a >= all_in_at { stack } else { a.min(stack) })
+            .collect();
+        puts.sort();
+        puts.dedup();
+        puts.into_iter()
+            .map(|put| {
+                let total = committed + put;
+                if put >= stack {
+                    Action::AllIn(total)
+                } else if raise {
+                    Action::Raise(total)
+                } else {
+                    Action::Bet(total)
+                }
+            })
+            .collect()
+    }
+}
+
+/// State after the player to act bets, raises or shoves to a street total.
+fn apply_bet(s: &BettingState, action: Action) -> BettingState {
+    let p = s.to_act;
+    let opp = 1 - p;
+    let total = match action {
+        Action::Bet(t) | Action::Raise(t) | Action::AllIn(t) => t,
+        Action::Fold | Action::Check | Action::Call => s.street_bets[p],
+    };
+    let put = (total - s.street_bets[p]).min(s.stacks[p]);
+    let facing = s.street_bets[opp];
+    let mut next = *s;
+    next.stacks[p] -= put;
+    next.pot += put;
+    next.street_bets[p] += put;
+    next.to_act = opp;
+    if facing.is_positive() {
+        next.raises += 1;
+    }
+    // An all-in short of a full raise does not change the min-raise size
+    next.last_raise = s.last_raise.max(next.street_bets[p] - facing);
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn street(bets: &[f64], raises: &[f64], cap: u32) -> StreetConfig {
+        StreetConfig {
+            bet_sizes: bets.iter().map(|&f| BetSize::PotFraction(f)).collect(),
+            raise_sizes: raises.iter().map(|&f| BetSize::PotFraction(f)).collect(),
+            raise_cap: cap,
+        }
+    }
+
+    fn c(amount: f64) -> Chips {
+        Chips::from(amount)
+    }
+
+    fn config(pot: f64, stack: f64, start: Street) -> TreeConfig {
+        TreeConfig {
+            starting_pot: c(pot),
+            effective_stack: c(stack),
+            big_blind: c(1.0),
+            starting_street: start,
+            flop: street(&[0.5], &[1.0], 1),
+            turn: street(&[0.5], &[1.0], 1),
+            river: street(&[0.5], &[1.0], 1),
+            all_in_threshold: 1.0,
+            force_all_in_spr: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_river_tree_shape() {
+        let tree = GameTree::build(&config(100.0, 1000.0, Street::River)).expect("valid");
+        let root = tree.root();
+        assert_eq!(root.player(), Some(OOP));
+        assert_eq!(root.actions, vec![Action::Check, Action::Bet(c(50.0))]);
+
+        // OOP bets 50: IP may fold, call, or raise pot (call 50 + 200 = to 250)
+        let facing = tree.node(root.children[1]);
+        assert_eq!(facing.player(), Some(IP));
+        assert_eq!(
+            facing.actions,
+            vec![Action::Fold, Action::Call, Action::Raise(c(250.0))]
+        );
+        assert_eq!(facing.pot, c(150.0));
+
+        // Raise cap of 1: OOP can only fold or call the raise
+        let reraise = tree.node(facing.children[2]);
+        assert_eq!(reraise.actions, vec![Action::Fold, Action::Call]);
+        assert_eq!(tree.node(reraise.children[1]).kind, NodeKind::Showdown);
+        assert_eq!(
+            tree.node(reraise.children[0]).kind,
+            NodeKind::Fold { winner: IP }
+        );
+
+        // check, check -> showdown; check, bet, fold/call/raise...
+        let stats = tree.stats();
+        assert_eq!(stats.total_nodes, tree.node_count());
+        assert_eq!(stats.chance_nodes, 0);
+        assert_eq!(stats.player_nodes + stats.terminal_nodes, stats.total_nodes);
+    }
+
+    #[test]
+    fn test_chance_nodes_between_streets() {
+        let tree = GameTree::build(&config(100.0, 1000.0, Street::Flop)).expect("valid");
+        // Check-check on the flop leads to a turn chance node
+        let ip = tree.node(tree.root().children[0]);
+        let chance = tree.node(ip.children[0]);
+        assert_eq!(chance.kind, NodeKind::Chance);
+        assert_eq!(chance.children.len(), 1);
+        let turn = tree.node(chance.children[0]);
+        assert_eq!(turn.street, Street::Turn);
+        assert_eq!(turn.player(), Some(OOP));
+
+        let stats = tree.stats();
+        assert!(stats.chance_nodes > 0);
+        let river = GameTree::build(&config(100.0, 1000.0, Street::River)).expect("valid");
+        assert!(stats.estimated_bytes > 49 * river.stats().estimated_bytes);
+    }
+
+    #[test]
+    fn test_all_in_threshold_and_runout() {
+        let mut cfg = config(100.0, 120.0, Street::Turn);
+        cfg.turn = street(&[1.0], &[1.0], 3);
+        cfg.all_in_threshold = 0.8;
+        let tree = GameTree::build(&cfg).expect("valid");
+        // A pot bet of 100 is >= 80% of 120 behind, so it becomes all-in
+        assert_eq!(
+            tree.root().actions,
+            vec![Action::Check, Action::AllIn(c(120.0))]
+        );
+
+        // Facing all-in there is no raise; calling runs out the river
+        let facing = tree.node(tree.root().children[1]);
+        assert_eq!(facing.actions, vec![Action::Fold, Action::Call]);
+        let chance = tree.node(facing.children[1]);
+        assert_eq!(chance.kind, NodeKind::Chance);
+        let showdown = tree.node(chance.children[0]);
+        assert_eq!(showdown.kind, NodeKind::Showdown);
+        assert_eq!(showdown.pot, c(340.0));
+        assert_eq!(showdown.stacks, [Chips::ZERO; 2]);
+    }
+
+    #[test]
+    fn test_force_all_in_below_spr() {
+        let mut cfg = config(100.0, 50.0, Street::River);
+        cfg.river = street(&[0.33, 0.75], &[1.0], 2);
+        cfg.force_all_in_spr = 1.0;
+        let tree = GameTree::build(&cfg).expect("valid");
+        assert_eq!(
+            tree.root().actions,
+            vec![Action::Check, Action::AllIn(c(50.0))]
+        );
+    }
+
+    #[test]
+    fn test_min_raise_and_dedup() {
+        let mut cfg = config(10.0, 1000.0, Street::River);
+        // Two tiny raise sizes both fall below the min-raise and collapse into one
+        cfg.river = StreetConfig {
+            bet_sizes: vec![BetSize::Absolute(c(100.0))],
+            raise_sizes: vec![BetSize::Absolute(c(1.0)), BetSize::Absolute(c(2.0))],
+            raise_cap: 1,
+        };
+        let tree = GameTree::build(&cfg).expect("valid");
+        let facing = tree.node(tree.root().children[1]);
+        // Min raise is to 200 (call 100 + raise by 100)
+        assert_eq!(
+            facing.actions,
+            vec![Action::Fold, Action::Call, Action::Raise(c(200.0))]
+        );
+    }
+
+    #[test]
+    fn test_parsed_sizes() {
+        let mut cfg = config(100.0, 1000.0, Street::River);
+        cfg.river = StreetConfig {
+            bet_sizes: BetSize::parse_list("33%, a<0.3").expect("valid"),
+            raise_sizes: BetSize::parse_list("3x, 20bb").expect("valid"),
+            raise_cap: 1,
+        };
+        let tree = GameTree::build(&cfg).expect("valid");
+        // Deep stacks: no all-in bet, and 20bb is below the min-raise to 66
+        assert_eq!(
+            tree.root().actions,
+            vec![Action::Check, Action::Bet(c(33.0))]
+        );
+        let facing = tree.node(tree.root().children[1]);
+        assert_eq!(
+            facing.actions,
+            vec![
+                Action::Fold,
+                Action::Call,
+                Action::Raise(c(66.0)),
+                Action::Raise(c(99.0))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(GameTree::build(&config(0.0, 100.0, Street::Flop)).is_err());
+        assert!(GameTree::build(&config(10.0, 100.0, Street::Preflop)).is_err());
+        let mut cfg = config(10.0, 100.0, Street::Flop);
+        cfg.flop.bet_sizes.push(BetSize::PotFraction(-0.5));
+        assert!(GameTree::build(&cfg).is_err());
+    }
+}
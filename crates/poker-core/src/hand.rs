@@ -86,8 +86,36 @@ impl Hand {
     pub fn mask(self) -> u64 {
         self.cards[0].mask() | self.cards[1].mask()
     }
+
+    /// Index of this specific combo among all 1326 (0..1325).
+    /// Ordered by the higher card index, then the lower: 2d2c = 0, AsAh = 1325.
+    pub fn combo_index(self) -> usize {
+        let a = self.cards[0].as_u8() as usize;
+        let b = self.cards[1].as_u8() as usize;
+        let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+        hi * (hi - 1) / 2 + lo
+    }
+
+    /// Inverse of `combo_index`. Returns None for indices >= 1326.
+    pub fn from_combo_index(index: usize) -> Option<Self> {
+        if index >= NUM_COMBOS {
+            return None;
+        }
+        // Largest hi with hi*(hi-1)/2 <= index
+        let mut hi = 1;
+        while (hi + 1) * hi / 2 <= index {
+            hi += 1;
+        }
+        let lo = index - hi * (hi - 1) / 2;
+        let c1 = Card::from_u8(hi as u8).ok()?;
+        let c2 = Card::from_u8(lo as u8).ok()?;
+        Some(Hand::new(c1, c2))
+    }
 }
 
+/// Number of distinct two-card combos (52 choose 2).
+pub const NUM_COMBOS: usize = 1326;
+
 impl fmt::Display for Hand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}{}", self.cards[0], self.cards[1])
@@ -115,6 +143,19 @@ pub fn notation_for_matrix_cell(row: usize, col: usize) -> String {
     }
 }
 
+/// Canonical hand group index (0..168) for two ranks, in either order.
+/// `suited` is ignored for pairs.
+pub fn matrix_index(r1: Rank, r2: Rank, suited: bool) -> usize {
+    let (hi, lo) = if r1 >= r2 { (r1, r2) } else { (r2, r1) };
+    let hi_row = 12 - hi.index() as usize;
+    let lo_row = 12 - lo.index() as usize;
+    if hi == lo || suited {
+        hi_row * 13 + lo_row
+    } else {
+        lo_row * 13 + hi_row
+    }
+}
+
 /// Count the number of specific combos for a hand group.
 pub fn combo_count(row: usize, col: usize) -> u32 {
     if row == col {
@@ -207,6 +248,21 @@ mod tests {
         assert_eq!(notation_for_matrix_cell(12, 12), "22");
     }
 
+    #[test]
+    fn test_matrix_index_matches_canonical_index() {
+        for a in 0..52u8 {
+            for b in (a + 1)..52 {
+                let c1 = Card::from_u8(a).expect("valid");
+                let c2 = Card::from_u8(b).expect("valid");
+                let hand = Hand::new(c1, c2);
+                assert_eq!(
+                    matrix_index(c1.rank(), c2.rank(), hand.is_suited()),
+                    hand.canonical_index()
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_combo_count() {
         assert_eq!(combo_count(0, 0), 6);   // Pair
@@ -214,6 +270,23 @@ mod tests {
         assert_eq!(combo_count(1, 0), 12);  // Offsuit
     }
 
+    #[test]
+    fn test_combo_index_round_trip() {
+        let mut seen = std::collections::HashSet::new();
+        for idx in 0..NUM_COMBOS {
+            let hand = Hand::from_combo_index(idx).expect("valid index");
+            assert_eq!(hand.combo_index(), idx);
+            assert!(seen.insert(hand.mask()));
+        }
+        assert!(Hand::from_combo_index(NUM_COMBOS).is_none());
+
+        let aa = Hand::new(
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::Ace, Suit::Hearts),
+        );
+        assert_eq!(aa.combo_index(), NUM_COMBOS - 1);
+    }
+
     #[test]
     fn test_hand_mask() {
         let hand = Hand::new(
@@ -0,0 +1,90 @@
+use crate::error::CoreError;
+
+use super::pokerstars::{parse_hand, Dialect};
+use super::{HandHistory, HandHistoryParser};
+
+/// GGPoker writes the PokerStars format with its own header, anonymised
+/// cash-game names, `Dealt to` lines for every player and jackpot fees in
+/// the summary.
+const GGPOKER: Dialect = Dialect {
+    site: "GGPoker",
+    headers: &["Poker Hand #"],
+};
+
+/// GGPoker cash games and tournaments.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GgPokerParser;
+
+impl HandHistoryParser for GgPokerParser {
+    fn site(&self) -> &'static str {
+        GGPOKER.site
+    }
+
+    fn detect(&self, line: &str) -> bool {
+        GGPOKER.is_header(line)
+    }
+
+    fn is_hand_start(&self, line: &str) -> bool {
+        GGPOKER.is_header(line)
+    }
+
+    fn parse_hand(&mut self, lines: &[(usize, String)]) -> Result<HandHistory, CoreError> {
+        parse_hand(&GGPOKER, lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::Action;
+    use crate::board::Street;
+    use crate::chips::Chips;
+    use crate::game_type::GameType;
+    use crate::hand_history::HandHistoryReader;
+    use crate::position::Position;
+
+    const HANDS: &str = include_str!("../../tests/fixtures/hand_history/ggpoker.txt");
+
+    #[test]
+    fn test_ggpoker_hands() {
+        let mut reader = HandHistoryReader::new(HANDS.as_bytes());
+        let cash = reader.next().expect("cash hand").expect("valid hand");
+        assert_eq!(reader.site(), Some("GGPoker"));
+        assert_eq!(cash.site, "GGPoker");
+        assert_eq!(cash.hand_id, "RC1450023519");
+        assert_eq!(cash.currency.as_deref(), Some("USD"));
+        assert_eq!(cash.big_blind, 0.1);
+        let hero = cash.hero.expect("hero");
+        assert_eq!(cash.seats[hero].position, Some(Position::SB));
+        assert!(cash.seats[hero].cards.is_some());
+        // Other players' bare `Dealt to` lines carry no cards; the villain's
+        // cards come from the showdown
+        let folded = cash.seat_of("8f3a2c1d").expect("folded player");
+        assert_eq!(cash.seats[folded].cards, None);
+        let villain = cash.seat_of("77aa31f0").expect("villain");
+        assert!(cash.seats[villain].cards.is_some());
+        assert_eq!(cash.seats.iter().filter(|s| s.cards.is_some()).count(), 2);
+        assert_eq!(cash.board.street(), Street::River);
+        // Rake and jackpot both come out of the pot
+        assert_eq!(cash.rake, Chips::from(0.3));
+        assert_eq!(cash.jackpot, Chips::from(0.03));
+        assert_eq!(cash.net(hero), Chips::from(2.8));
+        assert_eq!(cash.net(villain), Chips::from(-3.0));
+
+        let mtt = reader.next().expect("tournament hand").expect("valid hand");
+        assert_eq!(mtt.game_type, GameType::MTT);
+        let info = mtt.tournament.as_ref().expect("tournament");
+        assert_eq!(info.id, "123456789");
+        assert_eq!(info.level.as_deref(), Some("10"));
+        assert_eq!(mtt.big_blind, 400.0);
+        let hero = mtt.hero.expect("hero");
+        assert_eq!(mtt.seats[hero].position, Some(Position::CO));
+        assert_eq!(
+            mtt.street_actions(Street::Preflop).next().map(|a| a.action),
+            Some(Action::Raise(Chips::from_units(800)))
+        );
+        assert_eq!(mtt.net(hero), Chips::from(1120.0));
+        let nets: Chips = (0..mtt.seats.len()).map(|s| mtt.net(s)).sum();
+        assert_eq!(nets, Chips::ZERO);
+        assert!(reader.next().is_none());
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+
+use crate::action::Action;
+use crate::board::Street;
+use crate::card::Card;
+use crate::error::CoreError;
+use crate::game_type::GameType;
+
+use super::{
+    invalid, parse_amount, Collected, HandBuilder, HandHistory, HandHistoryParser, PostKind,
+    TournamentInfo,
+};
+
+/// iPoker network XML exports.
+///
+/// A file is a `<session>` whose `<general>` block holds the table, stakes
+/// and hero name, followed by one `<game>` per hand. The session details
+/// are kept from hand to hand. Elements are expected one per line, as the
+/// client writes them.
+#[derive(Debug, Clone, Default)]
+pub struct IPokerParser {
+    session: HashMap<String, String>,
+}
+
+impl HandHistoryParser for IPokerParser {
+    fn site(&self) -> &'static str {
+        "iPoker"
+    }
+
+    fn detect(&self, line: &str) -> bool {
+        line.starts_with("<?xml") || line.starts_with("<session")
+    }
+
+    fn is_hand_start(&self, line: &str) -> bool {
+        line.trim_start().starts_with("<game ")
+    }
+
+    fn parse_hand(&mut self, lines: &[(usize, String)]) -> Result<HandHistory, CoreError> {
+        let mut game: Option<HandBuilder> = None;
+        let mut hand = None;
+        let mut in_session = false;
+        let mut last = lines.first().map_or(1, |(line, _)| *line);
+        for (line, text) in lines {
+            let (line, text) = (*line, text.trim());
+            last = line;
+            if text.starts_with("<session") {
+                self.session.clear();
+            } else if text == "<general>" {
+                in_session = game.is_none();
+            } else if text == "</general>" {
+                in_session = false;
+            } else if in_session {
+                if let Some((tag, value)) = element(text) {
+                    self.session.insert(tag.to_string(), unescape(value));
+                }
+            } else if text.starts_with("<game ") {
+                game = Some(self.start_game(line, text)?);
+            } else if text == "</game>" {
+                let mut builder = game
+                    .take()
+                    .ok_or_else(|| invalid(line, "</game> without <game>"))?;
+                builder.return_uncalled();
+                builder.derive_totals();
+                let players = builder.hand_mut().seats.len();
+                if builder.hand_mut().max_seats == 0 {
+                    builder.hand_mut().max_seats = players;
+                }
+                hand = Some(builder.finish(line)?);
+            } else if let Some(builder) = game.as_mut() {
+                self.parse_game_line(builder, line, text)?;
+            }
+        }
+        hand.ok_or_else(|| invalid(last, "hand has no closing </game>"))
+    }
+}
+
+impl IPokerParser {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.session.get(name).map(String::as_str)
+    }
+
+    /// `<game gamecode="7520031001">`
+    fn start_game(&self, line: usize, text: &str) -> Result<HandBuilder, CoreError> {
+        let hand_id = attr(text, "gamecode").ok_or_else(|| invalid(line, "game has no code"))?;
+        let game = self
+            .field("gametype")
+            .ok_or_else(|| invalid(line, "session has no game type"))?;
+        if !game.starts_with("Holdem NL") {
+            return Err(invalid(line, "only no-limit hold'em is supported"));
+        }
+        let blind = |name: &str| {
+            let value = self
+                .field(name)
+                .ok_or_else(|| invalid(line, format!("session has no {name}")))?;
+            parse_amount(value).map_err(|e| invalid(line, e))
+        };
+        let big = blind("bigblind")?;
+        if big <= 0.0 {
+            return Err(invalid(line, "big blind must be positive"));
+        }
+        let small = blind("smallblind")?;
+
+        let mut builder = HandBuilder::new("iPoker", hand_id, big);
+        let small = builder.chips(small);
+        let hand = builder.hand_mut();
+        hand.blinds.small = small;
+        hand.table = self.field("tablename").unwrap_or_default().to_string();
+        hand.date = self.field("startdate").unwrap_or_default().to_string();
+        hand.max_seats = self
+            .field("tablesize")
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(0);
+        if let Some(id) = self.field("tournamentcode") {
+            hand.game_type = GameType::MTT;
+            hand.tournament = Some(TournamentInfo {
+                id: id.to_string(),
+                buy_in: self.field("buyin").map(str::to_string),
+                level: None,
+            });
+        } else {
+            hand.currency = self
+                .field("currency")
+                .or_else(|| self.field("tablecurrency"))
+                .map(str::to_string);
+        }
+        Ok(builder)
+    }
+
+    fn parse_game_line(
+        &self,
+        builder: &mut HandBuilder,
+        line: usize,
+        text: &str,
+    ) -> Result<(), CoreError> {
+        let amount = |builder: &HandBuilder, name: &str| {
+            let value = attr(text, name).unwrap_or_default();
+            parse_amount(&value)
+                .map(|a| builder.chips(a))
+                .map_err(|e| invalid(line, format!("invalid {name}: {e}")))
+        };
+        let seat_of = |builder: &mut HandBuilder| {
+            let player = attr(text, "player").unwrap_or_default();
+            builder
+                .hand_mut()
+                .seat_of(&player)
+                .ok_or_else(|| invalid(line, format!("unknown player '{player}'")))
+        };
+
+        if let Some((tag, value)) = element(text) {
+            if tag == "startdate" {
+                builder.hand_mut().date = value.to_string();
+            }
+        } else if text.starts_with("<player ") {
+            let name = attr(text, "name").ok_or_else(|| invalid(line, "player has no name"))?;
+            let number = attr(text, "seat").unwrap_or_default();
+            let number = number
+                .parse()
+                .map_err(|_| invalid(line, format!("invalid seat number '{number}'")))?;
+            let stack = amount(builder, "chips")?;
+            let win = amount(builder, "win")?;
+            builder.add_seat(number, name, stack, false);
+            let seat = builder.hand_mut().seats.len() - 1;
+            if attr(text, "dealer").as_deref() == Some("1") {
+                builder.hand_mut().button_seat = number;
+            }
+            if win.is_positive() {
+                let collected = Collected { seat, amount: win };
+                builder.hand_mut().collected.push(collected);
+            }
+        } else if text.starts_with("<cards ") {
+            let kind = attr(text, "type").unwrap_or_default();
+            let cards = text
+                .split_once('>')
+                .and_then(|(_, c)| c.split_once('<'))
+                .map(|(c, _)| c)
+                .unwrap_or_default();
+            let street = match kind.as_str() {
+                "Pocket" => {
+                    if cards.contains('X') {
+                        return Ok(());
+                    }
+                    let seat = seat_of(builder)?;
+                    match parse_cards(line, cards)?[..] {
+                        [a, b] if a != b => builder.show(seat, [a, b]),
+                        _ => {
+                            return Err(invalid(line, format!("expected two hole cards: {cards}")))
+                        }
+                    }
+                    if self.field("nickname")
+                        == Some(builder.hand_mut().seats[seat].player.as_str())
+                    {
+                        builder.hand_mut().hero = Some(seat);
+                    }
+                    return Ok(());
+                }
+                "Flop" => Street::Flop,
+                "Turn" => Street::Turn,
+                "River" => Street::River,
+                other => return Err(invalid(line, format!("unknown cards type '{other}'"))),
+            };
+            let cards = parse_cards(line, cards)?;
+            builder.start_street(street, &cards, line)?;
+        } else if text.starts_with("<action ") {
+            let seat = seat_of(builder)?;
+            let sum = amount(builder, "sum")?;
+            let kind = attr(text, "type").unwrap_or_default();
+            match kind.as_str() {
+                "0" => builder.passive(seat, Action::Fold),
+                "4" => builder.passive(seat, Action::Check),
+                "1" => builder.post(seat, PostKind::SmallBlind, sum),
+                "2" => builder.post(seat, PostKind::BigBlind, sum),
+                "15" => {
+                    if builder.hand_mut().blinds.ante.is_zero() {
+                        builder.hand_mut().blinds.ante = sum;
+                    }
+                    builder.post(seat, PostKind::Ante, sum);
+                }
+                // Call, bet, all-in and raise all give the chips added
+                "3" | "5" | "23" => builder.add(seat, sum, false),
+                "7" => builder.add(seat, sum, true),
+                // Sitting out, timeouts and the like move no chips
+                _ if sum.is_zero() => {}
+                other => return Err(invalid(line, format!("unknown action type '{other}'"))),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `<tag>value</tag>` on one line.
+fn element(text: &str) -> Option<(&str, &str)> {
+    let (tag, rest) = text.strip_prefix('<')?.split_once('>')?;
+    let value = rest
+        .strip_suffix('>')?
+        .strip_suffix(tag)?
+        .strip_suffix("</")?;
+    Some((tag, value))
+}
+
+/// Value of `name="..."` in a start tag.
+fn attr(text: &str, name: &str) -> Option<String> {
+    let key = format!(" {name}=\"");
+    let start = text.find(&key)? + key.len();
+    let len = text[start..].find('"')?;
+    Some(unescape(&text[start..start + len]))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Cards written suit first, e.g. `SA D7 C10`.
+fn parse_cards(line: usize, text: &str) -> Result<Vec<Card>, CoreError> {
+    text.split_whitespace()
+        .map(|token| {
+            let mut chars = token.chars();
+            let suit = chars.next().unwrap_or_default().to_ascii_lowercase();
+            let rank = match chars.as_str() {
+                "10" => "T",
+                rank => rank,
+            };
+            Card::from_str_notation(&format!("{rank}{suit}"))
+                .map_err(|e| invalid(line, e.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chips::Chips;
+    use crate::hand::Hand;
+    use crate::hand_history::HandHistoryReader;
+    use crate::position::Position;
+
+    const SESSION: &str = include_str!("../../tests/fixtures/hand_history/ipoker.xml");
+
+    fn card(s: &str) -> Card {
+        Card::from_str_notation(s).expect("valid card")
+    }
+
+    #[test]
+    fn test_ipoker_session() {
+        let hands: Vec<_> = HandHistoryReader::new(SESSION.as_bytes()).collect();
+        assert_eq!(hands.len(), 2);
+        let hand = hands[0].as_ref().expect("valid hand");
+        assert_eq!(hand.site, "iPoker");
+        assert_eq!(hand.hand_id, "8104477213");
+        assert_eq!(hand.table, "Brighton");
+        assert_eq!(hand.max_seats, 6);
+        assert_eq!(hand.big_blind, 0.2);
+        assert_eq!(hand.blinds.small, Chips::from(0.1));
+        assert_eq!(hand.currency.as_deref(), Some("GBP"));
+        assert_eq!(hand.date, "2023-03-14 19:04:51");
+        let hero = hand.hero.expect("hero");
+        assert_eq!(hand.seats[hero].player, "Hero");
+        assert_eq!(hand.seats[hero].position, Some(Position::BB));
+        assert_eq!(
+            hand.seats[hero].cards,
+            Some(Hand::new(card("Tc"), card("Td")))
+        );
+        // Hidden pocket cards are written as `X X`
+        let villain = hand.seat_of("Player9e6b").expect("villain");
+        assert_eq!(hand.seats[villain].position, Some(Position::BTN));
+        assert_eq!(hand.seats[villain].cards, None);
+        assert_eq!(hand.board.to_string(), "[Th 7s 2c Kd]");
+
+        // Type 23 gives the chips added by a raise, not the raise-to amount
+        let flop: Vec<_> = hand.street_actions(Street::Flop).collect();
+        assert_eq!(flop[0].action, Action::Check);
+        assert_eq!(flop[1].action, Action::Bet(Chips::from(0.8)));
+        assert_eq!(flop[2].action, Action::Raise(Chips::from(2.4)));
+        assert_eq!(flop[3].action, Action::Call);
+        assert_eq!(hand.returned[0].amount, Chips::from(1.0));
+        assert_eq!(hand.total_pot, Chips::from(6.1));
+        assert_eq!(hand.rake, Chips::from(0.3));
+        assert_eq!(hand.net(hero), Chips::from(2.8));
+
+        // The second game reuses the session header; type 7 is an all-in
+        let hand = hands[1].as_ref().expect("valid hand");
+        assert_eq!(hand.table, "Brighton");
+        assert_eq!(hand.currency.as_deref(), Some("GBP"));
+        let hero = hand.hero.expect("hero");
+        assert_eq!(hand.seats[hero].position, Some(Position::SB));
+        let villain = hand.seat_of("Player01ad").expect("villain");
+        assert_eq!(
+            hand.seats[villain].cards,
+            Some(Hand::new(card("Jh"), card("Jd")))
+        );
+        let preflop: Vec<_> = hand.street_actions(Street::Preflop).collect();
+        assert_eq!(preflop[3].action, Action::AllIn(Chips::from(9.85)));
+        assert!(preflop[3].all_in);
+        assert_eq!(hand.board.to_string(), "[9d 4c 2h Qs 3s]");
+        assert_eq!(hand.rake, Chips::from(0.5));
+        assert_eq!(hand.net(hero), Chips::from(-9.85));
+        assert_eq!(hand.net(villain), Chips::from(9.35));
+    }
+
+    #[test]
+    fn test_ipoker_malformed_action() {
+        let broken = SESSION.replacen("type=\"23\" sum=\"£2.40\"", "type=\"23\" sum=\"£two\"", 1);
+        let results: Vec<_> = HandHistoryReader::new(broken.as_bytes()).collect();
+        assert!(matches!(
+            results[0],
+            Err(CoreError::InvalidHandHistory { line: 48, .. })
+        ));
+        assert!(results[1].is_ok());
+    }
+}
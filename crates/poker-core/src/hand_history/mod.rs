@@ -0,0 +1,811 @@
+//! Hand histories from online poker rooms.
+//!
+//! Every amount in a [`HandHistory`] is kept as the room wrote it, in its
+//! currency for cash games and in chips for tournaments, so a 75-chip ante
+//! under a 600 big blind stays exact. [`HandHistory::in_bb`] converts to big
+//! blinds for display and stats.
+//!
+//! Each room's format is a [`HandHistoryParser`]; [`HandHistoryReader`]
+//! picks one from the first line of a file unless given one explicitly.
+
+mod ggpoker;
+mod ipoker;
+mod ohh;
+mod poker888;
+mod pokerstars;
+mod winamax;
+
+pub use ggpoker::GgPokerParser;
+pub use ipoker::IPokerParser;
+pub use ohh::{
+    Ohh, OhhAction, OhhBetLimit, OhhFile, OhhPlayer, OhhPlayerWin, OhhPot, OhhRound,
+    OhhTournamentInfo, OHH_SPEC_VERSION,
+};
+pub use poker888::Poker888Parser;
+pub use pokerstars::PokerStarsParser;
+pub use winamax::WinamaxParser;
+
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+
+use crate::action::Action;
+use crate::board::{Board, Street};
+use crate::card::Card;
+use crate::chips::Chips;
+use crate::error::CoreError;
+use crate::game_state::Blinds;
+use crate::game_type::GameType;
+use crate::hand::Hand;
+use crate::position::Position;
+
+/// Tournament details from the hand header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TournamentInfo {
+    pub id: String,
+    /// Buy-in as written, e.g. `$4.40+$0.60`.
+    pub buy_in: Option<String>,
+    /// Blind level as written, e.g. `IV`.
+    pub level: Option<String>,
+}
+
+/// A player in the hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandSeat {
+    /// Seat number as printed by the room.
+    pub seat: u8,
+    pub player: String,
+    /// Starting stack.
+    pub stack: Chips,
+    /// `None` for players sitting out.
+    pub position: Option<Position>,
+    /// Hole cards, when dealt to hero or shown.
+    pub cards: Option<Hand>,
+    pub sitting_out: bool,
+    /// Bounty on the player in a knockout tournament, in the tournament's
+    /// currency, when the room prints it.
+    #[serde(default)]
+    pub bounty: Option<f64>,
+}
+
+/// Kind of forced bet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PostKind {
+    SmallBlind,
+    BigBlind,
+    /// Small and big blind together; the small blind part is dead.
+    BothBlinds,
+    Ante,
+    Straddle,
+}
+
+/// A forced bet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Post {
+    /// Index into `HandHistory::seats`.
+    pub seat: usize,
+    pub kind: PostKind,
+    pub amount: Chips,
+}
+
+/// A voluntary action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandAction {
+    pub street: Street,
+    /// Index into `HandHistory::seats`.
+    pub seat: usize,
+    /// Bet, raise and all-in amounts are the player's street total.
+    pub action: Action,
+    /// Chips this action added to the pot.
+    pub amount: Chips,
+    pub all_in: bool,
+}
+
+/// Chips a player took from the pot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Collected {
+    /// Index into `HandHistory::seats`.
+    pub seat: usize,
+    pub amount: Chips,
+}
+
+/// One parsed hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandHistory {
+    pub site: String,
+    pub hand_id: String,
+    pub game_type: GameType,
+    pub tournament: Option<TournamentInfo>,
+    /// Currency code or symbol for real-money hands.
+    pub currency: Option<String>,
+    /// Start time as written by the room.
+    pub date: String,
+    pub table: String,
+    pub max_seats: usize,
+    pub button_seat: u8,
+    /// Big blind in the room's own units, the same as every amount here.
+    pub big_blind: f64,
+    pub blinds: Blinds,
+    pub seats: Vec<HandSeat>,
+    /// Index into `seats` of the player the cards were dealt to.
+    pub hero: Option<usize>,
+    pub posts: Vec<Post>,
+    pub actions: Vec<HandAction>,
+    pub board: Board,
+    /// Uncalled bets given back.
+    pub returned: Vec<Collected>,
+    pub collected: Vec<Collected>,
+    pub total_pot: Chips,
+    /// Rake plus any jackpot or other fees taken from the pot.
+    pub rake: Chips,
+    /// The part of `rake` that went to jackpots and other room fees.
+    #[serde(default)]
+    pub jackpot: Chips,
+}
+
+impl HandHistory {
+    /// Parse a single hand.
+    pub fn parse(text: &str) -> Result<Self, CoreError> {
+        HandHistoryReader::new(text.as_bytes())
+            .next()
+            .unwrap_or_else(|| Err(invalid(1, "no hand found")))
+    }
+
+    /// An amount from this hand in big blinds.
+    pub fn in_bb(&self, amount: Chips) -> f64 {
+        amount.to_f64() / self.big_blind
+    }
+
+    pub fn seat_of(&self, player: &str) -> Option<usize> {
+        self.seats.iter().position(|s| s.player == player)
+    }
+
+    /// Actions taken on one street.
+    pub fn street_actions(&self, street: Street) -> impl Iterator<Item = &HandAction> {
+        self.actions.iter().filter(move |a| a.street == street)
+    }
+
+    /// Chips a seat put in, net of any uncalled bet returned.
+    pub fn invested(&self, seat: usize) -> Chips {
+        let posted: Chips = self
+            .posts
+            .iter()
+            .filter(|p| p.seat == seat)
+            .map(|p| p.amount)
+            .sum();
+        let acted: Chips = self
+            .actions
+            .iter()
+            .filter(|a| a.seat == seat)
+            .map(|a| a.amount)
+            .sum();
+        posted + acted - seat_total(&self.returned, seat)
+    }
+
+    /// Chips a seat won or lost over the hand.
+    pub fn net(&self, seat: usize) -> Chips {
+        seat_total(&self.collected, seat) - self.invested(seat)
+    }
+}
+
+fn seat_total(entries: &[Collected], seat: usize) -> Chips {
+    entries
+        .iter()
+        .filter(|c| c.seat == seat)
+        .map(|c| c.amount)
+        .sum()
+}
+
+fn invalid(line: usize, message: impl Into<String>) -> CoreError {
+    CoreError::InvalidHandHistory {
+        line,
+        message: message.into(),
+    }
+}
+
+/// A room's hand history format.
+///
+/// Files are cut into hands at lines where [`is_hand_start`] holds, and each
+/// hand's numbered lines go to [`parse_hand`]. Lines before a file's first
+/// hand, such as an XML session header, arrive with that hand.
+///
+/// [`is_hand_start`]: HandHistoryParser::is_hand_start
+/// [`parse_hand`]: HandHistoryParser::parse_hand
+pub trait HandHistoryParser: Send {
+    /// Room name written to `HandHistory::site`.
+    fn site(&self) -> &'static str;
+
+    /// Whether a file whose first non-blank line is `line` is in this format.
+    fn detect(&self, line: &str) -> bool;
+
+    /// Whether `line` begins a new hand.
+    fn is_hand_start(&self, line: &str) -> bool;
+
+    /// Parse one hand. Takes `&mut self` so formats that write shared
+    /// details once per file can carry them from hand to hand.
+    fn parse_hand(&mut self, lines: &[(usize, String)]) -> Result<HandHistory, CoreError>;
+}
+
+/// A parser for every supported format, in detection order.
+pub fn parsers() -> Vec<Box<dyn HandHistoryParser>> {
+    vec![
+        Box::new(PokerStarsParser),
+        Box::new(GgPokerParser),
+        Box::new(Poker888Parser),
+        Box::new(WinamaxParser),
+        Box::new(IPokerParser::default()),
+    ]
+}
+
+/// The parser for a file whose first non-blank line is `line`.
+pub fn detect_format(line: &str) -> Option<Box<dyn HandHistoryParser>> {
+    parsers().into_iter().find(|p| p.detect(line))
+}
+
+/// Reads hands one at a time from a file that may hold thousands.
+///
+/// A malformed hand yields an error carrying its line number, and reading
+/// carries on with the next hand. A file in no known format yields a
+/// single error.
+pub struct HandHistoryReader<R> {
+    lines: std::io::Lines<R>,
+    line: usize,
+    pending: Option<(usize, String)>,
+    parser: Option<Box<dyn HandHistoryParser>>,
+    unknown: bool,
+}
+
+impl<R: BufRead> HandHistoryReader<R> {
+    /// Read a file, detecting its format from the first non-blank line.
+    pub fn new(reader: R) -> Self {
+        HandHistoryReader {
+            lines: reader.lines(),
+            line: 0,
+            pending: None,
+            parser: None,
+            unknown: false,
+        }
+    }
+
+    /// Read a file in a known format.
+    pub fn with_parser(reader: R, parser: Box<dyn HandHistoryParser>) -> Self {
+        HandHistoryReader {
+            parser: Some(parser),
+            ..HandHistoryReader::new(reader)
+        }
+    }
+
+    /// Room of the detected format, once the first line has been read.
+    pub fn site(&self) -> Option<&'static str> {
+        self.parser.as_ref().map(|p| p.site())
+    }
+}
+
+impl<R: BufRead> Iterator for HandHistoryReader<R> {
+    type Item = Result<HandHistory, CoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.unknown {
+            return None;
+        }
+        let mut lines: Vec<(usize, String)> = self.pending.take().into_iter().collect();
+        let mut started = !lines.is_empty();
+        for next in self.lines.by_ref() {
+            self.line += 1;
+            let text = match next {
+                Ok(text) => text.trim_start_matches('\u{feff}').trim_end().to_string(),
+                Err(e) => return Some(Err(invalid(self.line, format!("read error: {e}")))),
+            };
+            if self.parser.is_none() && !text.is_empty() {
+                self.parser = detect_format(&text);
+                if self.parser.is_none() {
+                    self.unknown = true;
+                    return Some(Err(invalid(self.line, "unrecognised hand history format")));
+                }
+            }
+            if self.parser.as_ref().is_some_and(|p| p.is_hand_start(&text)) {
+                if started {
+                    self.pending = Some((self.line, text));
+                    break;
+                }
+                started = true;
+            }
+            if !(lines.is_empty() && text.is_empty()) {
+                lines.push((self.line, text));
+            }
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        Some(self.parser.as_mut()?.parse_hand(&lines))
+    }
+}
+
+/// Assembles a [`HandHistory`] while a room's lines are parsed, tracking
+/// street commitments so every action knows what it added.
+pub(crate) struct HandBuilder {
+    hand: HandHistory,
+    street: Street,
+    committed: Vec<Chips>,
+    /// Chips each seat has left behind.
+    remaining: Vec<Chips>,
+}
+
+impl HandBuilder {
+    pub(crate) fn new(site: &str, hand_id: String, big_blind: f64) -> Self {
+        HandBuilder {
+            hand: HandHistory {
+                site: site.to_string(),
+                hand_id,
+                game_type: GameType::CashNLH,
+                tournament: None,
+                currency: None,
+                date: String::new(),
+                table: String::new(),
+                max_seats: 0,
+                button_seat: 0,
+                big_blind,
+                blinds: Blinds {
+                    small: Chips::ZERO,
+                    big: Chips::from_f64(big_blind),
+                    ante: Chips::ZERO,
+                },
+                seats: Vec::new(),
+                hero: None,
+                posts: Vec::new(),
+                actions: Vec::new(),
+                board: Board::new(),
+                returned: Vec::new(),
+                collected: Vec::new(),
+                total_pot: Chips::ZERO,
+                rake: Chips::ZERO,
+                jackpot: Chips::ZERO,
+            },
+            street: Street::Preflop,
+            committed: Vec::new(),
+            remaining: Vec::new(),
+        }
+    }
+
+    pub(crate) fn hand_mut(&mut self) -> &mut HandHistory {
+        &mut self.hand
+    }
+
+    /// An amount as the room wrote it.
+    pub(crate) fn chips(&self, amount: f64) -> Chips {
+        Chips::from_f64(amount)
+    }
+
+    pub(crate) fn add_seat(&mut self, seat: u8, player: String, stack: Chips, sitting_out: bool) {
+        self.hand.seats.push(HandSeat {
+            seat,
+            player,
+            stack,
+            position: None,
+            cards: None,
+            sitting_out,
+            bounty: None,
+        });
+        self.committed.push(Chips::ZERO);
+        self.remaining.push(stack);
+    }
+
+    /// Seat index of the player a line starts with, longest name first so
+    /// that "Bob" doesn't match a line from "Bobby".
+    pub(crate) fn find_player<'a>(&self, line: &'a str) -> Option<(usize, &'a str)> {
+        self.hand
+            .seats
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| line.starts_with(s.player.as_str()))
+            .max_by_key(|(_, s)| s.player.len())
+            .map(|(i, s)| (i, &line[s.player.len()..]))
+    }
+
+    pub(crate) fn post(&mut self, seat: usize, kind: PostKind, amount: Chips) {
+        let live = match kind {
+            PostKind::Ante => Chips::ZERO,
+            PostKind::BothBlinds => amount.min(self.hand.blinds.big),
+            _ => amount,
+        };
+        self.committed[seat] += live;
+        self.remaining[seat] -= amount;
+        self.hand.posts.push(Post { seat, kind, amount });
+    }
+
+    pub(crate) fn start_street(
+        &mut self,
+        street: Street,
+        cards: &[Card],
+        line: usize,
+    ) -> Result<(), CoreError> {
+        for &card in cards {
+            if self.hand.board.mask() & card.mask() != 0 {
+                return Err(invalid(line, format!("{card} is already on the board")));
+            }
+            self.hand
+                .board
+                .add_card(card)
+                .map_err(|e| invalid(line, e.to_string()))?;
+        }
+        if self.hand.board.street() != street {
+            return Err(invalid(
+                line,
+                format!("board {} does not match the {street:?}", self.hand.board),
+            ));
+        }
+        self.street = street;
+        self.committed.iter_mut().for_each(|c| *c = Chips::ZERO);
+        Ok(())
+    }
+
+    /// Record a fold or check.
+    pub(crate) fn passive(&mut self, seat: usize, action: Action) {
+        self.push(seat, action, Chips::ZERO, false);
+    }
+
+    /// Record a call that added `amount`.
+    pub(crate) fn call(&mut self, seat: usize, amount: Chips, all_in: bool) {
+        self.committed[seat] += amount;
+        self.push(seat, Action::Call, amount, all_in);
+    }
+
+    /// Record a bet that added `amount`.
+    pub(crate) fn bet(&mut self, seat: usize, amount: Chips, all_in: bool) {
+        let total = self.committed[seat] + amount;
+        self.committed[seat] = total;
+        let action = if all_in {
+            Action::AllIn(total)
+        } else {
+            Action::Bet(total)
+        };
+        self.push(seat, action, amount, all_in);
+    }
+
+    /// Record a raise to a street total of `total`.
+    pub(crate) fn raise_to(&mut self, seat: usize, total: Chips, all_in: bool) {
+        let amount = total - self.committed[seat];
+        self.committed[seat] = total;
+        let action = if all_in {
+            Action::AllIn(total)
+        } else {
+            Action::Raise(total)
+        };
+        self.push(seat, action, amount, all_in);
+    }
+
+    /// Record chips put in by a room that writes only the amount added,
+    /// working out whether it called, bet or raised. A seat left with no
+    /// chips is all-in whether or not the room says so.
+    pub(crate) fn add(&mut self, seat: usize, amount: Chips, all_in: bool) {
+        let all_in = all_in || self.remaining[seat] <= amount;
+        let facing = self.committed.iter().copied().max().unwrap_or_default();
+        let total = self.committed[seat] + amount;
+        if total <= facing {
+            self.call(seat, amount, all_in);
+        } else if facing.is_zero() {
+            self.bet(seat, amount, all_in);
+        } else {
+            self.raise_to(seat, total, all_in);
+        }
+    }
+
+    /// Give back the part of the last bet nobody called, for rooms that
+    /// leave it out of the history.
+    pub(crate) fn return_uncalled(&mut self) {
+        if !self.hand.returned.is_empty() {
+            return;
+        }
+        let Some(top) = (0..self.committed.len()).max_by_key(|&i| self.committed[i]) else {
+            return;
+        };
+        let called = (0..self.committed.len())
+            .filter(|&i| i != top)
+            .map(|i| self.committed[i])
+            .max()
+            .unwrap_or_default();
+        let excess = self.committed[top] - called;
+        if excess.is_positive() {
+            self.committed[top] = called;
+            self.remaining[top] += excess;
+            self.hand.returned.push(Collected {
+                seat: top,
+                amount: excess,
+            });
+        }
+    }
+
+    /// Work out the pot and rake for rooms that don't print them: the pot
+    /// is everything invested and the rake whatever nobody collected.
+    pub(crate) fn derive_totals(&mut self) {
+        let hand = &mut self.hand;
+        hand.total_pot = (0..hand.seats.len()).map(|s| hand.invested(s)).sum();
+        let collected: Chips = hand.collected.iter().map(|c| c.amount).sum();
+        hand.rake = hand.total_pot - collected;
+    }
+
+    fn push(&mut self, seat: usize, action: Action, amount: Chips, all_in: bool) {
+        self.remaining[seat] -= amount;
+        self.hand.actions.push(HandAction {
+            street: self.street,
+            seat,
+            action,
+            amount,
+            all_in,
+        });
+    }
+
+    pub(crate) fn show(&mut self, seat: usize, cards: [Card; 2]) {
+        self.hand.seats[seat].cards = Some(Hand::new(cards[0], cards[1]));
+    }
+
+    /// Assign positions from the button and return the hand.
+    pub(crate) fn finish(mut self, line: usize) -> Result<HandHistory, CoreError> {
+        let hand = &mut self.hand;
+        let mut active: Vec<usize> = (0..hand.seats.len())
+            .filter(|&i| !hand.seats[i].sitting_out)
+            .collect();
+        if active.len() < 2 {
+            return Err(invalid(line, "fewer than two players were dealt in"));
+        }
+        active.sort_by_key(|&i| hand.seats[i].seat);
+        // With a dead button, the first live seat at or after it acts as button
+        let first = active
+            .iter()
+            .position(|&i| hand.seats[i].seat >= hand.button_seat)
+            .unwrap_or(0);
+        active.rotate_left(first);
+        let positions = Position::clockwise_from_button(active.len())
+            .ok_or_else(|| invalid(line, format!("{} players is more than 9", active.len())))?;
+        for (&i, position) in active.iter().zip(positions) {
+            hand.seats[i].position = Some(position);
+        }
+        Ok(self.hand)
+    }
+}
+
+/// Cards in the last `[..]` group of a line, e.g. `[As 7d 2c] [Kc]` -> Kc.
+pub(crate) fn last_bracket_cards(text: &str) -> Option<Result<Vec<Card>, CoreError>> {
+    let start = text.rfind('[')?;
+    let end = start + text[start..].find(']')?;
+    Some(
+        text[start + 1..end]
+            .split_whitespace()
+            .map(Card::from_str_notation)
+            .collect(),
+    )
+}
+
+/// Bounty from a seat line ending `..., $1.25 bounty)`, if there is one.
+pub(crate) fn parse_bounty(text: &str) -> Option<f64> {
+    let (before, _) = text.rsplit_once(" bounty")?;
+    let amount = before.rsplit([' ', ',', '(']).next()?;
+    parse_amount(amount).ok()
+}
+
+/// Parse an amount such as `$1,234.50`, `2€` or `1500`.
+pub(crate) fn parse_amount(text: &str) -> Result<f64, String> {
+    let cleaned: String = text
+        .trim()
+        .trim_matches(['$', '€', '£'])
+        .chars()
+        .filter(|&c| c != ',')
+        .collect();
+    match cleaned.parse::<f64>() {
+        Ok(v) if v.is_finite() && v >= 0.0 => Ok(v),
+        _ => Err(format!("invalid amount '{}'", text.trim())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CASH: &str = include_str!("../../tests/fixtures/hand_history/pokerstars_cash.txt");
+    const TOURNAMENT: &str =
+        include_str!("../../tests/fixtures/hand_history/pokerstars_tournament.txt");
+
+    fn c(amount: f64) -> Chips {
+        Chips::from(amount)
+    }
+
+    fn card(s: &str) -> Card {
+        Card::from_str_notation(s).expect("valid card")
+    }
+
+    #[test]
+    fn test_cash_hand() {
+        let hands: Vec<_> = HandHistoryReader::new(CASH.as_bytes()).collect();
+        assert_eq!(hands.len(), 2);
+        let hand = hands[0].as_ref().expect("valid hand");
+
+        assert_eq!(hand.site, "PokerStars");
+        assert_eq!(hand.hand_id, "243920174392");
+        assert_eq!(hand.game_type, GameType::CashNLH);
+        assert_eq!(hand.currency.as_deref(), Some("USD"));
+        assert_eq!(hand.table, "Aase III");
+        assert_eq!(hand.max_seats, 6);
+        assert_eq!(hand.big_blind, 0.1);
+        assert_eq!(hand.blinds.small, c(0.05));
+
+        assert_eq!(hand.seats.len(), 6);
+        let hero = hand.hero.expect("hero");
+        assert_eq!(hand.seats[hero].player, "Hero");
+        assert_eq!(hand.seats[hero].position, Some(Position::BB));
+        assert_eq!(hand.seats[hero].stack, c(12.0));
+        assert_eq!(
+            hand.seats[hero].cards,
+            Some(Hand::new(card("Ah"), card("Kh")))
+        );
+        let bob = hand.seat_of("Bob").expect("Bob");
+        assert_eq!(hand.seats[bob].position, Some(Position::CO));
+        assert_eq!(
+            hand.seats[bob].cards,
+            Some(Hand::new(card("Qs"), card("Qd")))
+        );
+        let carol = hand.seat_of("Carol").expect("Carol");
+        assert!(hand.seats[carol].sitting_out);
+        assert_eq!(hand.seats[carol].position, None);
+
+        let preflop: Vec<Action> = hand
+            .street_actions(Street::Preflop)
+            .map(|a| a.action)
+            .collect();
+        assert_eq!(
+            preflop,
+            vec![
+                Action::Fold,
+                Action::Raise(c(0.3)),
+                Action::Fold,
+                Action::Fold,
+                Action::Raise(c(1.1)),
+                Action::Call
+            ]
+        );
+        let turn: Vec<_> = hand.street_actions(Street::Turn).collect();
+        assert_eq!(turn[1].action, Action::AllIn(c(7.74)));
+        assert!(turn[1].all_in);
+        assert_eq!(turn[2].amount, c(4.64));
+
+        assert_eq!(hand.board.to_string(), "[As 7d 2c Kc 3h]");
+        assert_eq!(hand.total_pot, c(20.13));
+        assert_eq!(hand.rake, c(0.94));
+        assert_eq!(
+            hand.collected,
+            vec![Collected {
+                seat: hero,
+                amount: c(19.19)
+            }]
+        );
+        assert_eq!(hand.net(hero), c(9.15));
+        assert!((hand.in_bb(hand.net(hero)) - 91.5).abs() < 1e-9);
+        assert_eq!(hand.net(bob), c(-10.04));
+        let eve = hand.seat_of("Eve").expect("Eve");
+        assert_eq!(hand.net(eve), c(-0.05));
+    }
+
+    #[test]
+    fn test_uncalled_bet_returned() {
+        let hand = HandHistoryReader::new(CASH.as_bytes())
+            .nth(1)
+            .expect("second hand")
+            .expect("valid hand");
+        assert_eq!(hand.hand_id, "243920174401");
+        assert_eq!(hand.seats.len(), 2);
+        let hero = hand.hero.expect("hero");
+        // Heads-up: the button is also the small blind
+        assert_eq!(hand.seats[hero].position, Some(Position::BTN));
+        assert_eq!(hand.posts[0].kind, PostKind::SmallBlind);
+        assert_eq!(
+            hand.returned,
+            vec![Collected {
+                seat: hero,
+                amount: c(0.2)
+            }]
+        );
+        assert_eq!(hand.net(hero), c(0.1));
+        assert!(hand.board.is_empty());
+    }
+
+    #[test]
+    fn test_tournament_hand() {
+        let hand = HandHistory::parse(TOURNAMENT).expect("valid hand");
+        assert_eq!(hand.game_type, GameType::MTT);
+        let info = hand.tournament.as_ref().expect("tournament");
+        assert_eq!(info.id, "3456789012");
+        assert_eq!(info.buy_in.as_deref(), Some("$4.40+$0.60"));
+        assert_eq!(info.level.as_deref(), Some("IV"));
+        assert_eq!(hand.currency, None);
+        assert_eq!(hand.big_blind, 100.0);
+        assert_eq!(hand.blinds.ante, c(10.0));
+        assert_eq!(hand.seats.len(), 9);
+        assert_eq!(
+            hand.posts
+                .iter()
+                .filter(|p| p.kind == PostKind::Ante)
+                .count(),
+            9
+        );
+        // 9-handed with the button on seat 2: seat 5 is under the gun
+        let utg = hand.seats.iter().find(|s| s.seat == 5).expect("seat 5");
+        assert_eq!(utg.position, Some(Position::UTG));
+        let stack = hand
+            .seats
+            .iter()
+            .find(|s| s.seat == 1)
+            .expect("seat 1")
+            .stack;
+        assert_eq!(stack, c(5250.0));
+        let total: Chips = hand.collected.iter().map(|c| c.amount).sum();
+        assert_eq!(total, hand.total_pot);
+        let nets: Chips = (0..hand.seats.len()).map(|s| hand.net(s)).sum();
+        assert_eq!(nets, Chips::ZERO);
+    }
+
+    #[test]
+    fn test_malformed_hands_report_lines() {
+        let broken = CASH.replacen("Bob: raises $0.20 to $0.30", "Bob: raises lots", 1);
+        let results: Vec<_> = HandHistoryReader::new(broken.as_bytes()).collect();
+        assert_eq!(results.len(), 2);
+        match &results[0] {
+            Err(CoreError::InvalidHandHistory { line, message }) => {
+                assert_eq!(*line, 14);
+                assert!(message.contains("raise"), "{message}");
+            }
+            other => panic!("expected an error, got {other:?}"),
+        }
+        // Reading carries on with the next hand
+        assert!(results[1].is_ok());
+
+        let bad_board = CASH.replacen("[As 7d 2c] [Kc]", "[As 7d 2c] [As]", 1);
+        let err = HandHistory::parse(&bad_board).expect_err("duplicate card");
+        assert!(
+            matches!(err, CoreError::InvalidHandHistory { line: 22, .. }),
+            "{err}"
+        );
+
+        assert!(HandHistory::parse("").is_err());
+        assert!(HandHistory::parse("hello\nworld").is_err());
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let files = [
+            (CASH, "PokerStars"),
+            (
+                include_str!("../../tests/fixtures/hand_history/ggpoker.txt"),
+                "GGPoker",
+            ),
+            (
+                include_str!("../../tests/fixtures/hand_history/poker888.txt"),
+                "888poker",
+            ),
+            (
+                include_str!("../../tests/fixtures/hand_history/winamax.txt"),
+                "Winamax",
+            ),
+            (
+                include_str!("../../tests/fixtures/hand_history/ipoker.xml"),
+                "iPoker",
+            ),
+        ];
+        for (text, site) in files {
+            let padded = format!("\u{feff}\n\n{text}");
+            let mut reader = HandHistoryReader::new(padded.as_bytes());
+            let hands: Vec<_> = reader.by_ref().collect::<Result<_, _>>().expect(site);
+            assert_eq!(reader.site(), Some(site));
+            assert_eq!(hands.len(), 2, "{site}");
+            assert!(hands.iter().all(|h| h.site == site));
+        }
+
+        let mut unknown = HandHistoryReader::new("\nFull Tilt Poker Game #1\nmore\n".as_bytes());
+        assert!(matches!(
+            unknown.next(),
+            Some(Err(CoreError::InvalidHandHistory { line: 2, .. }))
+        ));
+        assert!(unknown.next().is_none());
+
+        // An explicit parser skips detection
+        let forced = HandHistoryReader::with_parser(CASH.as_bytes(), Box::new(WinamaxParser));
+        assert!(forced.take(1).all(|h| h.is_err()));
+    }
+}
@@ -0,0 +1,1038 @@
+//! Open Hand History (OHH) JSON, the interchange format trackers read.
+//!
+//! OHH amounts are in the room's currency or chips, like a `HandHistory`'s,
+//! so they carry over unchanged. Action amounts are the chips each action added. OHH has one pot list
+//! and no blind level, so a hand exports as a single pot and a tournament
+//! level is not kept. Rooms print local time; `start_date_utc` is converted
+//! when the room names a zone we know the rules for, and left out otherwise.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::action::Action;
+use crate::board::Street;
+use crate::card::Card;
+use crate::chips::Chips;
+use crate::error::CoreError;
+use crate::game_type::GameType;
+
+use super::{parse_amount, Collected, HandBuilder, HandHistory, PostKind, TournamentInfo};
+
+/// Spec version written on export.
+pub const OHH_SPEC_VERSION: &str = "1.4.6";
+
+/// One entry of an OHH file: `{"ohh": {...}}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OhhFile {
+    pub ohh: Ohh,
+}
+
+/// A hand in OHH form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ohh {
+    pub spec_version: String,
+    pub site_name: String,
+    #[serde(default)]
+    pub network_name: String,
+    #[serde(default)]
+    pub internal_version: String,
+    #[serde(default)]
+    pub tournament: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tournament_info: Option<OhhTournamentInfo>,
+    pub game_number: String,
+    /// ISO-8601 UTC, e.g. `2023-03-12T18:31:07Z`. Empty when the room's
+    /// timezone is unknown.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub start_date_utc: String,
+    #[serde(default)]
+    pub table_name: String,
+    pub game_type: String,
+    pub bet_limit: OhhBetLimit,
+    pub table_size: usize,
+    #[serde(default)]
+    pub currency: String,
+    pub dealer_seat: u8,
+    pub small_blind_amount: f64,
+    pub big_blind_amount: f64,
+    #[serde(default)]
+    pub ante_amount: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hero_player_id: Option<u32>,
+    #[serde(default)]
+    pub flags: Vec<String>,
+    pub players: Vec<OhhPlayer>,
+    pub rounds: Vec<OhhRound>,
+    #[serde(default)]
+    pub pots: Vec<OhhPot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OhhBetLimit {
+    pub bet_type: String,
+    #[serde(default)]
+    pub bet_cap: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OhhTournamentInfo {
+    pub tournament_number: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub start_date_utc: String,
+    #[serde(default)]
+    pub currency: String,
+    #[serde(default)]
+    pub buyin_amount: f64,
+    #[serde(default)]
+    pub fee_amount: f64,
+    #[serde(default)]
+    pub bounty_fee_amount: f64,
+    #[serde(default)]
+    pub initial_stack: f64,
+    /// `MTT` or `STT`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OhhPlayer {
+    pub id: u32,
+    pub seat: u8,
+    pub name: String,
+    #[serde(default)]
+    pub display: String,
+    pub starting_stack: f64,
+    #[serde(default)]
+    pub player_bounty: f64,
+    #[serde(default)]
+    pub is_sitting_out: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OhhRound {
+    pub id: u32,
+    /// `Preflop`, `Flop`, `Turn`, `River` or `Showdown`.
+    pub street: String,
+    /// Board cards dealt this round.
+    #[serde(default)]
+    pub cards: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<OhhAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OhhAction {
+    pub action_number: u32,
+    pub player_id: u32,
+    /// e.g. `Post SB`, `Dealt Cards`, `Raise`, `Shows Cards`.
+    pub action: String,
+    /// Chips the action added.
+    #[serde(default)]
+    pub amount: f64,
+    #[serde(default)]
+    pub is_allin: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cards: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OhhPot {
+    pub number: u32,
+    /// Everything in the pot, rake included.
+    pub amount: f64,
+    #[serde(default)]
+    pub rake: f64,
+    #[serde(default)]
+    pub jackpot: f64,
+    pub player_wins: Vec<OhhPlayerWin>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OhhPlayerWin {
+    pub player_id: u32,
+    pub win_amount: f64,
+    #[serde(default)]
+    pub contributed_rake: f64,
+}
+
+const STREETS: [(&str, Option<Street>); 5] = [
+    ("Preflop", Some(Street::Preflop)),
+    ("Flop", Some(Street::Flop)),
+    ("Turn", Some(Street::Turn)),
+    ("River", Some(Street::River)),
+    ("Showdown", None),
+];
+
+/// Actions that move no chips and that import skips.
+const IGNORED_ACTIONS: [&str; 5] = [
+    "Sits Down",
+    "Stands Up",
+    "Sits Out",
+    "Sits In",
+    "Added Chips",
+];
+
+impl HandHistory {
+    /// Convert to OHH.
+    pub fn to_ohh(&self) -> Ohh {
+        let money = |c: Chips| c.to_f64();
+        let id = |seat: usize| seat as u32 + 1;
+        let mut number = 0;
+        let mut next = || {
+            number += 1;
+            number
+        };
+
+        let mut preflop = Vec::new();
+        for post in &self.posts {
+            let action = match post.kind {
+                PostKind::SmallBlind => "Post SB",
+                PostKind::BigBlind => "Post BB",
+                PostKind::BothBlinds => "Post Dead",
+                PostKind::Ante => "Post Ante",
+                PostKind::Straddle => "Straddle",
+            };
+            preflop.push(OhhAction {
+                action_number: next(),
+                player_id: id(post.seat),
+                action: action.to_string(),
+                amount: money(post.amount),
+                is_allin: false,
+                cards: Vec::new(),
+            });
+        }
+        if let Some(hero) = self.hero {
+            if let Some(cards) = self.seats[hero].cards {
+                preflop.push(OhhAction {
+                    action_number: next(),
+                    player_id: id(hero),
+                    action: "Dealt Cards".to_string(),
+                    amount: 0.0,
+                    is_allin: false,
+                    cards: cards.cards().iter().map(Card::to_string).collect(),
+                });
+            }
+        }
+
+        let board = self.board.cards();
+        let mut rounds = Vec::new();
+        let betting = [Street::Preflop, Street::Flop, Street::Turn, Street::River];
+        for (round, street) in betting.into_iter().enumerate() {
+            let cards: &[Card] = match street {
+                Street::Preflop => &[],
+                Street::Flop => board.get(..3).unwrap_or_default(),
+                Street::Turn => board.get(3..4).unwrap_or_default(),
+                Street::River => board.get(4..5).unwrap_or_default(),
+            };
+            let mut actions = if street == Street::Preflop {
+                std::mem::take(&mut preflop)
+            } else {
+                Vec::new()
+            };
+            // An all-in is a bet unless someone bet first; preflop the blinds have
+            let mut opened = street == Street::Preflop;
+            for a in self.street_actions(street) {
+                let action = match a.action {
+                    Action::Fold => "Fold",
+                    Action::Check => "Check",
+                    Action::Call => "Call",
+                    Action::Bet(_) => "Bet",
+                    Action::Raise(_) => "Raise",
+                    Action::AllIn(_) if opened => "Raise",
+                    Action::AllIn(_) => "Bet",
+                };
+                opened |= matches!(
+                    a.action,
+                    Action::Bet(_) | Action::Raise(_) | Action::AllIn(_)
+                );
+                actions.push(OhhAction {
+                    action_number: next(),
+                    player_id: id(a.seat),
+                    action: action.to_string(),
+                    amount: money(a.amount),
+                    is_allin: a.all_in,
+                    cards: Vec::new(),
+                });
+            }
+            if street != Street::Preflop && cards.is_empty() && actions.is_empty() {
+                break;
+            }
+            rounds.push(OhhRound {
+                id: round as u32,
+                street: STREETS[round].0.to_string(),
+                cards: cards.iter().map(Card::to_string).collect(),
+                actions,
+            });
+        }
+
+        // Hero shows only when someone else did and hero was still in
+        let villain_shown =
+            (0..self.seats.len()).any(|s| Some(s) != self.hero && self.seats[s].cards.is_some());
+        let shows: Vec<OhhAction> = (0..self.seats.len())
+            .filter(|&s| {
+                let still_in = !self
+                    .actions
+                    .iter()
+                    .any(|a| a.seat == s && a.action == Action::Fold);
+                Some(s) != self.hero || (villain_shown && still_in)
+            })
+            .filter_map(|s| self.seats[s].cards.map(|c| (s, c)))
+            .map(|(s, cards)| OhhAction {
+                action_number: next(),
+                player_id: id(s),
+                action: "Shows Cards".to_string(),
+                amount: 0.0,
+                is_allin: false,
+                cards: cards.cards().iter().map(Card::to_string).collect(),
+            })
+            .collect();
+        if !shows.is_empty() {
+            rounds.push(OhhRound {
+                id: rounds.len() as u32,
+                street: "Showdown".to_string(),
+                cards: Vec::new(),
+                actions: shows,
+            });
+        }
+
+        let mut wins: Vec<OhhPlayerWin> = Vec::new();
+        for c in &self.collected {
+            match wins.iter_mut().find(|w| w.player_id == id(c.seat)) {
+                Some(win) => win.win_amount = round_money(win.win_amount + money(c.amount)),
+                None => wins.push(OhhPlayerWin {
+                    player_id: id(c.seat),
+                    win_amount: money(c.amount),
+                    contributed_rake: 0.0,
+                }),
+            }
+        }
+
+        let tournament_info = self.tournament.as_ref().map(|info| {
+            let parts: Option<Vec<f64>> = info
+                .buy_in
+                .as_deref()
+                .map(|b| b.split('+').map(|p| parse_amount(p).ok()).collect())
+                .unwrap_or_default();
+            let part = |i: usize| parts.as_ref().and_then(|p| p.get(i)).copied();
+            OhhTournamentInfo {
+                tournament_number: info.id.clone(),
+                // A buy-in that isn't `buy-in+fee[+bounty]` is kept as the name
+                name: match parts {
+                    Some(_) => String::new(),
+                    None => info.buy_in.clone().unwrap_or_default(),
+                },
+                start_date_utc: String::new(),
+                currency: self.currency.clone().unwrap_or_default(),
+                buyin_amount: part(0).unwrap_or_default(),
+                fee_amount: part(1).unwrap_or_default(),
+                bounty_fee_amount: part(2).unwrap_or_default(),
+                initial_stack: 0.0,
+                kind: match self.game_type {
+                    GameType::MTT => "MTT",
+                    _ => "STT",
+                }
+                .to_string(),
+                flags: Vec::new(),
+            }
+        });
+
+        Ohh {
+            spec_version: OHH_SPEC_VERSION.to_string(),
+            site_name: self.site.clone(),
+            network_name: self.site.clone(),
+            internal_version: env!("CARGO_PKG_VERSION").to_string(),
+            tournament: self.tournament.is_some(),
+            tournament_info,
+            game_number: self.hand_id.clone(),
+            start_date_utc: utc_date(&self.date).unwrap_or_default(),
+            table_name: self.table.clone(),
+            game_type: "Holdem".to_string(),
+            bet_limit: OhhBetLimit {
+                bet_type: "NL".to_string(),
+                bet_cap: 0.0,
+            },
+            table_size: self.max_seats,
+            currency: self.currency.clone().unwrap_or_default(),
+            dealer_seat: self.button_seat,
+            small_blind_amount: money(self.blinds.small),
+            big_blind_amount: self.big_blind,
+            ante_amount: money(self.blinds.ante),
+            hero_player_id: self.hero.map(id),
+            flags: Vec::new(),
+            players: self
+                .seats
+                .iter()
+                .enumerate()
+                .map(|(i, s)| OhhPlayer {
+                    id: id(i),
+                    seat: s.seat,
+                    name: s.player.clone(),
+                    display: s.player.clone(),
+                    starting_stack: money(s.stack),
+                    player_bounty: s.bounty.unwrap_or_default(),
+                    is_sitting_out: s.sitting_out,
+                })
+                .collect(),
+            rounds,
+            pots: vec![OhhPot {
+                number: 0,
+                amount: money(self.total_pot),
+                rake: money(self.rake - self.jackpot),
+                jackpot: money(self.jackpot),
+                player_wins: wins,
+            }],
+        }
+    }
+
+    /// Convert to an OHH file entry.
+    pub fn to_ohh_json(&self) -> Result<String, CoreError> {
+        let file = OhhFile { ohh: self.to_ohh() };
+        serde_json::to_string_pretty(&file).map_err(|e| CoreError::InvalidOhh(e.to_string()))
+    }
+
+    /// Read an OHH file entry, `{"ohh": {...}}`.
+    pub fn from_ohh_json(json: &str) -> Result<Self, CoreError> {
+        let file: OhhFile =
+            serde_json::from_str(json).map_err(|e| CoreError::InvalidOhh(e.to_string()))?;
+        HandHistory::from_ohh(&file.ohh)
+    }
+
+    /// Convert from OHH, checking it against the spec. Errors name the
+    /// offending field, e.g. `rounds[1].actions[0].player_id`.
+    pub fn from_ohh(ohh: &Ohh) -> Result<Self, CoreError> {
+        validate_header(ohh)?;
+        let mut builder = HandBuilder::new(
+            &ohh.site_name,
+            ohh.game_number.clone(),
+            ohh.big_blind_amount,
+        );
+        let small = builder.chips(ohh.small_blind_amount);
+        let ante = builder.chips(ohh.ante_amount);
+        let hand = builder.hand_mut();
+        hand.blinds.small = small;
+        hand.blinds.ante = ante;
+        hand.date = ohh.start_date_utc.clone();
+        hand.table = ohh.table_name.clone();
+        hand.max_seats = ohh.table_size;
+        hand.button_seat = ohh.dealer_seat;
+        hand.currency = Some(ohh.currency.clone()).filter(|c| !c.is_empty());
+        if let Some(info) = &ohh.tournament_info {
+            hand.game_type = match info.kind.as_str() {
+                "MTT" => GameType::MTT,
+                _ => GameType::SNG,
+            };
+            let amounts = [info.buyin_amount, info.fee_amount, info.bounty_fee_amount];
+            let buy_in = if amounts.iter().any(|&a| a > 0.0) {
+                let shown = if info.bounty_fee_amount > 0.0 { 3 } else { 2 };
+                Some(
+                    amounts[..shown]
+                        .iter()
+                        .map(|a| format!("{a:.2}"))
+                        .collect::<Vec<_>>()
+                        .join("+"),
+                )
+            } else {
+                Some(info.name.clone()).filter(|n| !n.is_empty())
+            };
+            hand.tournament = Some(TournamentInfo {
+                id: info.tournament_number.clone(),
+                buy_in,
+                level: None,
+            });
+        }
+
+        let mut seats = HashMap::new();
+        for p in &ohh.players {
+            seats.insert(p.id, seats.len());
+            let stack = builder.chips(p.starting_stack);
+            builder.add_seat(p.seat, p.name.clone(), stack, p.is_sitting_out);
+            if let Some(last) = builder.hand_mut().seats.last_mut() {
+                last.bounty = Some(p.player_bounty).filter(|&b| b > 0.0);
+            }
+        }
+        builder.hand_mut().hero = ohh.hero_player_id.map(|id| seats[&id]);
+
+        let mut last_street = None;
+        let mut last_number = 0;
+        for (r, round) in ohh.rounds.iter().enumerate() {
+            let path = format!("rounds[{r}]");
+            let position = STREETS
+                .iter()
+                .position(|(name, _)| *name == round.street)
+                .ok_or_else(|| {
+                    spec(
+                        format!("{path}.street"),
+                        format!("unknown street '{}'", round.street),
+                    )
+                })?;
+            if last_street.is_some_and(|last| position <= last) {
+                return Err(spec(&path, format!("{} out of order", round.street)));
+            }
+            last_street = Some(position);
+
+            let cards = parse_cards(&format!("{path}.cards"), &round.cards)?;
+            let expected = match STREETS[position].1 {
+                Some(Street::Flop) => 3,
+                Some(Street::Turn) | Some(Street::River) => 1,
+                _ => 0,
+            };
+            if cards.len() != expected {
+                return Err(spec(
+                    format!("{path}.cards"),
+                    format!(
+                        "{} deals {expected} cards, got {}",
+                        round.street,
+                        cards.len()
+                    ),
+                ));
+            }
+            if let Some(street) = STREETS[position].1.filter(|&s| s != Street::Preflop) {
+                builder
+                    .start_street(street, &cards, 0)
+                    .map_err(reword(format!("{path}.cards")))?;
+            }
+
+            for (a, action) in round.actions.iter().enumerate() {
+                let path = format!("{path}.actions[{a}]");
+                if action.action_number <= last_number {
+                    return Err(spec(
+                        format!("{path}.action_number"),
+                        format!("{} does not follow {last_number}", action.action_number),
+                    ));
+                }
+                last_number = action.action_number;
+                let seat = *seats.get(&action.player_id).ok_or_else(|| {
+                    spec(
+                        format!("{path}.player_id"),
+                        format!("no player with id {}", action.player_id),
+                    )
+                })?;
+                if !(action.amount.is_finite() && action.amount >= 0.0) {
+                    return Err(spec(
+                        format!("{path}.amount"),
+                        "must be a non-negative number",
+                    ));
+                }
+                let amount = builder.chips(action.amount);
+                apply_action(&mut builder, &path, seat, action, amount)?;
+            }
+        }
+        builder.return_uncalled();
+
+        if ohh.pots.is_empty() {
+            builder.derive_totals();
+        } else {
+            let (mut total, mut rake, mut jackpot) = (0.0, 0.0, 0.0);
+            for (p, pot) in ohh.pots.iter().enumerate() {
+                let path = format!("pots[{p}]");
+                if [pot.amount, pot.rake, pot.jackpot]
+                    .iter()
+                    .any(|v| !(v.is_finite() && *v >= 0.0))
+                {
+                    return Err(spec(&path, "amounts must be non-negative numbers"));
+                }
+                total += pot.amount;
+                rake += pot.rake + pot.jackpot;
+                jackpot += pot.jackpot;
+                for (w, win) in pot.player_wins.iter().enumerate() {
+                    let path = format!("{path}.player_wins[{w}]");
+                    let seat = *seats.get(&win.player_id).ok_or_else(|| {
+                        spec(
+                            format!("{path}.player_id"),
+                            format!("no player with id {}", win.player_id),
+                        )
+                    })?;
+                    if !(win.win_amount.is_finite() && win.win_amount >= 0.0) {
+                        return Err(spec(
+                            format!("{path}.win_amount"),
+                            "must be a non-negative number",
+                        ));
+                    }
+                    let amount = builder.chips(win.win_amount);
+                    if amount.is_positive() {
+                        builder
+                            .hand_mut()
+                            .collected
+                            .push(Collected { seat, amount });
+                    }
+                }
+            }
+            let (total, rake, jackpot) = (
+                builder.chips(total),
+                builder.chips(rake),
+                builder.chips(jackpot),
+            );
+            let hand = builder.hand_mut();
+            hand.total_pot = total;
+            hand.rake = rake;
+            hand.jackpot = jackpot;
+        }
+        builder.finish(0).map_err(reword("players"))
+    }
+}
+
+/// Checks on everything outside the rounds and pots.
+fn validate_header(ohh: &Ohh) -> Result<(), CoreError> {
+    if !ohh.spec_version.starts_with("1.") {
+        return Err(spec(
+            "spec_version",
+            format!("unsupported version '{}'", ohh.spec_version),
+        ));
+    }
+    if ohh.game_number.is_empty() {
+        return Err(spec("game_number", "must not be empty"));
+    }
+    if ohh.game_type != "Holdem" {
+        return Err(spec(
+            "game_type",
+            format!("only Holdem is supported, got '{}'", ohh.game_type),
+        ));
+    }
+    if ohh.bet_limit.bet_type != "NL" {
+        return Err(spec(
+            "bet_limit.bet_type",
+            format!("only NL is supported, got '{}'", ohh.bet_limit.bet_type),
+        ));
+    }
+    if !(ohh.big_blind_amount.is_finite() && ohh.big_blind_amount > 0.0) {
+        return Err(spec("big_blind_amount", "must be positive"));
+    }
+    for (name, value) in [
+        ("small_blind_amount", ohh.small_blind_amount),
+        ("ante_amount", ohh.ante_amount),
+    ] {
+        if !(value.is_finite() && value >= 0.0) {
+            return Err(spec(name, "must be a non-negative number"));
+        }
+    }
+    if ohh.tournament && ohh.tournament_info.is_none() {
+        return Err(spec("tournament_info", "required when tournament is true"));
+    }
+    if ohh.players.len() < 2 {
+        return Err(spec("players", "a hand needs at least two players"));
+    }
+    for (i, p) in ohh.players.iter().enumerate() {
+        let path = format!("players[{i}]");
+        if ohh.players[..i].iter().any(|q| q.id == p.id) {
+            return Err(spec(format!("{path}.id"), format!("duplicate id {}", p.id)));
+        }
+        if ohh.players[..i].iter().any(|q| q.seat == p.seat) {
+            return Err(spec(
+                format!("{path}.seat"),
+                format!("seat {} taken twice", p.seat),
+            ));
+        }
+        if p.name.is_empty() {
+            return Err(spec(format!("{path}.name"), "must not be empty"));
+        }
+        if !(p.starting_stack.is_finite() && p.starting_stack >= 0.0) {
+            return Err(spec(
+                format!("{path}.starting_stack"),
+                "must be a non-negative number",
+            ));
+        }
+    }
+    if !ohh.players.iter().any(|p| p.seat == ohh.dealer_seat) {
+        return Err(spec(
+            "dealer_seat",
+            format!("no player in seat {}", ohh.dealer_seat),
+        ));
+    }
+    if let Some(hero) = ohh.hero_player_id {
+        if !ohh.players.iter().any(|p| p.id == hero) {
+            return Err(spec("hero_player_id", format!("no player with id {hero}")));
+        }
+    }
+    Ok(())
+}
+
+fn apply_action(
+    builder: &mut HandBuilder,
+    path: &str,
+    seat: usize,
+    action: &OhhAction,
+    amount: Chips,
+) -> Result<(), CoreError> {
+    let post = |builder: &mut HandBuilder, kind| builder.post(seat, kind, amount);
+    match action.action.as_str() {
+        "Post SB" => post(builder, PostKind::SmallBlind),
+        "Post BB" | "Post Extra Blind" => post(builder, PostKind::BigBlind),
+        "Post Dead" => post(builder, PostKind::BothBlinds),
+        "Post Ante" => post(builder, PostKind::Ante),
+        "Straddle" => post(builder, PostKind::Straddle),
+        "Fold" => builder.passive(seat, Action::Fold),
+        "Check" => builder.passive(seat, Action::Check),
+        "Call" | "Bet" | "Raise" => builder.add(seat, amount, action.is_allin),
+        "Dealt Cards" | "Shows Cards" | "Mucks Cards" => {
+            let path = format!("{path}.cards");
+            match parse_cards(&path, &action.cards)?[..] {
+                [a, b] if a != b => builder.show(seat, [a, b]),
+                // Hidden or partial cards carry nothing we keep
+                [] => {}
+                _ => return Err(spec(path, "expected two hole cards")),
+            }
+        }
+        other if IGNORED_ACTIONS.contains(&other) => {}
+        other => {
+            return Err(spec(
+                format!("{path}.action"),
+                format!("unknown action '{other}'"),
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn parse_cards(path: &str, cards: &[String]) -> Result<Vec<Card>, CoreError> {
+    cards
+        .iter()
+        .map(|c| Card::from_str_notation(c).map_err(|e| spec(path, e)))
+        .collect()
+}
+
+/// Money rounded to a millionth, hiding float noise from the conversion.
+fn round_money(amount: f64) -> f64 {
+    (amount * 1e6).round() / 1e6
+}
+
+/// A room timestamp as ISO-8601 UTC: `2023/03/12 14:31:07 ET` becomes
+/// `2023-03-12T18:31:07Z`. PokerStars may print two zones,
+/// `... CET [2023/03/12 14:31:07 ET]`; the first known one is used. `None`
+/// when no zone is given or it is not one we know the rules for.
+fn utc_date(date: &str) -> Option<String> {
+    date.split(['[', ']']).find_map(|part| {
+        let part = part.trim();
+        let (local, zone) = match part.strip_suffix('Z') {
+            // Already UTC, as read back from OHH
+            Some(iso) => (iso.replacen('T', " ", 1), "UTC"),
+            None => {
+                let (local, zone) = part.rsplit_once(' ')?;
+                (local.to_string(), zone)
+            }
+        };
+        let local = parse_timestamp(&local)?;
+        let offset = zone_offset(zone, local)?;
+        Some(format_timestamp(local - offset))
+    })
+}
+
+/// Seconds since the epoch of `2023/03/12 14:31:07` (or with dashes),
+/// taking the wall clock time as if it were UTC.
+fn parse_timestamp(text: &str) -> Option<i64> {
+    let (date, time) = text.trim().split_once(' ')?;
+    let date: Vec<i64> = date
+        .split(['/', '-'])
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let time: Vec<i64> = time
+        .trim()
+        .split(':')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let (&[y, m, d], &[hh, mm, ss]) = (&date[..], &time[..]) else {
+        return None;
+    };
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) || hh > 23 || mm > 59 || ss > 59 {
+        return None;
+    }
+    Some(days_from_civil(y, m, d) * 86_400 + hh * 3600 + mm * 60 + ss)
+}
+
+fn format_timestamp(seconds: i64) -> String {
+    let (days, secs) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+    let (y, m, d) = civil_from_days(days);
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Offset from UTC in seconds of a zone at the given local time.
+fn zone_offset(zone: &str, local: i64) -> Option<i64> {
+    const HOUR: i64 = 3600;
+    let year = civil_from_days(local.div_euclid(86_400)).0;
+    let at = |day: i64, hour: i64| day * 86_400 + hour * HOUR;
+    match zone {
+        "UTC" | "GMT" => Some(0),
+        // US Eastern: daylight from 2am on the second Sunday of March to
+        // 2am on the first Sunday of November
+        "ET" => {
+            let start = at(nth_sunday(year, 3, 2), 2);
+            let end = at(nth_sunday(year, 11, 1), 2);
+            let hours = if (start..end).contains(&local) {
+                -4
+            } else {
+                -5
+            };
+            Some(hours * HOUR)
+        }
+        // Central European: summer time from 2am on the last Sunday of
+        // March to 3am on the last Sunday of October
+        "CET" => {
+            let start = at(nth_sunday(year, 4, 1) - 7, 2);
+            let end = at(nth_sunday(year, 11, 1) - 7, 3);
+            let hours = if (start..end).contains(&local) { 2 } else { 1 };
+            Some(hours * HOUR)
+        }
+        _ => None,
+    }
+}
+
+/// Day number of the `n`th Sunday of a month.
+fn nth_sunday(year: i64, month: i64, n: i64) -> i64 {
+    let first = days_from_civil(year, month, 1);
+    // 1970-01-01 was a Thursday
+    let weekday = (first + 4).rem_euclid(7);
+    first + (7 - weekday) % 7 + 7 * (n - 1)
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn spec(path: impl Display, message: impl Display) -> CoreError {
+    CoreError::InvalidOhh(format!("{path}: {message}"))
+}
+
+/// Restate a builder error against an OHH field.
+fn reword(path: impl Display) -> impl Fn(CoreError) -> CoreError {
+    move |e| match e {
+        CoreError::InvalidHandHistory { message, .. } => spec(&path, message),
+        other => spec(&path, other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_history::HandHistoryReader;
+
+    const CASH: &str = include_str!("../../tests/fixtures/hand_history/pokerstars_cash.txt");
+    const TOURNAMENT: &str =
+        include_str!("../../tests/fixtures/hand_history/pokerstars_tournament.txt");
+
+    fn cash_hand() -> HandHistory {
+        HandHistory::parse(CASH).expect("valid hand")
+    }
+
+    fn invalid_field(ohh: &Ohh) -> String {
+        match HandHistory::from_ohh(ohh) {
+            Err(CoreError::InvalidOhh(message)) => message,
+            other => panic!("expected a spec violation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cash_round_trip() {
+        for hand in HandHistoryReader::new(CASH.as_bytes()) {
+            let hand = hand.expect("valid hand");
+            let json = hand.to_ohh_json().expect("serialize");
+            let back = HandHistory::from_ohh_json(&json).expect("valid OHH");
+            // The room's local time comes back as UTC
+            assert_eq!(Some(back.date.clone()), utc_date(&hand.date));
+            assert_eq!(back.to_ohh_json().expect("serialize"), json);
+            let date = hand.date.clone();
+            assert_eq!(HandHistory { date, ..back }, hand);
+        }
+    }
+
+    #[test]
+    fn test_start_date_utc() {
+        // PokerStars prints US Eastern time, daylight saving from 12 March 2023
+        assert_eq!(cash_hand().to_ohh().start_date_utc, "2023-03-12T18:31:07Z");
+        let cases = [
+            ("2023/03/12 01:59:59 ET", Some("2023-03-12T06:59:59Z")),
+            ("2023/11/05 02:30:00 ET", Some("2023-11-05T07:30:00Z")),
+            ("2023/12/31 22:15:00 ET", Some("2024-01-01T03:15:00Z")),
+            ("2023/03/26 01:00:00 CET", Some("2023-03-26T00:00:00Z")),
+            ("2023/07/01 12:00:00 CET", Some("2023-07-01T10:00:00Z")),
+            (
+                "2023/03/12 20:31:07 CET [2023/03/12 15:31:07 ET]",
+                Some("2023-03-12T19:31:07Z"),
+            ),
+            ("2023/03/14 13:40:34 UTC", Some("2023-03-14T13:40:34Z")),
+            ("2024-02-29T23:00:00Z", Some("2024-02-29T23:00:00Z")),
+            // No zone, or one we don't know the rules for
+            ("14 03 2023 20:41:12", None),
+            ("2023-03-14 19:04:51", None),
+            ("2023/03/12 14:31:07 MSK", None),
+            ("2023/13/12 14:31:07 ET", None),
+        ];
+        for (date, expected) in cases {
+            assert_eq!(utc_date(date).as_deref(), expected, "{date}");
+        }
+
+        // Without a known zone the field is left out
+        let mut hand = cash_hand();
+        hand.date = "2023-03-14 19:04:51".to_string();
+        let json = hand.to_ohh_json().expect("serialize");
+        assert!(!json.contains("start_date_utc"), "{json}");
+        let back = HandHistory::from_ohh_json(&json).expect("valid OHH");
+        assert_eq!(back.date, "");
+    }
+
+    #[test]
+    fn test_ohh_layout() {
+        let ohh = cash_hand().to_ohh();
+        assert_eq!(ohh.spec_version, OHH_SPEC_VERSION);
+        assert!(!ohh.tournament);
+        assert_eq!(ohh.currency, "USD");
+        assert_eq!(ohh.big_blind_amount, 0.1);
+        assert_eq!(ohh.small_blind_amount, 0.05);
+        assert_eq!(ohh.dealer_seat, 4);
+        let hero = ohh.hero_player_id.expect("hero");
+        let hero = ohh.players.iter().find(|p| p.id == hero).expect("player");
+        assert_eq!(hero.name, "Hero");
+        assert_eq!(hero.starting_stack, 12.0);
+
+        let streets: Vec<&str> = ohh.rounds.iter().map(|r| r.street.as_str()).collect();
+        assert_eq!(streets, ["Preflop", "Flop", "Turn", "River", "Showdown"]);
+        assert_eq!(ohh.rounds[1].cards, ["As", "7d", "2c"]);
+        let preflop: Vec<&str> = ohh.rounds[0]
+            .actions
+            .iter()
+            .map(|a| a.action.as_str())
+            .collect();
+        assert_eq!(
+            preflop,
+            [
+                "Post SB",
+                "Post BB",
+                "Dealt Cards",
+                "Fold",
+                "Raise",
+                "Fold",
+                "Fold",
+                "Raise",
+                "Call"
+            ]
+        );
+        // Amounts are what each action added, in dollars
+        let shove = &ohh.rounds[2].actions[1];
+        assert_eq!(
+            (shove.action.as_str(), shove.amount, shove.is_allin),
+            ("Raise", 7.74, true)
+        );
+        assert_eq!(ohh.rounds[4].actions.len(), 2);
+
+        assert_eq!(ohh.pots.len(), 1);
+        assert_eq!(ohh.pots[0].amount, 20.13);
+        assert_eq!(ohh.pots[0].rake, 0.94);
+        assert_eq!(ohh.pots[0].player_wins[0].win_amount, 19.19);
+    }
+
+    #[test]
+    fn test_tournament_export() {
+        let hand = HandHistory::parse(TOURNAMENT).expect("valid hand");
+        let ohh = hand.to_ohh();
+        assert!(ohh.tournament);
+        let info = ohh.tournament_info.as_ref().expect("tournament info");
+        assert_eq!(info.tournament_number, "3456789012");
+        assert_eq!((info.buyin_amount, info.fee_amount), (4.4, 0.6));
+        assert_eq!(info.kind, "MTT");
+        assert_eq!(ohh.ante_amount, 10.0);
+
+        let back = HandHistory::from_ohh(&ohh).expect("valid OHH");
+        assert_eq!(back.game_type, GameType::MTT);
+        let back_info = back.tournament.as_ref().expect("tournament");
+        assert_eq!(back_info.buy_in.as_deref(), Some("4.40+0.60"));
+        assert_eq!(back.seats, hand.seats);
+        assert_eq!(back.actions, hand.actions);
+        assert_eq!(back.posts, hand.posts);
+        assert_eq!(back.collected, hand.collected);
+        assert_eq!(back.board, hand.board);
+    }
+
+    #[test]
+    fn test_jackpot_and_bounty_round_trip() {
+        let gg = include_str!("../../tests/fixtures/hand_history/ggpoker.txt");
+        let hand = HandHistoryReader::new(gg.as_bytes())
+            .next()
+            .expect("cash hand")
+            .expect("valid hand");
+        let ohh = hand.to_ohh();
+        assert_eq!(ohh.pots[0].rake, 0.27);
+        assert_eq!(ohh.pots[0].jackpot, 0.03);
+        let back = HandHistory::from_ohh(&ohh).expect("valid OHH");
+        assert_eq!((back.rake, back.jackpot), (hand.rake, hand.jackpot));
+
+        let winamax = include_str!("../../tests/fixtures/hand_history/winamax.txt");
+        let hand = HandHistoryReader::new(winamax.as_bytes())
+            .nth(1)
+            .expect("tournament hand")
+            .expect("valid hand");
+        let ohh = hand.to_ohh();
+        let hero = ohh.players.iter().find(|p| p.name == "Hero").expect("hero");
+        assert_eq!(hero.player_bounty, 3.37);
+        let back = HandHistory::from_ohh(&ohh).expect("valid OHH");
+        let bounties: Vec<_> = back.seats.iter().map(|s| s.bounty).collect();
+        assert_eq!(bounties, [Some(2.25), Some(3.37), Some(2.25), Some(5.62)]);
+    }
+
+    #[test]
+    fn test_import_reports_spec_violations() {
+        let ohh = cash_hand().to_ohh();
+
+        let mut bad = ohh.clone();
+        bad.rounds[1].actions[0].player_id = 42;
+        assert_eq!(
+            invalid_field(&bad),
+            "rounds[1].actions[0].player_id: no player with id 42"
+        );
+
+        let mut bad = ohh.clone();
+        bad.rounds.insert(2, ohh.rounds[1].clone());
+        assert!(invalid_field(&bad).starts_with("rounds[2]: Flop out of order"));
+
+        let mut bad = ohh.clone();
+        bad.rounds[2].cards.push("2h".to_string());
+        assert!(invalid_field(&bad).starts_with("rounds[2].cards: Turn deals 1 cards"));
+
+        let mut bad = ohh.clone();
+        bad.rounds[0].actions[4].action = "Jam".to_string();
+        assert!(invalid_field(&bad).contains("unknown action 'Jam'"));
+
+        let mut bad = ohh.clone();
+        bad.bet_limit.bet_type = "FL".to_string();
+        assert!(invalid_field(&bad).starts_with("bet_limit.bet_type"));
+
+        let mut bad = ohh.clone();
+        bad.players[1].seat = bad.players[0].seat;
+        assert!(invalid_field(&bad).starts_with("players[1].seat"));
+
+        let mut bad = ohh.clone();
+        bad.rounds[1].cards[0] = "Kc".to_string();
+        // Kc shows up again on the turn
+        assert!(invalid_field(&bad).starts_with("rounds[2].cards"));
+
+        let err = HandHistory::from_ohh_json(r#"{"ohh": {"spec_version": "1.4.6"}}"#)
+            .expect_err("missing fields");
+        assert!(err.to_string().contains("missing field"), "{err}");
+    }
+}
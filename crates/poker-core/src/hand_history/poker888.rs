@@ -0,0 +1,416 @@
+use crate::action::Action;
+use crate::board::Street;
+use crate::card::Card;
+use crate::error::CoreError;
+use crate::game_type::GameType;
+
+use super::{
+    invalid, parse_amount, Collected, HandBuilder, HandHistory, HandHistoryParser, PostKind,
+    TournamentInfo,
+};
+
+const HAND_START: &str = "#Game No :";
+
+/// 888poker cash games and tournaments.
+///
+/// 888 writes amounts in brackets as the chips each action added, never
+/// says who is all-in and prints neither uncalled bets nor the pot total,
+/// so those are worked out from the stacks and actions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Poker888Parser;
+
+impl HandHistoryParser for Poker888Parser {
+    fn site(&self) -> &'static str {
+        "888poker"
+    }
+
+    fn detect(&self, line: &str) -> bool {
+        line.starts_with(HAND_START) || line.starts_with("***** 888poker Hand History")
+    }
+
+    fn is_hand_start(&self, line: &str) -> bool {
+        line.starts_with(HAND_START)
+    }
+
+    fn parse_hand(&mut self, lines: &[(usize, String)]) -> Result<HandHistory, CoreError> {
+        let (first, header) = &lines[0];
+        let hand_id = header
+            .strip_prefix(HAND_START)
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| invalid(*first, "expected '#Game No : <id>'"))?;
+
+        let mut rest = lines[1..]
+            .iter()
+            .map(|(line, text)| (*line, text.as_str()))
+            .filter(|(_, text)| !text.starts_with("*****"));
+        let (line, stakes) = rest
+            .next()
+            .ok_or_else(|| invalid(*first, "hand ends after the header"))?;
+        let mut builder = parse_stakes(hand_id, line, stakes)?;
+        let (line, table) = rest
+            .next()
+            .ok_or_else(|| invalid(line, "missing table line"))?;
+        parse_table(&mut builder, line, table)?;
+
+        let mut summary = false;
+        let mut last = line;
+        for (line, text) in rest {
+            last = line;
+            if let Some(button) = text
+                .strip_prefix("Seat ")
+                .and_then(|s| s.strip_suffix(" is the button"))
+            {
+                builder.hand_mut().button_seat = button
+                    .parse()
+                    .map_err(|_| invalid(line, format!("invalid button seat '{button}'")))?;
+            } else if let Some(seat) = text.strip_prefix("Seat ") {
+                parse_seat(&mut builder, line, seat)?;
+            } else if let Some(marker) = text.strip_prefix("** ") {
+                summary |= parse_marker(&mut builder, line, marker)?;
+            } else if let Some(to) = text.strip_prefix("Dealt to ") {
+                if let Some((seat, cards)) = builder.find_player(to) {
+                    builder.hand_mut().hero = Some(seat);
+                    let cards = two_cards(line, cards)?;
+                    builder.show(seat, cards);
+                }
+            } else if let Some((seat, rest)) = builder.find_player(text) {
+                parse_player_line(&mut builder, line, seat, rest, summary)?;
+            }
+        }
+        if !summary {
+            return Err(invalid(last, "hand has no summary"));
+        }
+        builder.return_uncalled();
+        builder.derive_totals();
+        builder.finish(last)
+    }
+}
+
+/// `$0.05/$0.10 Blinds No Limit Holdem - *** 12 03 2023 14:31:07`
+fn parse_stakes(hand_id: &str, line: usize, text: &str) -> Result<HandBuilder, CoreError> {
+    let (blinds, game) = text
+        .split_once(" Blinds ")
+        .ok_or_else(|| invalid(line, "expected a blinds line"))?;
+    if !game.starts_with("No Limit Holdem") {
+        return Err(invalid(line, "only no-limit hold'em is supported"));
+    }
+    let (small, big) = blinds
+        .split_once('/')
+        .ok_or_else(|| invalid(line, "missing blinds"))?;
+    let small = parse_amount(small).map_err(|e| invalid(line, e))?;
+    let big = parse_amount(big).map_err(|e| invalid(line, e))?;
+    if big <= 0.0 {
+        return Err(invalid(line, "big blind must be positive"));
+    }
+
+    let mut builder = HandBuilder::new("888poker", hand_id.to_string(), big);
+    let small = builder.chips(small);
+    let hand = builder.hand_mut();
+    hand.blinds.small = small;
+    hand.date = game
+        .split_once("***")
+        .map(|(_, d)| d.trim().to_string())
+        .unwrap_or_default();
+    hand.currency = match blinds.chars().next() {
+        Some('$') => Some("USD"),
+        Some('€') => Some("EUR"),
+        Some('£') => Some("GBP"),
+        _ => None,
+    }
+    .map(str::to_string);
+    Ok(builder)
+}
+
+/// `Table Athens 6 Max (Real Money)` or
+/// `Tournament #3456789012 $4.40 + $0.60 - Table #7 9 Max (Real Money)`.
+fn parse_table(builder: &mut HandBuilder, line: usize, text: &str) -> Result<(), CoreError> {
+    let (tournament, table) = match text.strip_prefix("Tournament #") {
+        Some(t) => {
+            let (info, table) = t
+                .split_once(" - ")
+                .ok_or_else(|| invalid(line, "malformed tournament line"))?;
+            let (id, buy_in) = info.split_once(' ').unwrap_or((info, ""));
+            let info = TournamentInfo {
+                id: id.to_string(),
+                buy_in: Some(buy_in.trim().to_string()).filter(|b| !b.is_empty()),
+                level: None,
+            };
+            (Some(info), table)
+        }
+        None => (None, text),
+    };
+    let table = table
+        .strip_prefix("Table ")
+        .ok_or_else(|| invalid(line, "expected a table line"))?;
+    let (name, size) = table
+        .split_once(" Max")
+        .and_then(|(head, _)| head.rsplit_once(' '))
+        .ok_or_else(|| invalid(line, "missing table size"))?;
+
+    let hand = builder.hand_mut();
+    if tournament.is_some() {
+        hand.game_type = GameType::MTT;
+        hand.currency = None;
+    }
+    hand.tournament = tournament;
+    hand.table = name.to_string();
+    hand.max_seats = size
+        .parse()
+        .map_err(|_| invalid(line, format!("invalid table size '{size}'")))?;
+    Ok(())
+}
+
+/// `1: Alice ( $10 )`
+fn parse_seat(builder: &mut HandBuilder, line: usize, text: &str) -> Result<(), CoreError> {
+    let (number, rest) = text
+        .split_once(": ")
+        .ok_or_else(|| invalid(line, "malformed seat line"))?;
+    let seat = number
+        .parse()
+        .map_err(|_| invalid(line, format!("invalid seat number '{number}'")))?;
+    let (player, stack) = rest
+        .rsplit_once(" ( ")
+        .and_then(|(p, s)| Some((p, s.strip_suffix(')')?)))
+        .ok_or_else(|| invalid(line, "seat line has no stack"))?;
+    let stack = parse_amount(stack).map_err(|e| invalid(line, e))?;
+    let stack = builder.chips(stack);
+    builder.add_seat(seat, player.to_string(), stack, false);
+    Ok(())
+}
+
+/// Returns whether the summary has started.
+fn parse_marker(builder: &mut HandBuilder, line: usize, marker: &str) -> Result<bool, CoreError> {
+    let street = if marker.starts_with("Dealing down cards") {
+        return Ok(false);
+    } else if marker.starts_with("Summary") {
+        return Ok(true);
+    } else if marker.starts_with("Dealing flop") {
+        Street::Flop
+    } else if marker.starts_with("Dealing turn") {
+        Street::Turn
+    } else if marker.starts_with("Dealing river") {
+        Street::River
+    } else {
+        return Err(invalid(line, format!("unsupported section '** {marker}'")));
+    };
+    let cards = bracket_cards(line, marker)?;
+    builder.start_street(street, &cards, line)?;
+    Ok(false)
+}
+
+/// Everything after the player's name on an action line.
+fn parse_player_line(
+    builder: &mut HandBuilder,
+    line: usize,
+    seat: usize,
+    rest: &str,
+    summary: bool,
+) -> Result<(), CoreError> {
+    let Some(verb) = rest.strip_prefix(' ') else {
+        return Ok(());
+    };
+    let amount = |builder: &HandBuilder, what: &str| {
+        let value = verb
+            .split_once('[')
+            .and_then(|(_, v)| v.split_once(']'))
+            .map(|(v, _)| v)
+            .ok_or_else(|| invalid(line, format!("{what} has no amount")))?;
+        parse_amount(value)
+            .map(|a| builder.chips(a))
+            .map_err(|e| invalid(line, format!("invalid {what}: {e}")))
+    };
+
+    if summary {
+        if let Some(shown) = verb.strip_prefix("shows ") {
+            let cards = two_cards(line, shown)?;
+            builder.show(seat, cards);
+        } else if let Some(shown) = verb.strip_prefix("mucks ") {
+            let cards = two_cards(line, shown)?;
+            builder.show(seat, cards);
+        } else if verb.starts_with("collected ") {
+            let amount = amount(builder, "collected amount")?;
+            builder
+                .hand_mut()
+                .collected
+                .push(Collected { seat, amount });
+        }
+    } else if verb == "folds" {
+        builder.passive(seat, Action::Fold);
+    } else if verb == "checks" {
+        builder.passive(seat, Action::Check);
+    } else if verb.starts_with("calls ") {
+        let a = amount(builder, "call")?;
+        builder.add(seat, a, false);
+    } else if verb.starts_with("bets ") || verb.starts_with("raises ") {
+        let a = amount(builder, "bet")?;
+        builder.add(seat, a, false);
+    } else if let Some(post) = verb.strip_prefix("posts ") {
+        let kind = if post.starts_with("small blind") {
+            PostKind::SmallBlind
+        } else if post.starts_with("big blind") {
+            PostKind::BigBlind
+        } else if post.starts_with("dead big blind") {
+            PostKind::BothBlinds
+        } else if post.starts_with("ante") {
+            PostKind::Ante
+        } else {
+            return Err(invalid(line, format!("unknown post '{post}'")));
+        };
+        let a = amount(builder, "post")?;
+        if kind == PostKind::Ante && builder.hand_mut().blinds.ante.is_zero() {
+            builder.hand_mut().blinds.ante = a;
+        }
+        builder.post(seat, kind, a);
+    }
+    Ok(())
+}
+
+/// Cards in the last `[ As, 7d, 2c ]` group of a line.
+fn bracket_cards(line: usize, text: &str) -> Result<Vec<Card>, CoreError> {
+    let start = text
+        .rfind('[')
+        .ok_or_else(|| invalid(line, "missing cards"))?;
+    let end = text[start..]
+        .find(']')
+        .map(|e| start + e)
+        .ok_or_else(|| invalid(line, "missing cards"))?;
+    text[start + 1..end]
+        .split(',')
+        .map(|c| Card::from_str_notation(c.trim()).map_err(|e| invalid(line, e.to_string())))
+        .collect()
+}
+
+fn two_cards(line: usize, text: &str) -> Result<[Card; 2], CoreError> {
+    match bracket_cards(line, text)?[..] {
+        [a, b] if a != b => Ok([a, b]),
+        _ => Err(invalid(line, format!("expected two hole cards in {text}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chips::Chips;
+    use crate::hand_history::HandHistoryReader;
+    use crate::position::Position;
+
+    const HANDS: &str = include_str!("../../tests/fixtures/hand_history/poker888.txt");
+
+    #[test]
+    fn test_888_hands() {
+        let mut reader = HandHistoryReader::new(HANDS.as_bytes());
+        let cash = reader.next().expect("cash hand").expect("valid hand");
+        assert_eq!(cash.site, "888poker");
+        assert_eq!(cash.hand_id, "1029384756");
+        assert_eq!(cash.table, "Lima");
+        assert_eq!(cash.max_seats, 9);
+        assert_eq!(cash.currency.as_deref(), Some("USD"));
+        // 888 writes the date day first, with spaces
+        assert_eq!(cash.date, "14 03 2023 20:41:12");
+        assert_eq!(cash.big_blind, 0.05);
+        assert_eq!(cash.blinds.small, Chips::from(0.02));
+        let hero = cash.hero.expect("hero");
+        assert_eq!(cash.seats[hero].position, Some(Position::UTG));
+        let villain = cash.seat_of("1a2b3c4d").expect("villain");
+        assert_eq!(cash.seats[villain].position, Some(Position::CO));
+        assert_eq!(cash.seats[villain].stack, Chips::from(2.1));
+        assert!(cash.seats[villain].cards.is_some());
+
+        // Bracketed amounts are the chips added; they become street totals
+        // and the all-in is inferred from the stack
+        let preflop: Vec<Action> = cash
+            .street_actions(Street::Preflop)
+            .map(|a| a.action)
+            .collect();
+        assert_eq!(preflop[0], Action::Call);
+        assert_eq!(preflop[2], Action::Raise(Chips::from(0.2)));
+        let flop: Vec<_> = cash.street_actions(Street::Flop).collect();
+        assert_eq!(flop[4].action, Action::Raise(Chips::from(1.35)));
+        assert_eq!(flop[5].action, Action::AllIn(Chips::from(1.9)));
+        assert!(flop[5].all_in);
+        assert_eq!(cash.board.to_string(), "[6d Jc 7c 2s Qh]");
+
+        // 888 prints no pot or rake line, so the rake is what nobody collected
+        assert_eq!(cash.total_pot, Chips::from(4.42));
+        assert_eq!(cash.rake, Chips::from(0.22));
+        assert_eq!(cash.net(hero), Chips::from(-2.1));
+        assert_eq!(cash.net(villain), Chips::from(2.1));
+
+        let mtt = reader.next().expect("tournament hand").expect("valid hand");
+        assert_eq!(mtt.game_type, GameType::MTT);
+        let info = mtt.tournament.as_ref().expect("tournament");
+        assert_eq!(info.id, "187654321");
+        assert_eq!(info.buy_in.as_deref(), Some("$10 + $1"));
+        assert_eq!(mtt.table, "#12");
+        assert_eq!(mtt.max_seats, 8);
+        assert_eq!(mtt.currency, None);
+        assert_eq!(mtt.big_blind, 200.0);
+        assert_eq!(mtt.blinds.ante, Chips::from(20.0));
+        // Chip counts use thousands separators
+        let shover = mtt.seat_of("2c3d4e5f").expect("shover");
+        assert_eq!(mtt.seats[shover].stack, Chips::from(4370.0));
+        let preflop: Vec<_> = mtt.street_actions(Street::Preflop).collect();
+        assert_eq!(preflop[1].action, Action::AllIn(Chips::from(4350.0)));
+        let hero = mtt.hero.expect("hero");
+        assert_eq!(mtt.seats[hero].position, Some(Position::SB));
+        assert_eq!(mtt.total_pot, Chips::from(9000.0));
+        assert_eq!(mtt.net(hero), Chips::from(4630.0));
+        assert_eq!(mtt.rake, Chips::ZERO);
+        let nets: Chips = (0..mtt.seats.len()).map(|s| mtt.net(s)).sum();
+        assert_eq!(nets, Chips::ZERO);
+    }
+
+    #[test]
+    fn test_uneven_ante_stays_exact() {
+        // A 75 ante is 0.125 big blinds at 300/600, finer than Chips resolve
+        let start = HANDS
+            .find("#Game No : 1029391188")
+            .expect("tournament hand");
+        let text = [
+            ("100/200 Blinds", "300/600 Blinds"),
+            ("[20]", "[75]"),
+            ("small blind [100]", "small blind [300]"),
+            ("big blind [200]", "big blind [600]"),
+            ("raises [4,350]", "raises [4,295]"),
+            ("calls [4,250]", "calls [3,995]"),
+            ("collected [ 9,000 ]", "collected [ 9,565 ]"),
+        ]
+        .iter()
+        .fold(HANDS[start..].to_string(), |text, (from, to)| {
+            text.replace(from, to)
+        });
+        let hand = HandHistory::parse(&text).expect("valid hand");
+        assert_eq!(hand.big_blind, 600.0);
+        assert_eq!(hand.blinds.ante, Chips::from(75.0));
+        assert_eq!(hand.in_bb(hand.blinds.ante), 0.125);
+        assert_eq!(hand.total_pot, Chips::from(9565.0));
+        assert_eq!(hand.rake, Chips::ZERO);
+        let hero = hand.hero.expect("hero");
+        assert_eq!(hand.net(hero), Chips::from(5195.0));
+        let nets: Chips = (0..hand.seats.len()).map(|s| hand.net(s)).sum();
+        assert_eq!(nets, Chips::ZERO);
+
+        // OHH carries the room's chips over unchanged, both ways
+        let ohh = hand.to_ohh();
+        assert_eq!(ohh.ante_amount, 75.0);
+        assert_eq!(ohh.pots[0].amount, 9565.0);
+        assert_eq!(ohh.pots[0].rake, 0.0);
+        let back = HandHistory::from_ohh(&ohh).expect("valid OHH");
+        assert_eq!(back.blinds, hand.blinds);
+        assert_eq!(back.posts, hand.posts);
+        assert_eq!(back.actions, hand.actions);
+        assert_eq!(back.collected, hand.collected);
+        assert_eq!((back.total_pot, back.rake), (hand.total_pot, hand.rake));
+    }
+
+    #[test]
+    fn test_888_malformed_amount() {
+        let broken = HANDS.replacen("5e0f1a2b calls [$0.15]", "5e0f1a2b calls [$0.1x]", 1);
+        let err = HandHistory::parse(&broken).expect_err("bad amount");
+        assert!(
+            matches!(err, CoreError::InvalidHandHistory { line: 22, .. }),
+            "{err}"
+        );
+    }
+}
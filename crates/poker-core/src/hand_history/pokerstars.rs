@@ -0,0 +1,413 @@
+use crate::action::Action;
+use crate::board::Street;
+use crate::card::Card;
+use crate::error::CoreError;
+use crate::game_type::GameType;
+
+use super::{
+    invalid, last_bracket_cards, parse_amount, parse_bounty, Collected, HandBuilder, HandHistory,
+    HandHistoryParser, PostKind, TournamentInfo,
+};
+
+/// Details that differ between rooms writing the PokerStars text format.
+pub(crate) struct Dialect {
+    pub(crate) site: &'static str,
+    /// Prefixes of the first line of a hand.
+    pub(crate) headers: &'static [&'static str],
+}
+
+impl Dialect {
+    pub(crate) fn is_header(&self, line: &str) -> bool {
+        self.headers.iter().any(|h| line.starts_with(h))
+    }
+}
+
+const POKERSTARS: Dialect = Dialect {
+    site: "PokerStars",
+    headers: &[
+        "PokerStars Hand #",
+        "PokerStars Game #",
+        "PokerStars Zoom Hand #",
+        "PokerStars Home Game Hand #",
+    ],
+};
+
+/// PokerStars cash games and tournaments.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PokerStarsParser;
+
+impl HandHistoryParser for PokerStarsParser {
+    fn site(&self) -> &'static str {
+        POKERSTARS.site
+    }
+
+    fn detect(&self, line: &str) -> bool {
+        POKERSTARS.is_header(line)
+    }
+
+    fn is_hand_start(&self, line: &str) -> bool {
+        POKERSTARS.is_header(line)
+    }
+
+    fn parse_hand(&mut self, lines: &[(usize, String)]) -> Result<HandHistory, CoreError> {
+        parse_hand(&POKERSTARS, lines)
+    }
+}
+
+/// Parse one hand from its numbered lines.
+pub(crate) fn parse_hand(
+    dialect: &Dialect,
+    lines: &[(usize, String)],
+) -> Result<HandHistory, CoreError> {
+    let (first, header) = &lines[0];
+    let mut builder = parse_header(dialect, *first, header)?;
+
+    let mut rest = lines[1..].iter();
+    let (line, table) = rest
+        .next()
+        .ok_or_else(|| invalid(*first, "hand ends after the header"))?;
+    parse_table(&mut builder, *line, table)?;
+
+    let mut dealt = false;
+    let mut summary = false;
+    let mut last = *line;
+    for (line, text) in rest {
+        let (line, text) = (*line, text.as_str());
+        last = line;
+        if let Some(seat) = text.strip_prefix("Seat ") {
+            if !dealt {
+                parse_seat(&mut builder, line, seat)?;
+            } else if summary {
+                parse_summary_seat(&mut builder, line, seat)?;
+            }
+        } else if let Some(marker) = text.strip_prefix("*** ") {
+            parse_marker(&mut builder, line, marker, &mut dealt, &mut summary)?;
+        } else if summary {
+            parse_summary_line(&mut builder, line, text)?;
+        } else if let Some(to) = text.strip_prefix("Dealt to ") {
+            // GGPoker lists every player dealt in, without cards
+            if let Some((seat, cards)) = builder.find_player(to) {
+                if !cards.trim().is_empty() {
+                    builder.hand_mut().hero = Some(seat);
+                    let cards = two_cards(line, cards)?;
+                    builder.show(seat, cards);
+                }
+            }
+        } else if let Some(returned) = text.strip_prefix("Uncalled bet (") {
+            let (amount, player) = returned
+                .split_once(") returned to ")
+                .ok_or_else(|| invalid(line, "malformed uncalled bet"))?;
+            let amount = builder.chips(parse_amount(amount).map_err(|e| invalid(line, e))?);
+            let (seat, _) = builder
+                .find_player(player)
+                .ok_or_else(|| invalid(line, format!("unknown player '{player}'")))?;
+            builder.hand_mut().returned.push(Collected { seat, amount });
+        } else if let Some((seat, rest)) = builder.find_player(text) {
+            parse_player_line(&mut builder, line, seat, rest)?;
+        }
+    }
+    if !summary {
+        return Err(invalid(last, "hand has no summary"));
+    }
+    builder.finish(last)
+}
+
+/// `PokerStars Hand #1: Hold'em No Limit ($0.05/$0.10 USD) - date` or
+/// `PokerStars Hand #1: Tournament #2, $4.40+$0.60 USD Hold'em No Limit - Level IV (50/100) - date`.
+fn parse_header(dialect: &Dialect, line: usize, text: &str) -> Result<HandBuilder, CoreError> {
+    if !dialect.is_header(text) {
+        return Err(invalid(
+            line,
+            format!("expected a {} hand header", dialect.site),
+        ));
+    }
+    let after_hash = &text[text.find('#').map_or(0, |i| i + 1)..];
+    let (id, body) = after_hash
+        .split_once(':')
+        .ok_or_else(|| invalid(line, "missing ':' after the hand number"))?;
+    if !body.contains("Hold'em No Limit") {
+        return Err(invalid(line, "only no-limit hold'em is supported"));
+    }
+
+    // GGPoker nests the ante: `Level10(200/400(50))`
+    let stakes = body
+        .split('(')
+        .skip(1)
+        .map(|s| s.split(')').next().unwrap_or(s))
+        .find(|s| s.contains('/'))
+        .ok_or_else(|| invalid(line, "missing blinds"))?;
+    let mut parts = stakes.split_whitespace();
+    let (small, big) = parts
+        .next()
+        .and_then(|b| b.split_once('/'))
+        .ok_or_else(|| invalid(line, "missing blinds"))?;
+    let small = parse_amount(small).map_err(|e| invalid(line, e))?;
+    let big = parse_amount(big).map_err(|e| invalid(line, e))?;
+    if big <= 0.0 {
+        return Err(invalid(line, "big blind must be positive"));
+    }
+
+    let mut builder = HandBuilder::new(dialect.site, id.trim().to_string(), big);
+    let small = builder.chips(small);
+    let hand = builder.hand_mut();
+    hand.blinds.small = small;
+    hand.date = body
+        .rsplit_once(" - ")
+        .map(|(_, d)| d.trim().to_string())
+        .unwrap_or_default();
+
+    if let Some(tourney) = body.trim().strip_prefix("Tournament #") {
+        let (id, rest) = tourney.split_once(',').unwrap_or((tourney, ""));
+        let buy_in = rest
+            .split(" Hold'em")
+            .next()
+            .map(|b| {
+                b.trim()
+                    .trim_end_matches(|c: char| c.is_ascii_uppercase())
+                    .trim()
+            })
+            .filter(|b| !b.is_empty() && *b != "Freeroll")
+            .map(str::to_string);
+        let level = body
+            .split_once("Level")
+            .map(|(_, l)| {
+                l.trim_start()
+                    .split(|c: char| !c.is_ascii_alphanumeric())
+                    .next()
+                    .unwrap_or_default()
+            })
+            .filter(|l| !l.is_empty())
+            .map(str::to_string);
+        hand.game_type = GameType::MTT;
+        hand.tournament = Some(TournamentInfo {
+            id: id.trim().to_string(),
+            buy_in,
+            level,
+        });
+    } else {
+        hand.currency = parts.next().map(str::to_string).or_else(|| {
+            match stakes.chars().next() {
+                Some('$') => Some("USD"),
+                Some('€') => Some("EUR"),
+                Some('£') => Some("GBP"),
+                _ => None,
+            }
+            .map(str::to_string)
+        });
+    }
+    Ok(builder)
+}
+
+/// `Table 'Aase III' 6-max Seat #4 is the button`
+fn parse_table(builder: &mut HandBuilder, line: usize, text: &str) -> Result<(), CoreError> {
+    let name = text
+        .strip_prefix("Table '")
+        .and_then(|t| t.rsplit_once('\''))
+        .ok_or_else(|| invalid(line, "expected a table line"))?;
+    let hand = builder.hand_mut();
+    hand.table = name.0.to_string();
+    hand.max_seats = name
+        .1
+        .split_whitespace()
+        .find_map(|w| w.strip_suffix("-max"))
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(9);
+    hand.button_seat = name
+        .1
+        .split_once("Seat #")
+        .and_then(|(_, s)| s.split_whitespace().next())
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| invalid(line, "missing button seat"))?;
+    Ok(())
+}
+
+/// `1: Alice ($10.00 in chips)`, optionally followed by `is sitting out`;
+/// knockout tournaments add the bounty: `(6000 in chips, $1.25 bounty)`.
+fn parse_seat(builder: &mut HandBuilder, line: usize, text: &str) -> Result<(), CoreError> {
+    let (number, rest) = text
+        .split_once(": ")
+        .ok_or_else(|| invalid(line, "malformed seat line"))?;
+    let seat = number
+        .parse()
+        .map_err(|_| invalid(line, format!("invalid seat number '{number}'")))?;
+    let chips_at = rest
+        .find(" in chips")
+        .ok_or_else(|| invalid(line, "seat line has no stack"))?;
+    let open = rest[..chips_at]
+        .rfind('(')
+        .ok_or_else(|| invalid(line, "seat line has no stack"))?;
+    let stack = parse_amount(&rest[open + 1..chips_at]).map_err(|e| invalid(line, e))?;
+    let player = rest[..open].trim().to_string();
+    let sitting_out = rest.ends_with("is sitting out") || rest.contains("out of hand");
+    let stack = builder.chips(stack);
+    builder.add_seat(seat, player, stack, sitting_out);
+    if let Some(last) = builder.hand_mut().seats.last_mut() {
+        last.bounty = parse_bounty(rest);
+    }
+    Ok(())
+}
+
+fn parse_marker(
+    builder: &mut HandBuilder,
+    line: usize,
+    marker: &str,
+    dealt: &mut bool,
+    summary: &mut bool,
+) -> Result<(), CoreError> {
+    let street = if marker.starts_with("HOLE CARDS") {
+        *dealt = true;
+        return Ok(());
+    } else if marker.starts_with("FLOP") {
+        Street::Flop
+    } else if marker.starts_with("TURN") {
+        Street::Turn
+    } else if marker.starts_with("RIVER") {
+        Street::River
+    } else if marker.starts_with("SUMMARY") {
+        *summary = true;
+        return Ok(());
+    } else if marker.starts_with("SHOW DOWN") || marker.starts_with("SHOWDOWN") {
+        return Ok(());
+    } else {
+        return Err(invalid(line, format!("unsupported section '*** {marker}'")));
+    };
+    let cards = last_bracket_cards(marker)
+        .ok_or_else(|| invalid(line, "street has no cards"))?
+        .map_err(|e| invalid(line, e.to_string()))?;
+    builder.start_street(street, &cards, line)
+}
+
+/// Everything after the player's name on an action line.
+fn parse_player_line(
+    builder: &mut HandBuilder,
+    line: usize,
+    seat: usize,
+    rest: &str,
+) -> Result<(), CoreError> {
+    let amount = |builder: &HandBuilder, text: &str, what: &str| {
+        parse_amount(text)
+            .map(|a| builder.chips(a))
+            .map_err(|e| invalid(line, format!("invalid {what}: {e}")))
+    };
+
+    if let Some(collected) = rest.strip_prefix(" collected ") {
+        let value = collected.split(" from ").next().unwrap_or(collected);
+        let amount = amount(builder, value, "collected amount")?;
+        builder
+            .hand_mut()
+            .collected
+            .push(Collected { seat, amount });
+        return Ok(());
+    }
+    let Some(verb) = rest.strip_prefix(": ") else {
+        return Ok(());
+    };
+    let (verb, all_in) = match verb.strip_suffix(" and is all-in") {
+        Some(v) => (v, true),
+        None => (verb, false),
+    };
+
+    if verb == "folds" || verb.starts_with("folds [") {
+        builder.passive(seat, Action::Fold);
+    } else if verb == "checks" {
+        builder.passive(seat, Action::Check);
+    } else if let Some(a) = verb.strip_prefix("calls ") {
+        let a = amount(builder, a, "call")?;
+        builder.call(seat, a, all_in);
+    } else if let Some(a) = verb.strip_prefix("bets ") {
+        let a = amount(builder, a, "bet")?;
+        builder.bet(seat, a, all_in);
+    } else if let Some(a) = verb.strip_prefix("raises ") {
+        let to = a
+            .split_once(" to ")
+            .map(|(_, to)| to)
+            .ok_or_else(|| invalid(line, format!("invalid raise: '{a}'")))?;
+        let to = amount(builder, to, "raise")?;
+        builder.raise_to(seat, to, all_in);
+    } else if let Some(post) = verb.strip_prefix("posts ") {
+        let (kind, value) = if let Some(a) = post.strip_prefix("small & big blinds ") {
+            (PostKind::BothBlinds, a)
+        } else if let Some(a) = post.strip_prefix("small blind ") {
+            (PostKind::SmallBlind, a)
+        } else if let Some(a) = post.strip_prefix("big blind ") {
+            (PostKind::BigBlind, a)
+        } else if let Some(a) = post.strip_prefix("the ante ") {
+            (PostKind::Ante, a)
+        } else if let Some(a) = post.strip_prefix("straddle ") {
+            (PostKind::Straddle, a)
+        } else {
+            return Err(invalid(line, format!("unknown post '{post}'")));
+        };
+        let a = amount(builder, value, "post")?;
+        if kind == PostKind::Ante && builder.hand_mut().blinds.ante.is_zero() {
+            builder.hand_mut().blinds.ante = a;
+        }
+        builder.post(seat, kind, a);
+    } else if let Some(shown) = verb.strip_prefix("shows ") {
+        let cards = two_cards(line, shown)?;
+        builder.show(seat, cards);
+    }
+    // Mucks, chat and table events carry nothing we keep
+    Ok(())
+}
+
+fn parse_summary_line(builder: &mut HandBuilder, line: usize, text: &str) -> Result<(), CoreError> {
+    if let Some(pot) = text.strip_prefix("Total pot ") {
+        let total = pot.split_whitespace().next().unwrap_or_default();
+        let total = parse_amount(total).map_err(|e| invalid(line, e))?;
+        // `| Rake $0.27 | Jackpot $0.03 | ...`: everything taken from the pot
+        let (mut rake, mut jackpot) = (0.0, 0.0);
+        for fee in pot.split('|').skip(1) {
+            let amount = fee.split_whitespace().last().unwrap_or_default();
+            let amount = parse_amount(amount).map_err(|e| invalid(line, e))?;
+            rake += amount;
+            if !fee.trim_start().starts_with("Rake") {
+                jackpot += amount;
+            }
+        }
+        let (total, rake, jackpot) = (
+            builder.chips(total),
+            builder.chips(rake),
+            builder.chips(jackpot),
+        );
+        let hand = builder.hand_mut();
+        hand.total_pot = total;
+        hand.rake = rake;
+        hand.jackpot = jackpot;
+    }
+    Ok(())
+}
+
+/// Summary seat lines repeat cards that were shown or mucked face up.
+fn parse_summary_seat(builder: &mut HandBuilder, line: usize, text: &str) -> Result<(), CoreError> {
+    let Some((_, rest)) = text.split_once(": ") else {
+        return Ok(());
+    };
+    let Some((seat, rest)) = builder.find_player(rest) else {
+        return Ok(());
+    };
+    for key in ["showed ", "mucked "] {
+        if let Some((_, cards)) = rest.split_once(key) {
+            if builder.hand_mut().seats[seat].cards.is_none() {
+                let cards = two_cards(line, cards)?;
+                builder.show(seat, cards);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Hole cards from text starting with `[Ah Kh]`.
+fn two_cards(line: usize, text: &str) -> Result<[Card; 2], CoreError> {
+    let group = text
+        .find(']')
+        .map(|end| &text[..=end])
+        .ok_or_else(|| invalid(line, "missing hole cards"))?;
+    let cards = last_bracket_cards(group)
+        .ok_or_else(|| invalid(line, "missing hole cards"))?
+        .map_err(|e| invalid(line, e.to_string()))?;
+    match cards[..] {
+        [a, b] if a != b => Ok([a, b]),
+        _ => Err(invalid(line, format!("expected two hole cards in {group}"))),
+    }
+}
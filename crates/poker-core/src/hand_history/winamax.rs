@@ -0,0 +1,371 @@
+use crate::action::Action;
+use crate::board::Street;
+use crate::card::Card;
+use crate::error::CoreError;
+use crate::game_type::GameType;
+
+use super::{
+    invalid, last_bracket_cards, parse_amount, parse_bounty, Collected, HandBuilder, HandHistory,
+    HandHistoryParser, PostKind, TournamentInfo,
+};
+
+const HEADER: &str = "Winamax Poker - ";
+
+/// Winamax cash games and tournaments.
+///
+/// Close to the PokerStars format, but player names carry no colon,
+/// amounts end in `€` and uncalled bets go back without a line of their own.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WinamaxParser;
+
+impl HandHistoryParser for WinamaxParser {
+    fn site(&self) -> &'static str {
+        "Winamax"
+    }
+
+    fn detect(&self, line: &str) -> bool {
+        line.starts_with(HEADER)
+    }
+
+    fn is_hand_start(&self, line: &str) -> bool {
+        line.starts_with(HEADER)
+    }
+
+    fn parse_hand(&mut self, lines: &[(usize, String)]) -> Result<HandHistory, CoreError> {
+        let (first, header) = &lines[0];
+        let mut builder = parse_header(*first, header)?;
+
+        let mut rest = lines[1..].iter();
+        let (line, table) = rest
+            .next()
+            .ok_or_else(|| invalid(*first, "hand ends after the header"))?;
+        parse_table(&mut builder, *line, table)?;
+
+        let mut dealt = false;
+        let mut summary = false;
+        let mut last = *line;
+        for (line, text) in rest {
+            let (line, text) = (*line, text.as_str());
+            last = line;
+            if let Some(seat) = text.strip_prefix("Seat ") {
+                if !dealt {
+                    parse_seat(&mut builder, line, seat)?;
+                }
+            } else if let Some(marker) = text.strip_prefix("*** ") {
+                dealt = true;
+                summary |= parse_marker(&mut builder, line, marker)?;
+            } else if let Some(pot) = text.strip_prefix("Total pot ") {
+                parse_total(&mut builder, line, pot)?;
+            } else if summary {
+                continue;
+            } else if let Some(to) = text.strip_prefix("Dealt to ") {
+                if let Some((seat, cards)) = builder.find_player(to) {
+                    builder.hand_mut().hero = Some(seat);
+                    let cards = two_cards(line, cards)?;
+                    builder.show(seat, cards);
+                }
+            } else if let Some((seat, rest)) = builder.find_player(text) {
+                parse_player_line(&mut builder, line, seat, rest)?;
+            }
+        }
+        if !summary {
+            return Err(invalid(last, "hand has no summary"));
+        }
+        builder.return_uncalled();
+        builder.finish(last)
+    }
+}
+
+/// `Winamax Poker - CashGame - HandId: #1-2-3 - Holdem no limit (0.05€/0.10€) - date` or
+/// `Winamax Poker - Tournament "Name" buyIn: 4.50€ + 0.50€ level: 4 - HandId: ... (10/50/100) - date`.
+fn parse_header(line: usize, text: &str) -> Result<HandBuilder, CoreError> {
+    let body = text
+        .strip_prefix(HEADER)
+        .ok_or_else(|| invalid(line, "expected a Winamax hand header"))?;
+    let (kind, rest) = body
+        .split_once(" - HandId: #")
+        .ok_or_else(|| invalid(line, "missing hand id"))?;
+    let (hand_id, rest) = rest
+        .split_once(" - ")
+        .ok_or_else(|| invalid(line, "missing game"))?;
+    let (game, date) = rest.rsplit_once(" - ").unwrap_or((rest, ""));
+    if !game.starts_with("Holdem no limit") {
+        return Err(invalid(line, "only no-limit hold'em is supported"));
+    }
+
+    // Tournaments list the ante first: `(10/50/100)`
+    let stakes = game
+        .split_once('(')
+        .and_then(|(_, s)| s.split_once(')'))
+        .map(|(s, _)| s)
+        .ok_or_else(|| invalid(line, "missing blinds"))?;
+    let amounts = stakes
+        .split('/')
+        .map(parse_amount)
+        .collect::<Result<Vec<f64>, String>>()
+        .map_err(|e| invalid(line, e))?;
+    let (small, big) = match amounts[..] {
+        [small, big] | [_, small, big] if big > 0.0 => (small, big),
+        _ => return Err(invalid(line, format!("invalid blinds '{stakes}'"))),
+    };
+
+    let mut builder = HandBuilder::new("Winamax", hand_id.to_string(), big);
+    let small = builder.chips(small);
+    let hand = builder.hand_mut();
+    hand.blinds.small = small;
+    hand.date = date.to_string();
+    if let Some(tourney) = kind.strip_prefix("Tournament ") {
+        let field = |name: &str| {
+            tourney
+                .split_once(name)
+                .map(|(_, v)| v.split(" level:").next().unwrap_or(v).trim().to_string())
+        };
+        hand.game_type = GameType::MTT;
+        hand.tournament = Some(TournamentInfo {
+            // Filled in from the table name
+            id: String::new(),
+            buy_in: field("buyIn:"),
+            level: field("level:"),
+        });
+    } else if stakes.contains('€') {
+        hand.currency = Some("EUR".to_string());
+    }
+    Ok(builder)
+}
+
+/// `Table: 'Nice 05' 6-max (real money) Seat #4 is the button`; tournament
+/// tables are named `Name(id)#table`.
+fn parse_table(builder: &mut HandBuilder, line: usize, text: &str) -> Result<(), CoreError> {
+    let (name, rest) = text
+        .strip_prefix("Table: '")
+        .and_then(|t| t.rsplit_once('\''))
+        .ok_or_else(|| invalid(line, "expected a table line"))?;
+    let hand = builder.hand_mut();
+    hand.table = name.to_string();
+    hand.max_seats = rest
+        .split_whitespace()
+        .find_map(|w| w.strip_suffix("-max"))
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(9);
+    hand.button_seat = rest
+        .split_once("Seat #")
+        .and_then(|(_, s)| s.split_whitespace().next())
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| invalid(line, "missing button seat"))?;
+    if let Some(info) = hand.tournament.as_mut() {
+        info.id = name
+            .rsplit_once('(')
+            .and_then(|(_, id)| id.split_once(')'))
+            .map_or(name, |(id, _)| id)
+            .to_string();
+    }
+    Ok(())
+}
+
+/// `1: Alice (10€)` or `1: p1 (5250, 2€ bounty)`.
+fn parse_seat(builder: &mut HandBuilder, line: usize, text: &str) -> Result<(), CoreError> {
+    let (number, rest) = text
+        .split_once(": ")
+        .ok_or_else(|| invalid(line, "malformed seat line"))?;
+    let seat = number
+        .parse()
+        .map_err(|_| invalid(line, format!("invalid seat number '{number}'")))?;
+    let (player, details) = rest
+        .rsplit_once(" (")
+        .ok_or_else(|| invalid(line, "seat line has no stack"))?;
+    let stack = details.split([',', ')']).next().unwrap_or_default();
+    let stack = parse_amount(stack).map_err(|e| invalid(line, e))?;
+    let stack = builder.chips(stack);
+    builder.add_seat(seat, player.to_string(), stack, false);
+    if let Some(last) = builder.hand_mut().seats.last_mut() {
+        last.bounty = parse_bounty(details);
+    }
+    Ok(())
+}
+
+/// Returns whether the summary has started.
+fn parse_marker(builder: &mut HandBuilder, line: usize, marker: &str) -> Result<bool, CoreError> {
+    let street = if marker.starts_with("SUMMARY") {
+        return Ok(true);
+    } else if marker.starts_with("ANTE/BLINDS")
+        || marker.starts_with("PRE-FLOP")
+        || marker.starts_with("SHOW DOWN")
+    {
+        return Ok(false);
+    } else if marker.starts_with("FLOP") {
+        Street::Flop
+    } else if marker.starts_with("TURN") {
+        Street::Turn
+    } else if marker.starts_with("RIVER") {
+        Street::River
+    } else {
+        return Err(invalid(line, format!("unsupported section '*** {marker}'")));
+    };
+    let cards = last_bracket_cards(marker)
+        .ok_or_else(|| invalid(line, "street has no cards"))?
+        .map_err(|e| invalid(line, e.to_string()))?;
+    builder.start_street(street, &cards, line)?;
+    Ok(false)
+}
+
+/// Everything after the player's name on an action line.
+fn parse_player_line(
+    builder: &mut HandBuilder,
+    line: usize,
+    seat: usize,
+    rest: &str,
+) -> Result<(), CoreError> {
+    let Some(verb) = rest.strip_prefix(' ') else {
+        return Ok(());
+    };
+    let amount = |builder: &HandBuilder, text: &str, what: &str| {
+        parse_amount(text)
+            .map(|a| builder.chips(a))
+            .map_err(|e| invalid(line, format!("invalid {what}: {e}")))
+    };
+    let (verb, all_in) = match verb.strip_suffix(" and is all-in") {
+        Some(v) => (v, true),
+        None => (verb, false),
+    };
+
+    if verb == "folds" {
+        builder.passive(seat, Action::Fold);
+    } else if verb == "checks" {
+        builder.passive(seat, Action::Check);
+    } else if let Some(a) = verb.strip_prefix("calls ") {
+        let a = amount(builder, a, "call")?;
+        builder.call(seat, a, all_in);
+    } else if let Some(a) = verb.strip_prefix("bets ") {
+        let a = amount(builder, a, "bet")?;
+        builder.bet(seat, a, all_in);
+    } else if let Some(a) = verb.strip_prefix("raises ") {
+        let to = a
+            .split_once(" to ")
+            .map(|(_, to)| to)
+            .ok_or_else(|| invalid(line, format!("invalid raise: '{a}'")))?;
+        let to = amount(builder, to, "raise")?;
+        builder.raise_to(seat, to, all_in);
+    } else if let Some(post) = verb.strip_prefix("posts ") {
+        let (kind, value) = if let Some(a) = post.strip_prefix("small blind ") {
+            (PostKind::SmallBlind, a)
+        } else if let Some(a) = post.strip_prefix("big blind ") {
+            (PostKind::BigBlind, a)
+        } else if let Some(a) = post.strip_prefix("ante ") {
+            (PostKind::Ante, a)
+        } else {
+            return Err(invalid(line, format!("unknown post '{post}'")));
+        };
+        let a = amount(builder, value, "post")?;
+        if kind == PostKind::Ante && builder.hand_mut().blinds.ante.is_zero() {
+            builder.hand_mut().blinds.ante = a;
+        }
+        builder.post(seat, kind, a);
+    } else if let Some(shown) = verb.strip_prefix("shows ") {
+        let cards = two_cards(line, shown)?;
+        builder.show(seat, cards);
+    } else if let Some(collected) = verb.strip_prefix("collected ") {
+        let value = collected.split(" from ").next().unwrap_or(collected);
+        let amount = amount(builder, value, "collected amount")?;
+        builder
+            .hand_mut()
+            .collected
+            .push(Collected { seat, amount });
+    }
+    Ok(())
+}
+
+/// `Total pot 20.13€ | Rake 0.94€` or `Total pot 1690 | No rake`.
+fn parse_total(builder: &mut HandBuilder, line: usize, text: &str) -> Result<(), CoreError> {
+    let (total, rake) = text.split_once('|').unwrap_or((text, ""));
+    let total = parse_amount(total).map_err(|e| invalid(line, e))?;
+    let rake = match rake.trim().strip_prefix("Rake ") {
+        Some(r) => parse_amount(r).map_err(|e| invalid(line, e))?,
+        None => 0.0,
+    };
+    let (total, rake) = (builder.chips(total), builder.chips(rake));
+    let hand = builder.hand_mut();
+    hand.total_pot = total;
+    hand.rake = rake;
+    Ok(())
+}
+
+fn two_cards(line: usize, text: &str) -> Result<[Card; 2], CoreError> {
+    let group = text
+        .find(']')
+        .map(|end| &text[..=end])
+        .ok_or_else(|| invalid(line, "missing hole cards"))?;
+    let cards = last_bracket_cards(group)
+        .ok_or_else(|| invalid(line, "missing hole cards"))?
+        .map_err(|e| invalid(line, e.to_string()))?;
+    match cards[..] {
+        [a, b] if a != b => Ok([a, b]),
+        _ => Err(invalid(line, format!("expected two hole cards in {group}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chips::Chips;
+    use crate::hand_history::HandHistoryReader;
+    use crate::position::Position;
+
+    const HANDS: &str = include_str!("../../tests/fixtures/hand_history/winamax.txt");
+
+    #[test]
+    fn test_winamax_hands() {
+        let mut reader = HandHistoryReader::new(HANDS.as_bytes());
+        let cash = reader.next().expect("cash hand").expect("valid hand");
+        assert_eq!(cash.site, "Winamax");
+        assert_eq!(cash.hand_id, "18876587-492053-1678801234");
+        assert_eq!(cash.table, "Wichita 05");
+        assert_eq!(cash.max_seats, 5);
+        assert_eq!(cash.currency.as_deref(), Some("EUR"));
+        assert_eq!(cash.date, "2023/03/14 13:40:34 UTC");
+        let hero = cash.hero.expect("hero");
+        assert_eq!(cash.seats[hero].position, Some(Position::BTN));
+        assert_eq!(cash.seats[hero].bounty, None);
+        let villain = cash.seat_of("61be3c").expect("villain");
+        let preflop: Vec<Action> = cash
+            .street_actions(Street::Preflop)
+            .map(|a| a.action)
+            .collect();
+        assert_eq!(preflop[1], Action::Raise(Chips::from(0.15)));
+        assert_eq!(cash.board.to_string(), "[Ts 6h 2d Jc]");
+        // Winamax gives the uncalled bet back silently
+        assert_eq!(
+            cash.returned,
+            vec![Collected {
+                seat: hero,
+                amount: Chips::from(0.5)
+            }]
+        );
+        assert_eq!(cash.total_pot, Chips::from(0.72));
+        assert_eq!(cash.rake, Chips::from(0.01));
+        assert_eq!(cash.net(hero), Chips::from(0.36));
+        assert_eq!(cash.net(villain), Chips::from(-0.35));
+
+        let mtt = reader.next().expect("tournament hand").expect("valid hand");
+        assert_eq!(mtt.game_type, GameType::MTT);
+        let info = mtt.tournament.as_ref().expect("tournament");
+        assert_eq!(info.id, "654321987");
+        assert_eq!(info.buy_in.as_deref(), Some("4.50€ + 0.50€"));
+        assert_eq!(info.level.as_deref(), Some("7"));
+        assert_eq!(mtt.big_blind, 200.0);
+        assert_eq!(mtt.blinds.ante, Chips::from(20.0));
+        assert_eq!(mtt.currency, None);
+        // Knockout seats carry the bounty on the player's head
+        let hero = mtt.hero.expect("hero");
+        assert_eq!(mtt.seats[hero].bounty, Some(3.37));
+        let villain = mtt.seat_of("b61e29").expect("villain");
+        assert_eq!(mtt.seats[villain].bounty, Some(2.25));
+        assert_eq!(mtt.seats[villain].position, Some(Position::BTN));
+        let preflop: Vec<_> = mtt.street_actions(Street::Preflop).collect();
+        assert_eq!(preflop[1].action, Action::AllIn(Chips::from(3280.0)));
+        assert_eq!(mtt.total_pot, Chips::from(6940.0));
+        assert_eq!(mtt.net(hero), Chips::from(-3300.0));
+        assert_eq!(mtt.rake, Chips::ZERO);
+        let nets: Chips = (0..mtt.seats.len()).map(|s| mtt.net(s)).sum();
+        assert_eq!(nets, Chips::ZERO);
+    }
+}
@@ -2,21 +2,36 @@ pub mod action;
 pub mod bet_size;
 pub mod board;
 pub mod card;
+pub mod chips;
+pub mod combo_range;
 pub mod deck;
 pub mod error;
+pub mod game_state;
+pub mod game_tree;
 pub mod game_type;
 pub mod hand;
+pub mod hand_history;
 pub mod position;
+pub mod pot;
 pub mod range;
 
 // Re-export primary types for convenience
 pub use action::Action;
-pub use bet_size::BetSize;
+pub use bet_size::{BetContext, BetSize, MAX_SIZE_FACTOR};
 pub use board::{Board, Street};
 pub use card::{Card, Rank, Suit};
+pub use chips::Chips;
+pub use combo_range::{ComboRange, ComboWeight};
 pub use deck::Deck;
 pub use error::CoreError;
+pub use game_state::{Blinds, GameState, LegalActions, Phase, Seat};
+pub use game_tree::{GameTree, NodeKind, StreetConfig, TreeConfig, TreeNode, TreeStats};
 pub use game_type::GameType;
 pub use hand::Hand;
+pub use hand_history::{
+    Collected, HandAction, HandHistory, HandHistoryParser, HandHistoryReader, HandSeat, Post,
+    PostKind, TournamentInfo,
+};
 pub use position::Position;
+pub use pot::{OddChip, Pot, PotAward, Pots, Settlement};
 pub use range::Range;
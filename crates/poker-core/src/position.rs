@@ -44,6 +44,31 @@ impl Position {
     /// Heads-up positions.
     pub const HEADS_UP: [Position; 2] = [Position::BTN, Position::BB];
 
+    /// Positions for a table of `players`, clockwise from the button.
+    /// Heads-up the button is also the small blind. `None` outside 2..=9.
+    pub fn clockwise_from_button(players: usize) -> Option<Vec<Position>> {
+        let early: &[Position] = match players {
+            2 => return Some(Position::HEADS_UP.to_vec()),
+            3 => &[],
+            4 => &[Position::CO],
+            5 => &[Position::MP, Position::CO],
+            6 => &[Position::UTG, Position::MP, Position::CO],
+            7 => &[Position::UTG, Position::LJ, Position::MP, Position::CO],
+            8 => &[
+                Position::UTG,
+                Position::UTG1,
+                Position::LJ,
+                Position::MP,
+                Position::CO,
+            ],
+            9 => &Position::NINE_MAX[..6],
+            _ => return None,
+        };
+        let mut positions = vec![Position::BTN, Position::SB, Position::BB];
+        positions.extend_from_slice(early);
+        Some(positions)
+    }
+
     pub fn label(self) -> &'static str {
         match self {
             Position::UTG => "UTG",
@@ -78,4 +103,27 @@ mod tests {
         assert_eq!(Position::NINE_MAX.len(), 9);
         assert_eq!(Position::HEADS_UP.len(), 2);
     }
+
+    #[test]
+    fn test_clockwise_from_button() {
+        assert_eq!(
+            Position::clockwise_from_button(2),
+            Some(vec![Position::BTN, Position::BB])
+        );
+        let six = Position::clockwise_from_button(6).expect("6-max");
+        assert_eq!(six[..3], [Position::BTN, Position::SB, Position::BB]);
+        assert_eq!(six[3..], [Position::UTG, Position::MP, Position::CO]);
+        for players in 2..=9 {
+            let positions = Position::clockwise_from_button(players).expect("valid");
+            assert_eq!(positions.len(), players);
+            let unique: std::collections::HashSet<_> = positions.iter().collect();
+            assert_eq!(unique.len(), players);
+        }
+        assert_eq!(
+            Position::clockwise_from_button(9).map(|p| p[3]),
+            Some(Position::UTG)
+        );
+        assert!(Position::clockwise_from_button(1).is_none());
+        assert!(Position::clockwise_from_button(10).is_none());
+    }
 }
@@ -0,0 +1,375 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chips::Chips;
+use crate::error::CoreError;
+
+/// One pot: chips and the seats that can win them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pot {
+    pub amount: Chips,
+    /// Seats still in the hand that covered this pot's level, ascending.
+    pub eligible: Vec<usize>,
+}
+
+/// Main pot and side pots built from what each seat put in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pots {
+    /// Main pot first, then side pots in order of increasing stack depth.
+    pots: Vec<Pot>,
+    seats: usize,
+}
+
+/// How to split pots that don't divide evenly.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OddChip {
+    /// Smallest chip in play; shares are whole multiples of it.
+    pub unit: Chips,
+    /// Leftover chips go one at a time to tied winners, starting with the
+    /// first seat left of the button.
+    pub button: usize,
+}
+
+/// Result of awarding one pot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PotAward {
+    pub amount: Chips,
+    /// Winning seats, ascending.
+    pub winners: Vec<usize>,
+    /// Amount each winner receives, in the same order as `winners`.
+    pub shares: Vec<Chips>,
+}
+
+/// Result of settling every pot at showdown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settlement {
+    pub awards: Vec<PotAward>,
+    /// Total chips each seat collects.
+    pub winnings: Vec<Chips>,
+}
+
+impl Pots {
+    /// Build pots from each seat's total contribution to the hand and whether
+    /// it folded. Chips a folded seat put in above the deepest live seat are
+    /// dead money in the top pot. An uncalled bet forms a final pot with a
+    /// single eligible seat, which returns it.
+    pub fn from_contributions(contributions: &[Chips], folded: &[bool]) -> Result<Self, CoreError> {
+        if contributions.len() != folded.len() {
+            return Err(CoreError::InvalidPot(format!(
+                "{} contributions but {} folded flags",
+                contributions.len(),
+                folded.len()
+            )));
+        }
+        if contributions.iter().any(|c| *c < Chips::ZERO) {
+            return Err(CoreError::InvalidPot(
+                "contributions must be non-negative".to_string(),
+            ));
+        }
+        let live: Vec<usize> = (0..contributions.len()).filter(|&s| !folded[s]).collect();
+        if live.is_empty() {
+            return Err(CoreError::InvalidPot("every seat folded".to_string()));
+        }
+
+        let mut levels: Vec<Chips> = live.iter().map(|&s| contributions[s]).collect();
+        levels.sort();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut previous = Chips::ZERO;
+        for &level in &levels {
+            let amount: Chips = contributions
+                .iter()
+                .map(|&c| c.min(level) - c.min(previous))
+                .sum();
+            let eligible: Vec<usize> = live
+                .iter()
+                .copied()
+                .filter(|&s| contributions[s] >= level)
+                .collect();
+            if amount.is_positive() {
+                pots.push(Pot { amount, eligible });
+            }
+            previous = level;
+        }
+
+        let dead: Chips = contributions
+            .iter()
+            .map(|&c| (c - previous).max(Chips::ZERO))
+            .sum();
+        if dead.is_positive() {
+            match pots.last_mut() {
+                Some(top) => top.amount += dead,
+                None => pots.push(Pot {
+                    amount: dead,
+                    eligible: live,
+                }),
+            }
+        }
+
+        Ok(Pots {
+            pots,
+            seats: contributions.len(),
+        })
+    }
+
+    pub fn pots(&self) -> &[Pot] {
+        &self.pots
+    }
+
+    /// Total chips across all pots.
+    pub fn total(&self) -> Chips {
+        self.pots.iter().map(|p| p.amount).sum()
+    }
+
+    /// Award every pot. `ranks` holds each seat's hand strength as a
+    /// `HandRank` value from the evaluator (lower is stronger), or `None` for
+    /// seats that folded or did not show. A pot with one eligible seat goes
+    /// to it without a showdown.
+    pub fn settle(
+        &self,
+        ranks: &[Option<u16>],
+        odd_chip: OddChip,
+    ) -> Result<Settlement, CoreError> {
+        if ranks.len() != self.seats {
+            return Err(CoreError::InvalidPot(format!(
+                "{} ranks for {} seats",
+                ranks.len(),
+                self.seats
+            )));
+        }
+        if !odd_chip.unit.is_positive() || odd_chip.button >= self.seats {
+            return Err(CoreError::InvalidPot(
+                "odd chip unit must be positive and the button a valid seat".to_string(),
+            ));
+        }
+
+        let mut winnings = vec![Chips::ZERO; self.seats];
+        let mut awards = Vec::with_capacity(self.pots.len());
+        for pot in &self.pots {
+            let winners = if pot.eligible.len() == 1 {
+                pot.eligible.clone()
+            } else {
+                let mut best = u16::MAX;
+                for &seat in &pot.eligible {
+                    let rank = ranks[seat].ok_or_else(|| {
+                        CoreError::InvalidPot(format!(
+                            "seat {seat} is contesting a pot without a rank"
+                        ))
+                    })?;
+                    best = best.min(rank);
+                }
+                pot.eligible
+                    .iter()
+                    .copied()
+                    .filter(|&s| ranks[s] == Some(best))
+                    .collect()
+            };
+            let shares = split(pot.amount, &winners, odd_chip, self.seats);
+            for (&seat, &share) in winners.iter().zip(&shares) {
+                winnings[seat] += share;
+            }
+            awards.push(PotAward {
+                amount: pot.amount,
+                winners,
+                shares,
+            });
+        }
+        Ok(Settlement { awards, winnings })
+    }
+}
+
+/// Split `amount` between `winners` in whole units. Leftover units go one
+/// each to winners in seat order starting left of the button; anything
+/// smaller than a unit goes to the first of them.
+fn split(amount: Chips, winners: &[usize], odd_chip: OddChip, seats: usize) -> Vec<Chips> {
+    let count = winners.len() as i64;
+    if count == 1 {
+        return vec![amount];
+    }
+    let units = amount.cents() / odd_chip.unit.cents();
+    let leftover = (units % count) as usize;
+    let mut shares = vec![odd_chip.unit * (units / count); winners.len()];
+
+    let order = |seat: usize| (seat + seats - odd_chip.button - 1) % seats;
+    let mut by_position: Vec<usize> = (0..winners.len()).collect();
+    by_position.sort_by_key(|&i| order(winners[i]));
+    for &i in by_position.iter().take(leftover) {
+        shares[i] += odd_chip.unit;
+    }
+    let remainder = amount - shares.iter().sum::<Chips>();
+    shares[by_position[0]] += remainder;
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHIP: OddChip = OddChip {
+        unit: Chips::from_units(1),
+        button: 0,
+    };
+
+    fn chips(amounts: &[f64]) -> Vec<Chips> {
+        amounts.iter().map(|&a| Chips::from(a)).collect()
+    }
+
+    fn pots(contributions: &[f64], folded: &[bool]) -> Pots {
+        Pots::from_contributions(&chips(contributions), folded).expect("valid")
+    }
+
+    fn odd_chip(unit: f64, button: usize) -> OddChip {
+        OddChip {
+            unit: Chips::from(unit),
+            button,
+        }
+    }
+
+    #[test]
+    fn test_three_way_all_in_side_pots() {
+        // Seat 0 all-in for 50, seat 1 for 100, seat 2 covers with 200
+        // (100 of it uncalled)
+        let p = pots(&[50.0, 100.0, 200.0], &[false; 3]);
+        let amounts: Vec<Chips> = p.pots().iter().map(|x| x.amount).collect();
+        assert_eq!(amounts, chips(&[150.0, 100.0, 100.0]));
+        assert_eq!(p.pots()[0].eligible, vec![0, 1, 2]);
+        assert_eq!(p.pots()[1].eligible, vec![1, 2]);
+        assert_eq!(p.pots()[2].eligible, vec![2]);
+
+        // Short stack has the best hand, seat 1 beats seat 2
+        let s = p
+            .settle(&[Some(10), Some(20), Some(30)], CHIP)
+            .expect("valid");
+        assert_eq!(s.winnings, chips(&[150.0, 100.0, 100.0]));
+    }
+
+    #[test]
+    fn test_folded_dead_money_and_single_live_seat() {
+        // Seat 2 bet 80 then folded to a shove; its chips stay in the pots
+        let p = pots(&[60.0, 60.0, 80.0], &[false, false, true]);
+        assert_eq!(p.pots().len(), 1);
+        assert_eq!(p.total(), Chips::from_units(200));
+        assert_eq!(p.pots()[0].eligible, vec![0, 1]);
+
+        let p = pots(&[10.0, 30.0, 5.0], &[true, false, true]);
+        let s = p
+            .settle(&[None, None, None], CHIP)
+            .expect("no showdown needed");
+        assert_eq!(s.winnings, chips(&[0.0, 45.0, 0.0]));
+    }
+
+    #[test]
+    fn test_split_pot_odd_chip() {
+        // 3 chips split two ways: the extra chip goes left of the button
+        let p = pots(&[1.0, 1.0, 1.0], &[false, false, false]);
+        let ranks = [Some(5), Some(9), Some(5)];
+        let s = p.settle(&ranks, odd_chip(1.0, 0)).expect("valid");
+        assert_eq!(s.winnings, chips(&[1.0, 0.0, 2.0]));
+        let s = p.settle(&ranks, odd_chip(1.0, 2)).expect("valid");
+        assert_eq!(s.winnings, chips(&[2.0, 0.0, 1.0]));
+
+        // Three-way chop of 100 in 1-chip units
+        let p = pots(&[40.0, 30.0, 30.0], &[false; 3]);
+        let s = p.settle(&[Some(1); 3], odd_chip(1.0, 1)).expect("valid");
+        assert_eq!(s.awards[0].amount, Chips::from_units(90));
+        assert_eq!(s.winnings, chips(&[40.0, 30.0, 30.0]));
+        let p = pots(&[34.0, 33.0, 33.0], &[false; 3]);
+        let s = p.settle(&[Some(1); 3], odd_chip(1.0, 1)).expect("valid");
+        assert_eq!(s.winnings, chips(&[34.0, 33.0, 33.0]));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(Pots::from_contributions(&chips(&[1.0]), &[false, false]).is_err());
+        assert!(Pots::from_contributions(&chips(&[-1.0, 2.0]), &[false, false]).is_err());
+        assert!(Pots::from_contributions(&chips(&[1.0, 2.0]), &[true, true]).is_err());
+        let p = pots(&[10.0, 10.0], &[false, false]);
+        assert!(p.settle(&[Some(1), None], CHIP).is_err());
+        assert!(p.settle(&[Some(1)], CHIP).is_err());
+        assert!(p.settle(&[Some(1), Some(2)], odd_chip(0.0, 0)).is_err());
+    }
+
+    /// Reference settlement: every chip level is its own pot, split exactly.
+    fn reference(contributions: &[u32], folded: &[bool], ranks: &[u16]) -> Vec<f64> {
+        let n = contributions.len();
+        let top = contributions.iter().copied().max().unwrap_or(0);
+        let mut won = vec![0.0; n];
+        let live_top = (0..n)
+            .filter(|&s| !folded[s])
+            .map(|s| contributions[s])
+            .max()
+            .unwrap_or(0);
+        for level in 0..top {
+            let chips = contributions.iter().filter(|&&c| c > level).count() as f64;
+            // Dead chips above every live seat go to the top live level
+            let contest = level.min(live_top - 1);
+            let eligible: Vec<usize> = (0..n)
+                .filter(|&s| !folded[s] && contributions[s] > contest)
+                .collect();
+            let best = eligible.iter().map(|&s| ranks[s]).min().unwrap_or(0);
+            let winners: Vec<usize> = eligible.into_iter().filter(|&s| ranks[s] == best).collect();
+            for &w in &winners {
+                won[w] += chips / winners.len() as f64;
+            }
+        }
+        won
+    }
+
+    fn check_all(n: usize) {
+        let cases = 4usize.pow(n as u32);
+        for stacks in 0..cases {
+            let contributions: Vec<u32> = (0..n)
+                .map(|i| ((stacks / 4usize.pow(i as u32)) % 4 + 1) as u32)
+                .collect();
+            for fold_mask in 0..(1usize << n) {
+                let folded: Vec<bool> = (0..n).map(|i| fold_mask & (1 << i) != 0).collect();
+                if folded.iter().all(|&f| f) {
+                    continue;
+                }
+                for rank_case in 0..3usize.pow(n as u32) {
+                    let ranks: Vec<u16> = (0..n)
+                        .map(|i| ((rank_case / 3usize.pow(i as u32)) % 3) as u16)
+                        .collect();
+                    let amounts: Vec<Chips> = contributions
+                        .iter()
+                        .map(|&c| Chips::from_units(c as i64 * 6))
+                        .collect();
+                    let p = Pots::from_contributions(&amounts, &folded).expect("valid");
+                    let shown: Vec<Option<u16>> = (0..n)
+                        .map(|s| if folded[s] { None } else { Some(ranks[s]) })
+                        .collect();
+                    let s = p.settle(&shown, odd_chip(0.5, 0)).expect("valid");
+
+                    let total: Chips = amounts.iter().sum();
+                    assert_eq!(s.winnings.iter().sum::<Chips>(), total);
+                    assert_eq!(p.total(), total);
+                    let expected = reference(&contributions, &folded, &ranks);
+                    for (won, exp) in s.winnings.iter().zip(&expected) {
+                        // 6 chips per level divide evenly among up to 4 winners
+                        // except for odd half-chips, at most one unit per pot
+                        assert!(
+                            (won.to_f64() - exp * 6.0).abs() <= 0.5 * p.pots().len() as f64 + 1e-9,
+                            "{contributions:?} {folded:?} {ranks:?}: {:?} vs {expected:?}",
+                            s.winnings
+                        );
+                    }
+                    for (seat, &f) in folded.iter().enumerate() {
+                        if f {
+                            assert_eq!(s.winnings[seat], Chips::ZERO);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_exhaustive_three_way() {
+        check_all(3);
+    }
+
+    #[test]
+    fn test_exhaustive_four_way() {
+        check_all(4);
+    }
+}
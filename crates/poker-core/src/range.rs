@@ -1,9 +1,18 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+
+use crate::card::{Card, Rank, Suit};
+use crate::combo_range::ComboRange;
+use crate::error::CoreError;
+use crate::hand::{matrix_index, Hand};
 
 /// A range represented as 169 weight values (0.0 to 1.0) for each canonical hand group.
 /// Index maps to the 13x13 matrix: row*13 + col.
-#[derive(Clone, Serialize, Deserialize)]
+///
+/// Ranges parse from and display as standard text notation, e.g.
+/// `"22+,A5s-A2s,KQo:0.5,T9s:75%"`.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Range {
     weights: Vec<f32>,
 }
@@ -90,6 +99,325 @@ impl fmt::Debug for Range {
     }
 }
 
+impl FromStr for Range {
+    type Err = CoreError;
+
+    /// Parse comma-separated range notation.
+    ///
+    /// Supported entries: pairs (`TT`), hand groups (`AKs`, `AKo`, `AK`),
+    /// plus-ranges (`22+`, `A2s+`), dash-ranges (`99-66`, `A5s-A2s`) and exact
+    /// combos (`AhKh`). Any entry may carry a weight as `:0.5` or `:75%`.
+    /// Later entries overwrite earlier ones, including an exact combo written
+    /// after a group that contains it (`AKs,AsKs:0.5`). Since a 169-cell range
+    /// cannot single out suits, the notation is applied per combo and each
+    /// group takes the mean weight of its combos.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.parse::<ComboRange>()?.to_range())
+    }
+}
+
+impl fmt::Display for Range {
+    /// Canonical notation: pairs first, then each high card from Ace down,
+    /// using the shortest of the suited/offsuit or combined encodings.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tokens = Vec::new();
+
+        let pairs: Vec<Option<f32>> = Rank::ALL
+            .iter()
+            .rev()
+            .map(|&r| self.group_weight(r, r, false))
+            .collect();
+        for (top, bottom, w) in runs(&pairs) {
+            let hi = Rank::ALL[12 - top];
+            let lo = Rank::ALL[12 - bottom];
+            let body = if top == 0 && bottom > 0 {
+                format!("{lo}{lo}+")
+            } else if top == bottom {
+                format!("{hi}{hi}")
+            } else {
+                format!("{hi}{hi}-{lo}{lo}")
+            };
+            tokens.push(with_weight(body, w));
+        }
+
+        for hi in Rank::ALL[1..].iter().rev().copied() {
+            // Kickers from hi-1 down to Two
+            let kickers: Vec<Rank> = Rank::ALL[..hi.index() as usize]
+                .iter()
+                .rev()
+                .copied()
+                .collect();
+            let suited: Vec<Option<f32>> = kickers
+                .iter()
+                .map(|&k| self.group_weight(hi, k, true))
+                .collect();
+            let offsuit: Vec<Option<f32>> = kickers
+                .iter()
+                .map(|&k| self.group_weight(hi, k, false))
+                .collect();
+
+            let mut separate = group_tokens(hi, &kickers, &suited, "s");
+            separate.extend(group_tokens(hi, &kickers, &offsuit, "o"));
+
+            let pick = |keep: fn(Option<f32>, Option<f32>) -> Option<f32>| -> Vec<Option<f32>> {
+                suited
+                    .iter()
+                    .zip(&offsuit)
+                    .map(|(&s, &o)| keep(s, o))
+                    .collect()
+            };
+            let any = pick(|s, o| if s == o { s } else { None });
+            let suited_rest = pick(|s, o| if s != o { s } else { None });
+            let offsuit_rest = pick(|s, o| if s != o { o } else { None });
+            let mut combined = group_tokens(hi, &kickers, &any, "");
+            combined.extend(group_tokens(hi, &kickers, &suited_rest, "s"));
+            combined.extend(group_tokens(hi, &kickers, &offsuit_rest, "o"));
+
+            if joined_len(&combined) <= joined_len(&separate) {
+                tokens.extend(combined);
+            } else {
+                tokens.extend(separate);
+            }
+        }
+
+        write!(f, "{}", tokens.join(","))
+    }
+}
+
+impl Range {
+    /// Weight of a hand group, or None when it is not in the range.
+    fn group_weight(&self, r1: Rank, r2: Rank, suited: bool) -> Option<f32> {
+        let w = self.weight(matrix_index(r1, r2, suited));
+        (w > 0.0).then_some(w)
+    }
+}
+
+/// Collapse consecutive equal weights into `(first, last, weight)` runs.
+fn runs(values: &[Option<f32>]) -> Vec<(usize, usize, f32)> {
+    let mut out: Vec<(usize, usize, f32)> = Vec::new();
+    for (i, v) in values.iter().enumerate() {
+        let Some(w) = *v else { continue };
+        match out.last_mut() {
+            Some((_, last, lw)) if *last + 1 == i && *lw == w => *last = i,
+            _ => out.push((i, i, w)),
+        }
+    }
+    out
+}
+
+/// Tokens for one high card; `values[i]` is the weight with `kickers[i]`.
+fn group_tokens(hi: Rank, kickers: &[Rank], values: &[Option<f32>], suffix: &str) -> Vec<String> {
+    runs(values)
+        .into_iter()
+        .map(|(top, bottom, w)| {
+            let (kt, kb) = (kickers[top], kickers[bottom]);
+            let body = if top == 0 && bottom > 0 {
+                format!("{hi}{kb}{suffix}+")
+            } else if top == bottom {
+                format!("{hi}{kt}{suffix}")
+            } else {
+                format!("{hi}{kt}{suffix}-{hi}{kb}{suffix}")
+            };
+            with_weight(body, w)
+        })
+        .collect()
+}
+
+fn with_weight(body: String, weight: f32) -> String {
+    if weight == 1.0 {
+        body
+    } else {
+        format!("{body}:{weight}")
+    }
+}
+
+fn joined_len(tokens: &[String]) -> usize {
+    tokens.iter().map(|t| t.len() + 1).sum()
+}
+
+/// One entry of range notation, before weights are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RangeEntry {
+    /// Whole hand groups by canonical index.
+    Groups(Vec<usize>),
+    /// A specific hole-card combo like `AhKh`.
+    Combo(Hand),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Suitedness {
+    Pair,
+    Suited,
+    Offsuit,
+    Any,
+}
+
+/// A single hand group as written, e.g. `AKs` or `TT`.
+#[derive(Debug, Clone, Copy)]
+struct GroupSpec {
+    hi: Rank,
+    lo: Rank,
+    kind: Suitedness,
+}
+
+impl GroupSpec {
+    fn indices(self) -> Vec<usize> {
+        match self.kind {
+            Suitedness::Pair => vec![matrix_index(self.hi, self.hi, false)],
+            Suitedness::Suited => vec![matrix_index(self.hi, self.lo, true)],
+            Suitedness::Offsuit => vec![matrix_index(self.hi, self.lo, false)],
+            Suitedness::Any => vec![
+                matrix_index(self.hi, self.lo, true),
+                matrix_index(self.hi, self.lo, false),
+            ],
+        }
+    }
+
+    fn with_kicker(self, lo: Rank) -> Self {
+        GroupSpec { lo, ..self }
+    }
+}
+
+/// Parse range notation into entries with their weights, in input order.
+pub(crate) fn parse_notation(s: &str) -> Result<Vec<(RangeEntry, f32)>, CoreError> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    for raw in s.split(',') {
+        let lead = raw.len() - raw.trim_start().len();
+        let token = raw.trim();
+        if !token.is_empty() {
+            let err = |reason: &str| CoreError::InvalidRangeToken {
+                token: token.to_string(),
+                offset: offset + lead,
+                reason: reason.to_string(),
+            };
+            entries.push(parse_token(token).map_err(|reason| err(&reason))?);
+        }
+        offset += raw.len() + 1;
+    }
+    Ok(entries)
+}
+
+fn parse_token(token: &str) -> Result<(RangeEntry, f32), String> {
+    let (body, weight) = match token.split_once(':') {
+        Some((body, w)) => (body.trim(), parse_weight(w.trim())?),
+        None => (token, 1.0),
+    };
+
+    let entry = if let Some(hand) = parse_combo(body)? {
+        RangeEntry::Combo(hand)
+    } else if let Some((a, b)) = body.split_once('-') {
+        RangeEntry::Groups(parse_dash(parse_group(a.trim())?, parse_group(b.trim())?)?)
+    } else if let Some(base) = body.strip_suffix('+') {
+        RangeEntry::Groups(parse_plus(parse_group(base)?))
+    } else {
+        RangeEntry::Groups(parse_group(body)?.indices())
+    };
+    Ok((entry, weight))
+}
+
+fn parse_weight(s: &str) -> Result<f32, String> {
+    let (num, scale) = match s.strip_suffix('%') {
+        Some(num) => (num.trim(), 100.0),
+        None => (s, 1.0),
+    };
+    let value: f32 = num.parse().map_err(|_| format!("invalid weight '{s}'"))?;
+    let weight = value / scale;
+    if !(0.0..=1.0).contains(&weight) {
+        return Err(format!("weight '{s}' is outside 0..1 (or 0%..100%)"));
+    }
+    Ok(weight)
+}
+
+/// Parse an exact combo like `AhKh`; returns None if the token is not one.
+fn parse_combo(body: &str) -> Result<Option<Hand>, String> {
+    let chars: Vec<char> = body.chars().collect();
+    if chars.len() != 4 || Suit::from_char(chars[1]).is_err() || Suit::from_char(chars[3]).is_err()
+    {
+        return Ok(None);
+    }
+    let card = |r: char, s: char| -> Result<Card, String> {
+        let rank = Rank::from_char(r).map_err(|e| e.to_string())?;
+        let suit = Suit::from_char(s).map_err(|e| e.to_string())?;
+        Ok(Card::new(rank, suit))
+    };
+    let c1 = card(chars[0], chars[1])?;
+    let c2 = card(chars[2], chars[3])?;
+    if c1 == c2 {
+        return Err(format!("combo uses {c1} twice"));
+    }
+    Ok(Some(Hand::new(c1, c2)))
+}
+
+fn parse_group(s: &str) -> Result<GroupSpec, String> {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() < 2 || chars.len() > 3 {
+        return Err(format!("expected a hand like 'AKs' or 'TT', got '{s}'"));
+    }
+    let r1 = Rank::from_char(chars[0]).map_err(|e| e.to_string())?;
+    let r2 = Rank::from_char(chars[1]).map_err(|e| e.to_string())?;
+    let (hi, lo) = if r1 >= r2 { (r1, r2) } else { (r2, r1) };
+    let kind = match chars.get(2) {
+        None if hi == lo => Suitedness::Pair,
+        None => Suitedness::Any,
+        Some(_) if hi == lo => return Err("pairs cannot be suited or offsuit".to_string()),
+        Some('s' | 'S') => Suitedness::Suited,
+        Some('o' | 'O') => Suitedness::Offsuit,
+        Some(c) => return Err(format!("expected 's' or 'o', got '{c}'")),
+    };
+    Ok(GroupSpec { hi, lo, kind })
+}
+
+/// `22+` covers 22..AA; `A2s+` raises the kicker up to one below the high card.
+fn parse_plus(base: GroupSpec) -> Vec<usize> {
+    if base.kind == Suitedness::Pair {
+        Rank::ALL[base.hi.index() as usize..]
+            .iter()
+            .flat_map(|&r| {
+                GroupSpec {
+                    hi: r,
+                    lo: r,
+                    ..base
+                }
+                .indices()
+            })
+            .collect()
+    } else {
+        Rank::ALL[base.lo.index() as usize..base.hi.index() as usize]
+            .iter()
+            .flat_map(|&k| base.with_kicker(k).indices())
+            .collect()
+    }
+}
+
+/// `99-66` covers the pairs between; `A5s-A2s` varies the kicker under a shared high card.
+fn parse_dash(a: GroupSpec, b: GroupSpec) -> Result<Vec<usize>, String> {
+    if a.kind != b.kind {
+        return Err("both ends of a dash range must be the same kind of hand".to_string());
+    }
+    if a.kind == Suitedness::Pair {
+        let (lo, hi) = (
+            a.hi.min(b.hi).index() as usize,
+            a.hi.max(b.hi).index() as usize,
+        );
+        return Ok(Rank::ALL[lo..=hi]
+            .iter()
+            .flat_map(|&r| GroupSpec { hi: r, lo: r, ..a }.indices())
+            .collect());
+    }
+    if a.hi != b.hi {
+        return Err("both ends of a dash range must share the high card".to_string());
+    }
+    let (lo, hi) = (
+        a.lo.min(b.lo).index() as usize,
+        a.lo.max(b.lo).index() as usize,
+    );
+    Ok(Rank::ALL[lo..=hi]
+        .iter()
+        .flat_map(|&k| a.with_kicker(k).indices())
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,4 +448,129 @@ mod tests {
         range.set_weight(0, -1.0); // Should clamp to 0.0
         assert_eq!(range.weight(0), 0.0);
     }
+
+    fn idx(notation: &str) -> usize {
+        (0..169)
+            .find(|&i| crate::hand::notation_for_matrix_cell(i / 13, i % 13) == notation)
+            .expect("valid notation")
+    }
+
+    #[test]
+    fn test_parse_pairs_and_groups() {
+        let range: Range = "22+,AKs,KQo".parse().expect("valid");
+        assert_eq!(range.combo_count(), 13.0 * 6.0 + 4.0 + 12.0);
+        assert_eq!(range.weight(idx("AKs")), 1.0);
+        assert_eq!(range.weight(idx("AKo")), 0.0);
+        assert_eq!(range.weight(idx("KQo")), 1.0);
+
+        let any: Range = "AK".parse().expect("valid");
+        assert_eq!(any.combo_count(), 16.0);
+    }
+
+    #[test]
+    fn test_parse_plus_and_dash_ranges() {
+        let range: Range = "A2s-A5s".parse().expect("valid");
+        for n in ["A2s", "A3s", "A4s", "A5s"] {
+            assert_eq!(range.weight(idx(n)), 1.0, "{n}");
+        }
+        assert_eq!(range.weight(idx("A6s")), 0.0);
+
+        let plus: Range = "KTo+".parse().expect("valid");
+        assert_eq!(plus.combo_count(), 3.0 * 12.0);
+        assert_eq!(plus.weight(idx("KQo")), 1.0);
+
+        let pairs: Range = "TT-77".parse().expect("valid");
+        assert_eq!(pairs.combo_count(), 4.0 * 6.0);
+        assert_eq!(pairs, "77-TT".parse().expect("valid"));
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let range: Range = "KQo:0.5, T9s:75%".parse().expect("valid");
+        assert_eq!(range.weight(idx("KQo")), 0.5);
+        assert_eq!(range.weight(idx("T9s")), 0.75);
+
+        // Later entries overwrite earlier ones
+        let range: Range = "22+,55:0.25".parse().expect("valid");
+        assert_eq!(range.weight(idx("55")), 0.25);
+        assert_eq!(range.weight(idx("66")), 1.0);
+    }
+
+    #[test]
+    fn test_parse_exact_combos() {
+        let range: Range = "AhKh,AsKs,AhKh".parse().expect("valid");
+        assert_eq!(range.weight(idx("AKs")), 0.5);
+        assert_eq!(range.combo_count(), 2.0);
+
+        let pair: Range = "AsAh".parse().expect("valid");
+        assert!((pair.combo_count() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_exact_combo_overwrites_group() {
+        // AsKs drops to 0.5; the other three suited combos stay at 1.
+        let range: Range = "AKs,AsKs:0.5".parse().expect("valid");
+        assert_eq!(range.weight(idx("AKs")), 0.875);
+        assert_eq!(range.combo_count(), 3.5);
+
+        // A later group overwrites the exact combo again.
+        let range: Range = "AsKs:0.5,AKs".parse().expect("valid");
+        assert_eq!(range.weight(idx("AKs")), 1.0);
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_token() {
+        let err = "AA, KQx".parse::<Range>().unwrap_err();
+        match err {
+            CoreError::InvalidRangeToken { token, offset, .. } => {
+                assert_eq!(token, "KQx");
+                assert_eq!(offset, 4);
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+
+        for bad in [
+            "AAs", "AK:1.5", "AK:abc", "A5s-K2s", "99-A2s", "AhAh", "Z2", "AKs+-",
+        ] {
+            assert!(bad.parse::<Range>().is_err(), "{bad} should fail");
+        }
+        assert_eq!("".parse::<Range>().expect("empty"), Range::empty());
+    }
+
+    #[test]
+    fn test_display_canonical() {
+        let cases = [
+            ("", ""),
+            ("AA,KK,QQ", "QQ+"),
+            ("22,33,44", "44-22"),
+            ("A2s,A3s,A4s,A5s", "A5s-A2s"),
+            ("ATs+,ATo+", "AT+"),
+            ("A2s+,ATo+", "A2s+,ATo+"),
+            ("KQo:0.5,T9s:75%", "KQo:0.5,T9s:0.75"),
+            ("AKs", "AKs"),
+        ];
+        for (input, expected) in cases {
+            let range: Range = input.parse().expect("valid");
+            assert_eq!(range.to_string(), expected, "input {input}");
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let inputs = [
+            "22+,A2s-A5s,KQo:0.5,T9s:75%",
+            "77+,A9s+,KTs+,QTs+,JTs,AJo+,KQo",
+            "AA:0.33,KK:0.66,AKs:0.1,AhKh",
+        ];
+        for input in inputs {
+            let range: Range = input.parse().expect("valid");
+            let text = range.to_string();
+            let reparsed: Range = text.parse().expect("canonical text parses");
+            assert_eq!(reparsed, range, "{input} -> {text}");
+            assert_eq!(reparsed.to_string(), text);
+        }
+
+        let full = Range::full();
+        assert_eq!(full.to_string().parse::<Range>().expect("valid"), full);
+    }
 }
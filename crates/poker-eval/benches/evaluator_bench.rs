@@ -1,6 +1,8 @@
-use criterion::{criterion_group, criterion_main, Criterion, Throughput};
-use poker_core::Card;
-use poker_eval::LookupTableEvaluator;
+use criterion::measurement::WallTime;
+use criterion::{criterion_group, criterion_main, BenchmarkGroup, Criterion, Throughput};
+use poker_core::hand::NUM_COMBOS;
+use poker_core::{Card, Hand, Rank, Suit};
+use poker_eval::{Evaluator, LookupTableEvaluator, SevenCardEvaluator};
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
@@ -15,32 +17,53 @@ fn generate_random_7card_hands(count: usize) -> Vec<[Card; 7]> {
     for _ in 0..count {
         let mut deck = all_cards.clone();
         deck.shuffle(&mut rng);
-        hands.push([deck[0], deck[1], deck[2], deck[3], deck[4], deck[5], deck[6]]);
+        hands.push([
+            deck[0], deck[1], deck[2], deck[3], deck[4], deck[5], deck[6],
+        ]);
     }
     hands
 }
 
+fn bench_evaluate_7<E: Evaluator>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    name: &str,
+    eval: &E,
+    hands: &[[Card; 7]],
+) {
+    group.bench_function(name, |b| {
+        b.iter(|| {
+            for hand in hands {
+                criterion::black_box(eval.evaluate_7(hand));
+            }
+        });
+    });
+}
+
 fn bench_7card_evaluation(c: &mut Criterion) {
-    let eval = LookupTableEvaluator::new();
     let hands = generate_random_7card_hands(10_000);
 
     let mut group = c.benchmark_group("hand_evaluation");
     group.throughput(Throughput::Elements(hands.len() as u64));
     group.sample_size(100);
 
-    group.bench_function("evaluate_7_cards", |b| {
-        b.iter(|| {
-            for hand in &hands {
-                criterion::black_box(eval.evaluate_7(hand));
-            }
-        });
-    });
+    bench_evaluate_7(
+        &mut group,
+        "evaluate_7_cards",
+        LookupTableEvaluator::global(),
+        &hands,
+    );
+    bench_evaluate_7(
+        &mut group,
+        "evaluate_7_cards_direct",
+        SevenCardEvaluator::global(),
+        &hands,
+    );
 
     group.finish();
 }
 
 fn bench_5card_evaluation(c: &mut Criterion) {
-    let eval = LookupTableEvaluator::new();
+    let eval = LookupTableEvaluator::global();
 
     let mut rng = StdRng::seed_from_u64(123);
     let all_cards: Vec<Card> = (0..52u8)
@@ -69,5 +92,57 @@ fn bench_5card_evaluation(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_7card_evaluation, bench_5card_evaluation);
+fn bench_many_on_river(c: &mut Criterion) {
+    let board = [
+        Card::new(Rank::Ace, Suit::Spades),
+        Card::new(Rank::King, Suit::Diamonds),
+        Card::new(Rank::Eight, Suit::Spades),
+        Card::new(Rank::Seven, Suit::Hearts),
+        Card::new(Rank::Two, Suit::Spades),
+    ];
+    let hands: Vec<Hand> = (0..NUM_COMBOS)
+        .map(|i| Hand::from_combo_index(i).expect("valid index"))
+        .collect();
+    let board_mask = board.iter().fold(0u64, |m, c| m | c.mask());
+
+    let mut group = c.benchmark_group("river_1326_combos");
+    group.throughput(Throughput::Elements(hands.len() as u64));
+    group.sample_size(100);
+
+    group.bench_function("loop_evaluate_7", |b| {
+        let eval = LookupTableEvaluator::global();
+        b.iter(|| {
+            for &hand in &hands {
+                if hand.mask() & board_mask != 0 {
+                    continue;
+                }
+                let [c0, c1] = hand.cards();
+                criterion::black_box(
+                    eval.evaluate_7(&[c0, c1, board[0], board[1], board[2], board[3], board[4]]),
+                );
+            }
+        });
+    });
+    group.bench_function("evaluate_many_lookup", |b| {
+        let eval = LookupTableEvaluator::global();
+        b.iter(|| criterion::black_box(eval.evaluate_many(&board, &hands)));
+    });
+    group.bench_function("evaluate_many_direct", |b| {
+        let eval = SevenCardEvaluator::global();
+        b.iter(|| criterion::black_box(eval.evaluate_many(&board, &hands)));
+    });
+    group.bench_function("evaluate_many_sorted_direct", |b| {
+        let eval = SevenCardEvaluator::global();
+        b.iter(|| criterion::black_box(eval.evaluate_many_sorted(&board, &hands)));
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_7card_evaluation,
+    bench_5card_evaluation,
+    bench_many_on_river
+);
 criterion_main!(benches);
@@ -0,0 +1,71 @@
+//! Generates the evaluator lookup tables at compile time so that
+//! `LookupTableEvaluator::new()` and `SevenCardEvaluator::new()` only have to
+//! point at static data.
+//!
+//! The generator itself lives in `src/table_gen.rs` and is shared with the
+//! library; only the pieces it references are pulled in here.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+#[allow(dead_code)]
+mod hand_rank {
+    /// Stand-in for the library's `HandRank`; the build only needs the tables.
+    pub struct HandRank(pub u16);
+}
+
+#[allow(dead_code)]
+#[path = "src/fast_hash.rs"]
+mod fast_hash;
+
+#[allow(dead_code)]
+#[path = "src/table_gen.rs"]
+mod table_gen;
+
+fn write_array<T: std::fmt::Display>(out: &mut String, name: &str, ty: &str, data: &[T]) {
+    writeln!(out, "pub static {name}: [{ty}; {}] = [", data.len()).unwrap();
+    for chunk in data.chunks(16) {
+        out.push_str("    ");
+        for v in chunk {
+            write!(out, "{v}, ").unwrap();
+        }
+        out.push('\n');
+    }
+    out.push_str("];\n");
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/table_gen.rs");
+    println!("cargo:rerun-if-changed=src/fast_hash.rs");
+
+    let flush_table = table_gen::generate_flush_table();
+    let unique5 = table_gen::generate_unique5_table();
+    // Sort so the probe layout (and thus the checksum) is reproducible.
+    let mut entries = table_gen::unique5_entries(&unique5);
+    entries.sort_unstable();
+    let fast = fast_hash::FastLookup::from_entries(&entries);
+
+    let checksum = table_gen::table_checksum(&flush_table, fast.keys(), fast.values());
+
+    let flush7 = table_gen::generate_flush7_table(&flush_table);
+    let noflush7 = table_gen::generate_noflush7_table(&flush_table, &unique5);
+    let checksum7 = table_gen::table_checksum(&flush7, &[], &noflush7);
+
+    let mut out = String::new();
+    out.push_str("// @generated by build.rs from src/table_gen.rs. Do not edit.\n");
+    write_array(&mut out, "FLUSH_TABLE", "u16", &flush_table);
+    write_array(&mut out, "UNIQUE5_KEYS", "u32", fast.keys());
+    write_array(&mut out, "UNIQUE5_VALUES", "u16", fast.values());
+    writeln!(out, "pub const UNIQUE5_ENTRIES: usize = {};", entries.len()).unwrap();
+    writeln!(out, "pub const CHECKSUM: u64 = {checksum:#018x};").unwrap();
+    write_array(&mut out, "FLUSH7_TABLE", "u16", &flush7);
+    write_array(&mut out, "NOFLUSH7_TABLE", "u16", &noflush7);
+    writeln!(out, "pub const CHECKSUM7: u64 = {checksum7:#018x};").unwrap();
+
+    let path = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR set by cargo"))
+        .join("lookup_tables.rs");
+    fs::write(path, out).expect("write generated lookup tables");
+}
@@ -0,0 +1,359 @@
+//! All-in expected value: what each player could expect to win from the
+//! moment the betting closed with someone all-in, instead of what the
+//! runout gave them.
+
+use poker_core::{Action, Card, Chips, HandHistory, Pots, Street};
+use serde::{Deserialize, Serialize};
+
+use crate::error::EvalError;
+use crate::evaluator::Evaluator;
+use crate::multiway::{equity_multiway, Holding, MultiwayConfig};
+
+/// Equity in one pot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PotEquity {
+    pub amount: Chips,
+    /// Seats contesting the pot, ascending.
+    pub seats: Vec<usize>,
+    /// Equity of each seat in `seats`.
+    pub equity: Vec<f64>,
+}
+
+/// Expected result of a hand whose money all went in before the river.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AllInEv {
+    /// Street on which the betting closed.
+    pub street: Street,
+    /// Main pot first, then side pots.
+    pub pots: Vec<PotEquity>,
+    /// Chips each seat could expect to collect, in the hand's units. Every
+    /// pot is scaled by what was actually collected over what was put in,
+    /// so the rake is paid in proportion. Left unrounded: a cent is a
+    /// tenth of a big blind at the smallest stakes.
+    pub expected: Vec<f64>,
+}
+
+impl AllInEv {
+    /// A seat's expected share of everything at stake.
+    pub fn equity(&self, seat: usize) -> f64 {
+        let total: f64 = self.expected.iter().sum();
+        if total > 0.0 {
+            self.expected[seat] / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Expected net result of a seat, in the hand's own units.
+    pub fn expected_net(&self, hand: &HandHistory, seat: usize) -> f64 {
+        self.expected[seat] - hand.invested(seat).to_f64()
+    }
+}
+
+/// The all-in expectation of a hand, or `None` when no one was all-in when
+/// the betting closed before the river, the hand did not reach showdown, or
+/// a contender's cards are unknown.
+///
+/// Multiway all-ins are split into main and side pots; each pot's equity is
+/// calculated among the seats contesting it, with the other contenders'
+/// cards dead.
+pub fn all_in_ev<E: Evaluator + ?Sized>(
+    eval: &E,
+    hand: &HandHistory,
+) -> Result<Option<AllInEv>, EvalError> {
+    let n = hand.seats.len();
+    let folded: Vec<bool> = (0..n)
+        .map(|s| {
+            hand.seats[s].sitting_out
+                || hand
+                    .actions
+                    .iter()
+                    .any(|a| a.seat == s && a.action == Action::Fold)
+        })
+        .collect();
+    let live: Vec<usize> = (0..n).filter(|&s| !folded[s]).collect();
+    let Some(last) = hand.actions.last() else {
+        return Ok(None);
+    };
+    let all_in = hand.actions.iter().any(|a| a.all_in && !folded[a.seat]);
+    if live.len() < 2 || !all_in || last.street == Street::River || hand.board.len() < 5 {
+        return Ok(None);
+    }
+    let mut cards = vec![None; n];
+    for &seat in &live {
+        let Some(hand) = hand.seats[seat].cards else {
+            return Ok(None);
+        };
+        cards[seat] = Some([hand.card1(), hand.card2()]);
+    }
+
+    let dealt = match last.street {
+        Street::Preflop => 0,
+        Street::Flop => 3,
+        Street::Turn => 4,
+        Street::River => 5,
+    };
+    let board = &hand.board.cards()[..dealt];
+    let invested: Vec<Chips> = (0..n).map(|s| hand.invested(s)).collect();
+    let pots = Pots::from_contributions(&invested, &folded)?;
+    let collected: Chips = hand.collected.iter().map(|c| c.amount).sum();
+    let scale = if pots.total().is_positive() {
+        collected.ratio(pots.total())
+    } else {
+        0.0
+    };
+
+    let config = MultiwayConfig::default();
+    let mut expected = vec![0.0; n];
+    let mut equities = Vec::with_capacity(pots.pots().len());
+    for pot in pots.pots() {
+        let equity = if pot.eligible.len() == 1 {
+            vec![1.0]
+        } else {
+            let holdings: Vec<Holding> = pot
+                .eligible
+                .iter()
+                .filter_map(|&s| cards[s].map(Holding::Hand))
+                .collect();
+            let dead: Vec<Card> = live
+                .iter()
+                .filter(|s| !pot.eligible.contains(s))
+                .filter_map(|&s| cards[s])
+                .flatten()
+                .collect();
+            let result = equity_multiway(eval, &holdings, board, &dead, &config)?;
+            result.players.iter().map(|p| p.equity).collect()
+        };
+        for (&seat, &e) in pot.eligible.iter().zip(&equity) {
+            expected[seat] += pot.amount.to_f64() * e * scale;
+        }
+        equities.push(PotEquity {
+            amount: pot.amount,
+            seats: pot.eligible.clone(),
+            equity,
+        });
+    }
+    Ok(Some(AllInEv {
+        street: last.street,
+        pots: equities,
+        expected,
+    }))
+}
+
+/// One hand of a player's session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvHand {
+    pub hand_id: String,
+    /// The player's share of everything at stake, for all-in hands.
+    pub equity: Option<f64>,
+    /// Actual and expected result in big blinds. They differ only in
+    /// all-in hands.
+    pub net: f64,
+    pub ev_net: f64,
+    /// Actual minus expected results over the session up to this hand.
+    pub running_difference: f64,
+}
+
+/// A player's actual results next to their all-in adjusted ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvSession {
+    pub player: String,
+    pub hands: Vec<EvHand>,
+}
+
+impl EvSession {
+    pub fn new(player: &str) -> Self {
+        EvSession {
+            player: player.to_string(),
+            hands: Vec::new(),
+        }
+    }
+
+    /// Add a hand. Returns whether the player was dealt in.
+    pub fn add_hand<E: Evaluator + ?Sized>(
+        &mut self,
+        eval: &E,
+        hand: &HandHistory,
+    ) -> Result<bool, EvalError> {
+        let Some(seat) = hand.seat_of(&self.player) else {
+            return Ok(false);
+        };
+        if hand.seats[seat].sitting_out {
+            return Ok(false);
+        }
+        let net = hand.in_bb(hand.net(seat));
+        let all_in = all_in_ev(eval, hand)?;
+        let (equity, ev_net) = match &all_in {
+            Some(ev) => (
+                Some(ev.equity(seat)),
+                ev.expected_net(hand, seat) / hand.big_blind,
+            ),
+            None => (None, net),
+        };
+        let running_difference = self.difference() + net - ev_net;
+        self.hands.push(EvHand {
+            hand_id: hand.hand_id.clone(),
+            equity,
+            net,
+            ev_net,
+            running_difference,
+        });
+        Ok(true)
+    }
+
+    /// Actual winnings in big blinds.
+    pub fn net(&self) -> f64 {
+        self.hands.iter().map(|h| h.net).sum()
+    }
+
+    /// All-in adjusted winnings in big blinds.
+    pub fn ev_net(&self) -> f64 {
+        self.hands.iter().map(|h| h.ev_net).sum()
+    }
+
+    /// Actual minus expected winnings: positive when running above EV.
+    pub fn difference(&self) -> f64 {
+        self.hands.last().map_or(0.0, |h| h.running_difference)
+    }
+
+    pub fn bb_per_100(&self) -> Option<f64> {
+        per_100(self.net(), self.hands.len())
+    }
+
+    pub fn ev_bb_per_100(&self) -> Option<f64> {
+        per_100(self.ev_net(), self.hands.len())
+    }
+}
+
+fn per_100(amount: f64, hands: usize) -> Option<f64> {
+    (hands > 0).then(|| amount / hands as f64 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup_table::LookupTableEvaluator;
+    use poker_core::HandHistoryReader;
+
+    const CASH: &str =
+        include_str!("../../poker-core/tests/fixtures/hand_history/pokerstars_cash.txt");
+
+    const THREE_WAY: &str =
+        include_str!("../../poker-core/tests/fixtures/hand_history/pokerstars_side_pot.txt");
+
+    fn parse(text: &str) -> Vec<HandHistory> {
+        HandHistoryReader::new(text.as_bytes())
+            .collect::<Result<_, _>>()
+            .expect("valid hands")
+    }
+
+    fn card(s: &str) -> Card {
+        Card::from_str_notation(s).expect("valid card")
+    }
+
+    #[test]
+    fn test_turn_all_in() {
+        let eval = LookupTableEvaluator::global();
+        let hands = parse(CASH);
+        let hand = &hands[0];
+        let ev = all_in_ev(eval, hand).expect("valid").expect("all-in");
+        assert_eq!(ev.street, Street::Turn);
+        assert_eq!(ev.pots.len(), 1);
+        let hero = hand.hero.expect("hero");
+        let bob = hand.seat_of("Bob").expect("Bob");
+        // Bob has two queens among 44 rivers
+        assert!((ev.equity(bob) - 2.0 / 44.0).abs() < 1e-4);
+        assert!((ev.equity(hero) - 42.0 / 44.0).abs() < 1e-4);
+        assert_eq!(ev.equity(hand.seat_of("Alice").expect("Alice")), 0.0);
+        // The pot was $20.13; $19.19 was collected after the rake
+        assert!((ev.expected[hero] - 19.19 * 42.0 / 44.0).abs() < 1e-9);
+        assert_eq!(
+            ev.expected_net(hand, hero),
+            ev.expected[hero] - hand.invested(hero).to_f64()
+        );
+
+        // No showdown, no all-in
+        assert!(all_in_ev(eval, &hands[1]).expect("valid").is_none());
+    }
+
+    #[test]
+    fn test_multiway_side_pot() {
+        let eval = LookupTableEvaluator::global();
+        let hand = &parse(THREE_WAY)[0];
+        let ev = all_in_ev(eval, hand).expect("valid").expect("all-in");
+        assert_eq!(ev.street, Street::Flop);
+        let seat = |name: &str| hand.seat_of(name).expect("seated");
+        let (alice, bob, hero) = (seat("Alice"), seat("Bob"), seat("Hero"));
+
+        // $15 main pot between all three, $10 side pot without Alice
+        assert_eq!(ev.pots.len(), 2);
+        assert_eq!(ev.pots[0].amount, Chips::from_units(15));
+        assert_eq!(ev.pots[0].seats, [alice, bob, hero]);
+        assert_eq!(ev.pots[1].amount, Chips::from_units(10));
+        assert_eq!(ev.pots[1].seats, [bob, hero]);
+
+        // Alice's queens are out of the deck for the side pot
+        let flop = [card("As"), card("7d"), card("2c")];
+        let side = equity_multiway(
+            eval,
+            &[
+                Holding::Hand([card("7s"), card("7h")]),
+                Holding::Hand([card("Ah"), card("Kh")]),
+            ],
+            &flop,
+            &[card("Qs"), card("Qd")],
+            &MultiwayConfig::default(),
+        )
+        .expect("valid");
+        assert!((ev.pots[1].equity[0] - side.players[0].equity).abs() < 1e-12);
+        let main: f64 = ev.pots[0].equity.iter().sum();
+        assert!((main - 1.0).abs() < 1e-9);
+
+        // 5% rake comes off every pot
+        let bob_ev = 0.95 * (15.0 * ev.pots[0].equity[1] + 10.0 * ev.pots[1].equity[0]);
+        assert!((ev.expected[bob] - bob_ev).abs() < 1e-9);
+        let total: f64 = ev.expected.iter().sum();
+        assert!((total - 23.75).abs() < 1e-9);
+        assert_eq!(ev.expected_net(hand, alice), ev.expected[alice] - 5.0);
+    }
+
+    #[test]
+    fn test_session_running_difference() {
+        let eval = LookupTableEvaluator::global();
+        let mut hands = parse(CASH);
+        hands.extend(parse(THREE_WAY));
+        let mut session = EvSession::new("Hero");
+        for hand in &hands {
+            assert!(session.add_hand(eval, hand).expect("valid"));
+        }
+        assert!(!EvSession::new("Nobody")
+            .add_hand(eval, &hands[0])
+            .expect("valid"));
+
+        let [first, second, third] = &session.hands[..] else {
+            panic!("three hands");
+        };
+        // Hero won the turn all-in as a big favourite: ran above EV
+        assert!(first.equity.is_some_and(|e| e > 0.9));
+        assert!(first.running_difference > 0.0);
+        assert_eq!(second.equity, None);
+        assert_eq!(second.net, second.ev_net);
+        assert_eq!(second.running_difference, first.running_difference);
+        // Then lost a three-way pot with some equity: ran below EV there
+        assert_eq!(third.net, -100.0);
+        assert!(third.ev_net > third.net);
+        assert!(
+            (third.running_difference - (first.running_difference + third.net - third.ev_net))
+                .abs()
+                < 1e-9
+        );
+
+        assert!((session.net() - (91.5 + 1.0 - 100.0)).abs() < 1e-9);
+        assert!((session.difference() - (session.net() - session.ev_net())).abs() < 1e-9);
+        let actual = session.bb_per_100().expect("hands");
+        assert!((actual - session.net() / 3.0 * 100.0).abs() < 1e-9);
+        // Still above EV over the session
+        assert!(session.difference() > 0.0);
+        assert!(session.ev_bb_per_100().expect("hands") < actual);
+    }
+}
@@ -0,0 +1,184 @@
+//! Ranking many hole-card combos against one board.
+//!
+//! Solvers and range equity rank hundreds or thousands of combos against the
+//! same river. `Evaluator::evaluate_many` returns one `HandRank` per combo,
+//! with `HandRank::INVALID` for combos that clash with the board.
+//! `RankedHands` sorts those results into flat parallel arrays grouped by
+//! tie class, so showdown loops can walk them without branching on ties.
+
+use poker_core::{Card, Hand};
+
+use crate::hand_rank::HandRank;
+
+/// Card mask of a river board, or `None` if it repeats a card.
+#[inline]
+pub(crate) fn board_mask(board: &[Card; 5]) -> Option<u64> {
+    let mask = board.iter().fold(0u64, |m, c| m | c.mask());
+    (mask.count_ones() == 5).then_some(mask)
+}
+
+/// Whether a combo can be dealt alongside a board with the given mask.
+#[inline]
+pub(crate) fn is_live(hand: Hand, board_mask: u64) -> bool {
+    let mask = hand.mask();
+    mask.count_ones() == 2 && mask & board_mask == 0
+}
+
+/// Combos sorted by strength, laid out as structure-of-arrays.
+///
+/// `indices[i]` and `ranks[i]` describe the `i`-th strongest valid combo;
+/// `class_starts` holds the offset where each tie class begins, followed by
+/// a final entry equal to `len()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RankedHands {
+    /// Input positions of the valid combos, strongest first.
+    pub indices: Vec<u32>,
+    /// Rank value of each entry in `indices` (lower is stronger).
+    pub ranks: Vec<u16>,
+    /// Start offset of every tie class, plus a trailing end offset.
+    pub class_starts: Vec<u32>,
+    /// Input positions of combos that conflict with the board.
+    pub invalid: Vec<u32>,
+}
+
+impl RankedHands {
+    /// Sort per-combo ranks (as returned by `evaluate_many`) into tie classes.
+    pub fn from_ranks(ranks: &[HandRank]) -> Self {
+        let mut invalid = Vec::new();
+        // Rank in the high half, input position in the low half: one integer
+        // sort orders by strength and keeps ties in input order.
+        let mut keys: Vec<u64> = Vec::with_capacity(ranks.len());
+        for (i, rank) in ranks.iter().enumerate() {
+            if rank.is_valid() {
+                keys.push(((rank.value() as u64) << 32) | i as u64);
+            } else {
+                invalid.push(i as u32);
+            }
+        }
+        keys.sort_unstable();
+
+        let indices: Vec<u32> = keys.iter().map(|&k| k as u32).collect();
+        let ranks: Vec<u16> = keys.iter().map(|&k| (k >> 32) as u16).collect();
+
+        let mut class_starts = Vec::new();
+        for i in 0..ranks.len() {
+            if i == 0 || ranks[i] != ranks[i - 1] {
+                class_starts.push(i as u32);
+            }
+        }
+        class_starts.push(ranks.len() as u32);
+
+        RankedHands {
+            indices,
+            ranks,
+            class_starts,
+            invalid,
+        }
+    }
+
+    /// Number of valid combos.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Whether no combo could be ranked.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Number of distinct tie classes.
+    pub fn class_count(&self) -> usize {
+        self.class_starts.len() - 1
+    }
+
+    /// Input positions of the combos in tie class `class` (0 = strongest).
+    pub fn class(&self, class: usize) -> &[u32] {
+        let start = self.class_starts[class] as usize;
+        let end = self.class_starts[class + 1] as usize;
+        &self.indices[start..end]
+    }
+
+    /// Rank shared by every combo in tie class `class`.
+    pub fn class_rank(&self, class: usize) -> HandRank {
+        HandRank(self.ranks[self.class_starts[class] as usize])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluator::Evaluator;
+    use crate::lookup_table::LookupTableEvaluator;
+    use crate::seven_card::SevenCardEvaluator;
+    use poker_core::hand::NUM_COMBOS;
+    use poker_core::{Rank, Suit};
+
+    fn river() -> [Card; 5] {
+        [
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::King, Suit::Spades),
+            Card::new(Rank::Seven, Suit::Hearts),
+            Card::new(Rank::Seven, Suit::Diamonds),
+            Card::new(Rank::Two, Suit::Clubs),
+        ]
+    }
+
+    fn all_combos() -> Vec<Hand> {
+        (0..NUM_COMBOS)
+            .map(|i| Hand::from_combo_index(i).expect("valid index"))
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluate_many_marks_board_conflicts() {
+        let board = river();
+        let hands = all_combos();
+        let lookup = LookupTableEvaluator::global().evaluate_many(&board, &hands);
+        let direct = SevenCardEvaluator::global().evaluate_many(&board, &hands);
+        assert_eq!(lookup, direct);
+
+        let mask = board_mask(&board).expect("distinct board");
+        for (hand, rank) in hands.iter().zip(&lookup) {
+            assert_eq!(rank.is_valid(), hand.mask() & mask == 0, "{hand}");
+        }
+        // C(47, 2) combos avoid the board.
+        assert_eq!(lookup.iter().filter(|r| r.is_valid()).count(), 1081);
+    }
+
+    #[test]
+    fn test_duplicate_board_is_all_invalid() {
+        let mut board = river();
+        board[4] = board[0];
+        let ranks = SevenCardEvaluator::global().evaluate_many(&board, &all_combos());
+        assert!(ranks.iter().all(|r| !r.is_valid()));
+        let ranked = RankedHands::from_ranks(&ranks);
+        assert!(ranked.is_empty());
+        assert_eq!(ranked.class_count(), 0);
+        assert_eq!(ranked.invalid.len(), NUM_COMBOS);
+    }
+
+    #[test]
+    fn test_ranked_hands_groups_ties() {
+        let board = river();
+        let hands = all_combos();
+        let eval = SevenCardEvaluator::global();
+        let ranks = eval.evaluate_many(&board, &hands);
+        let ranked = eval.evaluate_many_sorted(&board, &hands);
+
+        assert_eq!(ranked.len() + ranked.invalid.len(), NUM_COMBOS);
+        assert!(ranked.ranks.windows(2).all(|w| w[0] <= w[1]));
+        for class in 0..ranked.class_count() {
+            let rank = ranked.class_rank(class);
+            let members = ranked.class(class);
+            assert!(!members.is_empty());
+            assert!(members.iter().all(|&i| ranks[i as usize] == rank));
+            if class > 0 {
+                assert!(ranked.class_rank(class - 1) > rank);
+            }
+        }
+        // The nuts on A-K-7-7-2: quad sevens, a single combo.
+        assert_eq!(ranked.class(0).len(), 1);
+        let nuts = hands[ranked.class(0)[0] as usize];
+        assert!(nuts.cards().iter().all(|c| c.rank() == Rank::Seven));
+    }
+}
@@ -1,11 +1,18 @@
-use poker_core::{Card, Rank, Suit};
+use std::time::{Duration, Instant};
 
-use crate::lookup_table::LookupTableEvaluator;
+use poker_core::{Card, ComboRange, Hand, Rank, Suit};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::error::EvalError;
+use crate::evaluator::Evaluator;
+use crate::range_equity::validate_board;
 
 /// Calculate equity of hand1 vs hand2 on a given board by enumeration.
 /// Returns (equity_hand1, equity_hand2) as fractions summing to 1.0.
-pub fn equity_heads_up(
-    eval: &LookupTableEvaluator,
+pub fn equity_heads_up<E: Evaluator + ?Sized>(
+    eval: &E,
     hand1: [Card; 2],
     hand2: [Card; 2],
     board: &[Card],
@@ -164,9 +171,270 @@ pub fn equity_heads_up(
     (eq1, eq2)
 }
 
+/// Stopping rules for a Monte Carlo equity estimate.
+///
+/// Samples are drawn in batches and the time budget and precision target are
+/// only checked between batches, so a run that stops on precision is fully
+/// determined by its seed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloConfig {
+    /// Hard cap on the number of sampled runouts.
+    pub max_iterations: u64,
+    /// Optional wall-clock budget.
+    pub time_budget: Option<Duration>,
+    /// Stop once the standard error of the equity falls below this value.
+    pub target_std_error: Option<f64>,
+    /// RNG seed. The same seed and iteration count give the same result.
+    pub seed: u64,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        MonteCarloConfig {
+            max_iterations: 1_000_000,
+            time_budget: None,
+            target_std_error: None,
+            seed: 0,
+        }
+    }
+}
+
+/// Number of samples between checks of the time budget and precision target.
+const MC_BATCH: u64 = 1_000;
+
+/// Give up after this many consecutive samples that conflict on cards.
+pub(crate) const MC_MAX_REJECTIONS: u32 = 10_000;
+
+/// Result of a Monte Carlo equity estimate, from player 1's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloEquity {
+    /// Estimated equity: win + tie / 2.
+    pub equity: f64,
+    pub win: f64,
+    pub tie: f64,
+    /// Standard error of `equity`.
+    pub std_error: f64,
+    /// Number of runouts sampled.
+    pub iterations: u64,
+}
+
+impl MonteCarloEquity {
+    /// Confidence interval `equity +/- z * std_error`, clamped to [0, 1].
+    /// Use z = 1.96 for 95%.
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let half = z * self.std_error;
+        ((self.equity - half).max(0.0), (self.equity + half).min(1.0))
+    }
+}
+
+/// Estimate equity of hand1 vs hand2 by sampling runouts.
+pub fn equity_monte_carlo<E: Evaluator + ?Sized>(
+    eval: &E,
+    hand1: [Card; 2],
+    hand2: [Card; 2],
+    board: &[Card],
+    config: &MonteCarloConfig,
+) -> Result<MonteCarloEquity, EvalError> {
+    let single = |cards: [Card; 2]| {
+        let mut range = ComboRange::empty();
+        range.set_weight(Hand::new(cards[0], cards[1]), 1.0);
+        range
+    };
+    if hand1[0] == hand1[1] || hand2[0] == hand2[1] {
+        return Err(EvalError::DuplicateCard);
+    }
+    range_equity_monte_carlo(eval, &single(hand1), &single(hand2), board, config)
+}
+
+/// Estimate equity of range1 vs range2 by sampling a combo from each range
+/// (proportional to weight, rejecting card conflicts) and then a runout.
+pub fn range_equity_monte_carlo<E: Evaluator + ?Sized>(
+    eval: &E,
+    range1: &ComboRange,
+    range2: &ComboRange,
+    board: &[Card],
+    config: &MonteCarloConfig,
+) -> Result<MonteCarloEquity, EvalError> {
+    let board_mask = validate_board(board)?;
+    let sampler1 = ComboSampler::new(range1, board_mask).ok_or(EvalError::NoLiveCombos)?;
+    let sampler2 = ComboSampler::new(range2, board_mask).ok_or(EvalError::NoLiveCombos)?;
+
+    let mut stats = OutcomeStats::default();
+    let mut runout = [Card::new(Rank::Two, Suit::Clubs); 5];
+    let mut cards1 = [Card::new(Rank::Two, Suit::Clubs); 7];
+    let mut cards2 = cards1;
+
+    run_batches(
+        config,
+        &mut stats,
+        |stats, rng| {
+            let (h1, h2) = sample_pair(&sampler1, &sampler2, rng)?;
+            draw_runout(rng, board, board_mask | h1.mask() | h2.mask(), &mut runout);
+            cards1[0] = h1.card1();
+            cards1[1] = h1.card2();
+            cards2[0] = h2.card1();
+            cards2[1] = h2.card2();
+            cards1[2..].copy_from_slice(&runout);
+            cards2[2..].copy_from_slice(&runout);
+
+            let r1 = eval.evaluate_7(&cards1);
+            let r2 = eval.evaluate_7(&cards2);
+            stats.record(r1.compare(r2));
+            Ok(())
+        },
+        OutcomeStats::std_error,
+    )?;
+
+    Ok(stats.finish())
+}
+
+/// Run `step` in batches of `MC_BATCH` until `max_iterations` is reached or,
+/// checked between batches, the time budget or precision target is met.
+/// Returns the number of steps taken.
+pub(crate) fn run_batches<S>(
+    config: &MonteCarloConfig,
+    state: &mut S,
+    mut step: impl FnMut(&mut S, &mut StdRng) -> Result<(), EvalError>,
+    std_error: impl Fn(&S) -> f64,
+) -> Result<u64, EvalError> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let start = Instant::now();
+    let mut n = 0u64;
+
+    while n < config.max_iterations {
+        let batch_end = (n + MC_BATCH).min(config.max_iterations);
+        while n < batch_end {
+            step(state, &mut rng)?;
+            n += 1;
+        }
+
+        if let Some(target) = config.target_std_error {
+            if std_error(state) <= target {
+                break;
+            }
+        }
+        if let Some(budget) = config.time_budget {
+            if start.elapsed() >= budget {
+                break;
+            }
+        }
+    }
+    Ok(n)
+}
+
+/// Complete `board` to 5 cards with a uniform draw from the cards not in `dead_mask`.
+pub(crate) fn draw_runout(rng: &mut StdRng, board: &[Card], dead_mask: u64, out: &mut [Card; 5]) {
+    out[..board.len()].copy_from_slice(board);
+    let mut deck = [Card::new(Rank::Two, Suit::Clubs); 52];
+    let mut len = 0;
+    for i in 0..52u8 {
+        if dead_mask & (1u64 << i) == 0 {
+            if let Ok(c) = Card::from_u8(i) {
+                deck[len] = c;
+                len += 1;
+            }
+        }
+    }
+    // Partial Fisher-Yates over the live deck
+    for (drawn, slot) in (board.len()..5).enumerate() {
+        let pick = rng.gen_range(drawn..len);
+        deck.swap(drawn, pick);
+        out[slot] = deck[drawn];
+    }
+}
+
+/// Weighted sampler over the combos of a range not blocked by the board.
+pub(crate) struct ComboSampler {
+    hands: Vec<Hand>,
+    cumulative: Vec<f64>,
+}
+
+impl ComboSampler {
+    pub(crate) fn new(range: &ComboRange, board_mask: u64) -> Option<Self> {
+        let mut hands = Vec::new();
+        let mut cumulative = Vec::new();
+        let mut total = 0.0;
+        for (hand, w) in range.iter() {
+            if hand.mask() & board_mask == 0 {
+                total += w as f64;
+                hands.push(hand);
+                cumulative.push(total);
+            }
+        }
+        (total > 0.0).then_some(ComboSampler { hands, cumulative })
+    }
+
+    pub(crate) fn sample(&self, rng: &mut StdRng) -> Hand {
+        let total = self.cumulative[self.cumulative.len() - 1];
+        let x = rng.gen::<f64>() * total;
+        let idx = self.cumulative.partition_point(|&c| c <= x);
+        self.hands[idx.min(self.hands.len() - 1)]
+    }
+}
+
+/// Draw one non-conflicting combo from each sampler.
+fn sample_pair(
+    s1: &ComboSampler,
+    s2: &ComboSampler,
+    rng: &mut StdRng,
+) -> Result<(Hand, Hand), EvalError> {
+    for _ in 0..MC_MAX_REJECTIONS {
+        let h1 = s1.sample(rng);
+        let h2 = s2.sample(rng);
+        if h1.mask() & h2.mask() == 0 {
+            return Ok((h1, h2));
+        }
+    }
+    Err(EvalError::NoLiveCombos)
+}
+
+/// Running win/tie counts. Each sample scores 1, 0.5 or 0, so the variance
+/// follows from the counts alone.
+#[derive(Default)]
+struct OutcomeStats {
+    n: u64,
+    wins: u64,
+    ties: u64,
+}
+
+impl OutcomeStats {
+    fn record(&mut self, outcome: std::cmp::Ordering) {
+        self.n += 1;
+        match outcome {
+            std::cmp::Ordering::Greater => self.wins += 1,
+            std::cmp::Ordering::Equal => self.ties += 1,
+            std::cmp::Ordering::Less => {}
+        }
+    }
+
+    /// Standard error of the mean of per-sample equity (1, 0.5 or 0).
+    fn std_error(&self) -> f64 {
+        if self.n < 2 {
+            return f64::INFINITY;
+        }
+        let n = self.n as f64;
+        let mean = (self.wins as f64 + 0.5 * self.ties as f64) / n;
+        let mean_sq = (self.wins as f64 + 0.25 * self.ties as f64) / n;
+        let variance = (mean_sq - mean * mean).max(0.0) * n / (n - 1.0);
+        (variance / n).sqrt()
+    }
+
+    fn finish(&self) -> MonteCarloEquity {
+        let n = self.n.max(1) as f64;
+        MonteCarloEquity {
+            equity: (self.wins as f64 + 0.5 * self.ties as f64) / n,
+            win: self.wins as f64 / n,
+            tie: self.ties as f64 / n,
+            std_error: self.std_error(),
+            iterations: self.n,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lookup_table::LookupTableEvaluator;
     use poker_core::{Rank, Suit};
 
     fn card(r: Rank, s: Suit) -> Card {
@@ -176,11 +444,8 @@ mod tests {
     #[test]
     fn test_equity_aa_vs_kk_river() {
         // On a dry board, AA should beat KK
-        let eval = LookupTableEvaluator::new();
-        let aa = [
-            card(Rank::Ace, Suit::Spades),
-            card(Rank::Ace, Suit::Hearts),
-        ];
+        let eval = LookupTableEvaluator::global();
+        let aa = [card(Rank::Ace, Suit::Spades), card(Rank::Ace, Suit::Hearts)];
         let kk = [
             card(Rank::King, Suit::Diamonds),
             card(Rank::King, Suit::Clubs),
@@ -192,7 +457,7 @@ mod tests {
             card(Rank::Nine, Suit::Clubs),
             card(Rank::Jack, Suit::Spades),
         ];
-        let (eq1, eq2) = equity_heads_up(&eval, aa, kk, &board);
+        let (eq1, eq2) = equity_heads_up(eval, aa, kk, &board);
         assert!(
             (eq1 - 1.0).abs() < 0.001,
             "AA should have ~100% equity vs KK on this board, got {eq1}"
@@ -202,7 +467,7 @@ mod tests {
 
     #[test]
     fn test_equity_known_matchup_turn() {
-        let eval = LookupTableEvaluator::new();
+        let eval = LookupTableEvaluator::global();
         // AKs vs QQ on a flop that doesn't help either
         let aks = [
             card(Rank::Ace, Suit::Spades),
@@ -218,7 +483,7 @@ mod tests {
             card(Rank::Nine, Suit::Clubs),
             card(Rank::Four, Suit::Spades),
         ];
-        let (eq1, _eq2) = equity_heads_up(&eval, aks, qq, &board);
+        let (eq1, _eq2) = equity_heads_up(eval, aks, qq, &board);
         // AKs has roughly 15-25% equity vs QQ on a blank board with turn dealt
         assert!(
             eq1 > 0.05 && eq1 < 0.50,
@@ -226,14 +491,108 @@ mod tests {
         );
     }
 
+    fn config(max_iterations: u64, seed: u64) -> MonteCarloConfig {
+        MonteCarloConfig {
+            max_iterations,
+            seed,
+            ..MonteCarloConfig::default()
+        }
+    }
+
     #[test]
-    fn test_equity_flopped_set() {
-        let eval = LookupTableEvaluator::new();
-        // Set of aces vs overpair on river
-        let aa = [
+    fn test_monte_carlo_reproducible() {
+        let eval = LookupTableEvaluator::global();
+        let aa = [card(Rank::Ace, Suit::Spades), card(Rank::Ace, Suit::Hearts)];
+        let kk = [
+            card(Rank::King, Suit::Diamonds),
+            card(Rank::King, Suit::Clubs),
+        ];
+        let a = equity_monte_carlo(eval, aa, kk, &[], &config(20_000, 7)).expect("valid");
+        let b = equity_monte_carlo(eval, aa, kk, &[], &config(20_000, 7)).expect("valid");
+        assert_eq!(a, b);
+        assert_eq!(a.iterations, 20_000);
+
+        let c = equity_monte_carlo(eval, aa, kk, &[], &config(20_000, 8)).expect("valid");
+        assert_ne!(a.equity, c.equity);
+    }
+
+    #[test]
+    fn test_monte_carlo_matches_enumeration() {
+        let eval = LookupTableEvaluator::global();
+        let aks = [
             card(Rank::Ace, Suit::Spades),
-            card(Rank::Ace, Suit::Hearts),
+            card(Rank::King, Suit::Spades),
         ];
+        let qq = [
+            card(Rank::Queen, Suit::Hearts),
+            card(Rank::Queen, Suit::Diamonds),
+        ];
+        let board = [
+            card(Rank::Two, Suit::Hearts),
+            card(Rank::Seven, Suit::Spades),
+            card(Rank::Nine, Suit::Clubs),
+        ];
+        let (exact, _) = equity_heads_up(eval, aks, qq, &board);
+        let mc = equity_monte_carlo(eval, aks, qq, &board, &config(50_000, 1)).expect("valid");
+        let (lo, hi) = mc.confidence_interval(4.0);
+        assert!(lo <= exact && exact <= hi, "{exact} not in [{lo}, {hi}]");
+        assert!(mc.win + mc.tie <= 1.0);
+    }
+
+    #[test]
+    fn test_monte_carlo_stops_at_precision() {
+        let eval = LookupTableEvaluator::global();
+        let aa = [card(Rank::Ace, Suit::Spades), card(Rank::Ace, Suit::Hearts)];
+        let kk = [
+            card(Rank::King, Suit::Diamonds),
+            card(Rank::King, Suit::Clubs),
+        ];
+        let cfg = MonteCarloConfig {
+            max_iterations: 10_000_000,
+            target_std_error: Some(0.005),
+            seed: 3,
+            ..MonteCarloConfig::default()
+        };
+        let result = equity_monte_carlo(eval, aa, kk, &[], &cfg).expect("valid");
+        assert!(result.std_error <= 0.005);
+        assert!(result.iterations < 20_000, "took {}", result.iterations);
+        assert!((result.equity - 0.82).abs() < 0.03, "{}", result.equity);
+
+        let timed = MonteCarloConfig {
+            time_budget: Some(Duration::from_millis(1)),
+            ..cfg
+        };
+        let result = equity_monte_carlo(eval, aa, kk, &[], &timed).expect("valid");
+        assert!(result.iterations >= MC_BATCH);
+    }
+
+    #[test]
+    fn test_monte_carlo_ranges() {
+        let eval = LookupTableEvaluator::global();
+        let r1: ComboRange = "AA,KK".parse().expect("valid");
+        let r2: ComboRange = "AKs".parse().expect("valid");
+        let board = [
+            card(Rank::Two, Suit::Hearts),
+            card(Rank::Seven, Suit::Spades),
+            card(Rank::Nine, Suit::Clubs),
+        ];
+        let exact =
+            crate::range_equity::equity_range_vs_range(eval, &r1, &r2, &board).expect("valid");
+        let mc =
+            range_equity_monte_carlo(eval, &r1, &r2, &board, &config(50_000, 11)).expect("valid");
+        let (lo, hi) = mc.confidence_interval(4.0);
+        assert!(lo <= exact.equity && exact.equity <= hi);
+
+        let blocked: ComboRange = "AhAs".parse().expect("valid");
+        let same = range_equity_monte_carlo(eval, &blocked, &blocked, &[], &config(10, 0));
+        assert!(same.is_err());
+    }
+
+    #[test]
+    fn test_equity_flopped_set() {
+        let eval = LookupTableEvaluator::global();
+        // Set of aces vs overpair on river
+        let aa = [card(Rank::Ace, Suit::Spades), card(Rank::Ace, Suit::Hearts)];
         let kk = [
             card(Rank::King, Suit::Diamonds),
             card(Rank::King, Suit::Clubs),
@@ -245,7 +604,7 @@ mod tests {
             card(Rank::Two, Suit::Spades),
             card(Rank::Three, Suit::Diamonds),
         ];
-        let (eq1, _) = equity_heads_up(&eval, aa, kk, &board);
+        let (eq1, _) = equity_heads_up(eval, aa, kk, &board);
         assert!(
             (eq1 - 1.0).abs() < 0.001,
             "Set of aces should beat pair of kings on this river"
@@ -16,6 +16,21 @@ pub enum EvalError {
     #[error("evaluation table not loaded")]
     TableNotLoaded,
 
+    #[error("evaluation table failed integrity check: {0}")]
+    CorruptTable(String),
+
+    #[error("no non-conflicting combos between the ranges and board")]
+    NoLiveCombos,
+
+    #[error("invalid player count: {0} (must be 2 to 9)")]
+    InvalidPlayerCount(usize),
+
+    #[error("invalid ICM input: {0}")]
+    InvalidIcmInput(String),
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
     #[error("core error: {0}")]
     Core(#[from] poker_core::CoreError),
 }
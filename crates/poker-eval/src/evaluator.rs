@@ -0,0 +1,92 @@
+use poker_core::{Card, Hand};
+
+use crate::batch::{board_mask, is_live, RankedHands};
+use crate::hand_rank::HandRank;
+
+/// A hand evaluator backend.
+///
+/// Equity, all-in EV and the solver are written against this trait so that
+/// faster backends, or evaluators for other game variants, can be swapped in.
+/// Callers rely only on `HandRank` ordering (lower is stronger, equal is a
+/// tie) and on `HandRank::category` naming the right hand class. How ranks
+/// are numbered beyond that is documented by each backend.
+pub trait Evaluator: Send + Sync {
+    /// Evaluate a 5-card hand.
+    fn evaluate_5(&self, cards: &[Card; 5]) -> HandRank;
+
+    /// Evaluate the best 5-card hand from 7 cards.
+    fn evaluate_7(&self, cards: &[Card; 7]) -> HandRank;
+
+    /// Evaluate many 7-card hands, in order.
+    fn evaluate_batch(&self, hands: &[[Card; 7]]) -> Vec<HandRank> {
+        hands.iter().map(|hand| self.evaluate_7(hand)).collect()
+    }
+
+    /// Rank every combo against a river board, in input order.
+    /// Combos that share a card with the board (or a board that repeats a
+    /// card) yield `HandRank::INVALID` instead of panicking.
+    fn evaluate_many(&self, board: &[Card; 5], hands: &[Hand]) -> Vec<HandRank> {
+        let Some(mask) = board_mask(board) else {
+            return vec![HandRank::INVALID; hands.len()];
+        };
+        hands
+            .iter()
+            .map(|&hand| {
+                if !is_live(hand, mask) {
+                    return HandRank::INVALID;
+                }
+                let [c0, c1] = hand.cards();
+                self.evaluate_7(&[c0, c1, board[0], board[1], board[2], board[3], board[4]])
+            })
+            .collect()
+    }
+
+    /// Like `evaluate_many`, sorted strongest first and grouped by ties.
+    fn evaluate_many_sorted(&self, board: &[Card; 5], hands: &[Hand]) -> RankedHands {
+        RankedHands::from_ranks(&self.evaluate_many(board, hands))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup_table::LookupTableEvaluator;
+    use crate::reference::ReferenceEvaluator;
+    use crate::seven_card::SevenCardEvaluator;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    fn random_hands(count: usize) -> Vec<[Card; 7]> {
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut deck: Vec<Card> = (0..52u8)
+            .map(|i| Card::from_u8(i).expect("valid"))
+            .collect();
+        (0..count)
+            .map(|_| {
+                deck.shuffle(&mut rng);
+                [
+                    deck[0], deck[1], deck[2], deck[3], deck[4], deck[5], deck[6],
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        let hands = random_hands(5_000);
+        let backends: [&dyn Evaluator; 3] = [
+            LookupTableEvaluator::global(),
+            SevenCardEvaluator::global(),
+            &ReferenceEvaluator::new(),
+        ];
+        let expected = backends[0].evaluate_batch(&hands);
+        for backend in &backends[1..] {
+            assert_eq!(backend.evaluate_batch(&hands), expected);
+            for hand in hands.iter().take(500) {
+                let five = [hand[0], hand[1], hand[2], hand[3], hand[4]];
+                assert_eq!(backend.evaluate_5(&five), backends[0].evaluate_5(&five));
+            }
+        }
+    }
+}
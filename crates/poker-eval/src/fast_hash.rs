@@ -1,8 +1,13 @@
+use std::borrow::Cow;
+
 /// A fast open-addressing hash table optimized for the hand evaluator.
 /// Uses Fibonacci hashing for O(1) amortized lookups with minimal collisions.
+///
+/// The slot arrays are either owned (built at runtime with `from_entries`)
+/// or borrowed from tables embedded at compile time (`from_static`).
 pub struct FastLookup {
-    keys: Vec<u32>,
-    values: Vec<u16>,
+    keys: Cow<'static, [u32]>,
+    values: Cow<'static, [u16]>,
     shift: u32,
     mask: usize,
 }
@@ -35,13 +40,55 @@ impl FastLookup {
         }
 
         FastLookup {
-            keys,
-            values,
+            keys: Cow::Owned(keys),
+            values: Cow::Owned(values),
             shift,
             mask,
         }
     }
 
+    /// Wrap slot arrays previously produced by `from_entries` without copying.
+    /// Both slices must have the same power-of-two length.
+    pub const fn from_static(keys: &'static [u32], values: &'static [u16]) -> Self {
+        assert!(keys.len() == values.len() && keys.len().is_power_of_two());
+        let bits = keys.len().trailing_zeros();
+        FastLookup {
+            keys: Cow::Borrowed(keys),
+            values: Cow::Borrowed(values),
+            shift: 32 - bits,
+            mask: keys.len() - 1,
+        }
+    }
+
+    /// Raw key slots (`u32::MAX` marks an empty slot).
+    pub fn keys(&self) -> &[u32] {
+        &self.keys
+    }
+
+    /// Raw value slots, parallel to `keys`.
+    pub fn values(&self) -> &[u16] {
+        &self.values
+    }
+
+    /// Occupied `(key, value)` slots, in slot order.
+    pub fn entries(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        self.keys
+            .iter()
+            .zip(self.values.iter())
+            .filter(|(&k, _)| k != EMPTY)
+            .map(|(&k, &v)| (k, v))
+    }
+
+    /// Number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.keys.iter().filter(|&&k| k != EMPTY).count()
+    }
+
+    /// Whether the table holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Look up a prime product. Returns 7462 (worst hand) if not found.
     #[inline(always)]
     pub fn get(&self, key: u32) -> u16 {
@@ -8,6 +8,16 @@ use std::fmt;
 pub struct HandRank(pub u16);
 
 impl HandRank {
+    /// Marker for a hand that could not be ranked, e.g. because it shares a
+    /// card with the board. Sorts below every real hand.
+    pub const INVALID: HandRank = HandRank(u16::MAX);
+
+    /// Whether this is a real rank (1..=7462) rather than `INVALID`.
+    #[inline]
+    pub fn is_valid(self) -> bool {
+        (1..=7462).contains(&self.0)
+    }
+
     /// Stronger hand has LOWER numeric rank.
     #[inline]
     pub fn value(self) -> u16 {
@@ -0,0 +1,409 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::error::EvalError;
+
+/// Settings for ICM calculations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IcmConfig {
+    /// Fields of up to this many players with chips are computed exactly.
+    /// Exact Malmuth-Harville costs O(2^n * n).
+    pub exact_max_players: usize,
+    /// Finishing orders sampled for larger fields. The standard error of each
+    /// $EV is at most about `max payout * 0.5 / sqrt(samples)`.
+    pub samples: u64,
+    /// RNG seed for sampling, so results are reproducible.
+    pub seed: u64,
+}
+
+impl Default for IcmConfig {
+    fn default() -> Self {
+        IcmConfig {
+            exact_max_players: 16,
+            samples: 100_000,
+            seed: 0,
+        }
+    }
+}
+
+/// A possible result of a hand: its probability and the stacks afterwards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub probability: f64,
+    pub stacks: Vec<f64>,
+}
+
+/// Independent Chip Model calculator for a fixed payout structure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Icm {
+    /// Prize for 1st, 2nd, ... place. Places beyond the list pay nothing.
+    payouts: Vec<f64>,
+    config: IcmConfig,
+}
+
+impl Icm {
+    pub fn new(payouts: &[f64]) -> Result<Self, EvalError> {
+        Self::with_config(payouts, IcmConfig::default())
+    }
+
+    pub fn with_config(payouts: &[f64], config: IcmConfig) -> Result<Self, EvalError> {
+        if payouts.iter().any(|p| !p.is_finite() || *p < 0.0) {
+            return Err(EvalError::InvalidIcmInput(
+                "payouts must be finite and non-negative".to_string(),
+            ));
+        }
+        Ok(Icm {
+            payouts: payouts.to_vec(),
+            config,
+        })
+    }
+
+    pub fn payouts(&self) -> &[f64] {
+        &self.payouts
+    }
+
+    /// Each player's $EV under Malmuth-Harville: a player finishes first with
+    /// probability proportional to their stack, then the next place is drawn
+    /// the same way among the rest.
+    ///
+    /// Players with zero chips share the lowest remaining places. Fields with
+    /// more than `exact_max_players` live stacks are estimated by sampling
+    /// finishing orders, which follow the same distribution.
+    pub fn equity(&self, stacks: &[f64]) -> Result<Vec<f64>, EvalError> {
+        validate_stacks(stacks)?;
+        let alive: Vec<usize> = (0..stacks.len()).filter(|&i| stacks[i] > 0.0).collect();
+        let live_stacks: Vec<f64> = alive.iter().map(|&i| stacks[i]).collect();
+        let live_equity = if alive.len() <= self.config.exact_max_players {
+            self.exact(&live_stacks)
+        } else {
+            self.sampled(&live_stacks)
+        };
+
+        let mut equity = vec![0.0; stacks.len()];
+        for (&i, e) in alive.iter().zip(live_equity) {
+            equity[i] = e;
+        }
+        // Busted players split the places below every live stack
+        let busted = stacks.len() - alive.len();
+        if busted > 0 {
+            let share: f64 = (alive.len()..stacks.len())
+                .map(|p| self.payout(p))
+                .sum::<f64>()
+                / busted as f64;
+            for (e, &s) in equity.iter_mut().zip(stacks) {
+                if s == 0.0 {
+                    *e = share;
+                }
+            }
+        }
+        Ok(equity)
+    }
+
+    /// Expected $EV of each player over a set of possible outcomes.
+    pub fn expected_equity(&self, outcomes: &[Outcome]) -> Result<Vec<f64>, EvalError> {
+        let Some(first) = outcomes.first() else {
+            return Err(EvalError::InvalidIcmInput("no outcomes".to_string()));
+        };
+        let mut total = vec![0.0; first.stacks.len()];
+        for outcome in outcomes {
+            if outcome.stacks.len() != total.len() {
+                return Err(EvalError::InvalidIcmInput(
+                    "outcomes have different player counts".to_string(),
+                ));
+            }
+            let equity = self.equity(&outcome.stacks)?;
+            for (t, e) in total.iter_mut().zip(equity) {
+                *t += outcome.probability * e;
+            }
+        }
+        Ok(total)
+    }
+
+    /// $EV of `player` after their stack changes by `chips`, with the
+    /// opposite change spread over the other players in proportion to their
+    /// stacks. Converts a chip EV result into $EV.
+    pub fn chip_ev_to_dollar_ev(
+        &self,
+        stacks: &[f64],
+        player: usize,
+        chips: f64,
+    ) -> Result<f64, EvalError> {
+        validate_stacks(stacks)?;
+        validate_player(stacks, player)?;
+        let others: f64 = stacks.iter().sum::<f64>() - stacks[player];
+        if chips < -stacks[player] || chips > others {
+            return Err(EvalError::InvalidIcmInput(format!(
+                "chip change {chips} is outside the chips in play"
+            )));
+        }
+        let after: Vec<f64> = stacks
+            .iter()
+            .enumerate()
+            .map(|(i, &s)| {
+                if i == player {
+                    s + chips
+                } else {
+                    s - chips * s / others
+                }
+            })
+            .collect();
+        Ok(self.equity(&after)?[player])
+    }
+
+    /// Bubble factor of `hero` against `villain`: $EV lost when losing an
+    /// all-in for the effective stack, divided by $EV gained when winning it.
+    /// 1.0 means chips and dollars are worth the same.
+    pub fn bubble_factor(
+        &self,
+        stacks: &[f64],
+        hero: usize,
+        villain: usize,
+    ) -> Result<f64, EvalError> {
+        validate_stacks(stacks)?;
+        validate_player(stacks, hero)?;
+        validate_player(stacks, villain)?;
+        if hero == villain {
+            return Err(EvalError::InvalidIcmInput(
+                "hero and villain must differ".to_string(),
+            ));
+        }
+        let at_risk = stacks[hero].min(stacks[villain]);
+        let shift = |delta: f64| {
+            let mut after = stacks.to_vec();
+            after[hero] += delta;
+            after[villain] -= delta;
+            after
+        };
+        let now = self.equity(stacks)?[hero];
+        let win = self.equity(&shift(at_risk))?[hero];
+        let lose = self.equity(&shift(-at_risk))?[hero];
+        let gained = win - now;
+        if gained <= 0.0 {
+            return Ok(f64::INFINITY);
+        }
+        Ok((now - lose) / gained)
+    }
+
+    /// Risk premium of `hero` against `villain`: the extra equity, over the
+    /// 50% chip EV needs, that hero needs to call an all-in with no dead money.
+    pub fn risk_premium(
+        &self,
+        stacks: &[f64],
+        hero: usize,
+        villain: usize,
+    ) -> Result<f64, EvalError> {
+        let bf = self.bubble_factor(stacks, hero, villain)?;
+        let required = if bf.is_infinite() {
+            1.0
+        } else {
+            bf / (bf + 1.0)
+        };
+        Ok(required - 0.5)
+    }
+
+    fn payout(&self, place: usize) -> f64 {
+        self.payouts.get(place).copied().unwrap_or(0.0)
+    }
+
+    /// Exact Malmuth-Harville over subsets of finished players.
+    fn exact(&self, stacks: &[f64]) -> Vec<f64> {
+        let n = stacks.len();
+        let places = self.payouts.len().min(n);
+        let total: f64 = stacks.iter().sum();
+        let mut equity = vec![0.0; n];
+        if places == 0 {
+            return equity;
+        }
+
+        // prob[mask]: probability that exactly the players in `mask` took the
+        // top |mask| places, in any order
+        let mut prob = vec![0.0f64; 1 << n];
+        let mut taken = vec![0.0f64; 1 << n];
+        prob[0] = 1.0;
+        for mask in 0..(1usize << n) {
+            let p = prob[mask];
+            let place = mask.count_ones() as usize;
+            if p == 0.0 || place >= places {
+                continue;
+            }
+            let remaining = total - taken[mask];
+            for i in (0..n).filter(|i| mask & (1 << i) == 0) {
+                let q = p * stacks[i] / remaining;
+                equity[i] += q * self.payouts[place];
+                let next = mask | (1 << i);
+                prob[next] += q;
+                taken[next] = taken[mask] + stacks[i];
+            }
+        }
+        equity
+    }
+
+    /// Estimate by sampling finishing orders. Drawing each place in turn with
+    /// probability proportional to stack is the same as sorting players by
+    /// `Exp(1) / stack`, so each sample costs O(n log n).
+    fn sampled(&self, stacks: &[f64]) -> Vec<f64> {
+        let n = stacks.len();
+        let places = self.payouts.len().min(n);
+        let mut rng = StdRng::seed_from_u64(self.config.seed);
+        let mut equity = vec![0.0; n];
+        let mut keys: Vec<(f64, usize)> = Vec::with_capacity(n);
+        let samples = self.config.samples.max(1);
+        for _ in 0..samples {
+            keys.clear();
+            for (i, &s) in stacks.iter().enumerate() {
+                let e = -(1.0 - rng.gen::<f64>()).ln();
+                keys.push((e / s, i));
+            }
+            if places < n {
+                keys.select_nth_unstable_by(places, |a, b| a.0.total_cmp(&b.0));
+            }
+            let top = &mut keys[..places];
+            top.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+            for (place, &(_, i)) in top.iter().enumerate() {
+                equity[i] += self.payouts[place];
+            }
+        }
+        for e in &mut equity {
+            *e /= samples as f64;
+        }
+        equity
+    }
+}
+
+fn validate_stacks(stacks: &[f64]) -> Result<(), EvalError> {
+    if stacks.is_empty() {
+        return Err(EvalError::InvalidIcmInput("no players".to_string()));
+    }
+    if stacks.iter().any(|s| !s.is_finite() || *s < 0.0) {
+        return Err(EvalError::InvalidIcmInput(
+            "stacks must be finite and non-negative".to_string(),
+        ));
+    }
+    if stacks.iter().all(|&s| s == 0.0) {
+        return Err(EvalError::InvalidIcmInput("no chips in play".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_player(stacks: &[f64], player: usize) -> Result<(), EvalError> {
+    if player >= stacks.len() {
+        return Err(EvalError::InvalidIcmInput(format!(
+            "player {player} out of range"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &[f64], b: &[f64], tol: f64) {
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).abs() < tol, "{a:?} vs {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_three_player_exact() {
+        let icm = Icm::new(&[500.0, 300.0, 200.0]).expect("valid");
+        let eq = icm.equity(&[5000.0, 3000.0, 2000.0]).expect("valid");
+        // Alice: 1st 0.5, 2nd 0.3*5/7 + 0.2*5/8, 3rd the rest
+        let second = 0.3 * 5.0 / 7.0 + 0.2 * 5.0 / 8.0;
+        let alice = 0.5 * 500.0 + second * 300.0 + (0.5 - second) * 200.0;
+        assert!((eq[0] - alice).abs() < 1e-9);
+        assert!((eq.iter().sum::<f64>() - 1000.0).abs() < 1e-9);
+        // The short stack is worth more than its chip share
+        assert!(eq[2] > 200.0 && eq[0] < 500.0);
+    }
+
+    #[test]
+    fn test_heads_up_and_equal_stacks() {
+        let icm = Icm::new(&[70.0, 30.0]).expect("valid");
+        let eq = icm.equity(&[3000.0, 1000.0]).expect("valid");
+        assert_close(&eq, &[0.75 * 40.0 + 30.0, 0.25 * 40.0 + 30.0], 1e-9);
+
+        let icm = Icm::new(&[50.0, 30.0, 20.0]).expect("valid");
+        let eq = icm.equity(&[1000.0; 10]).expect("valid");
+        assert_close(&eq, &[10.0; 10], 1e-9);
+    }
+
+    #[test]
+    fn test_busted_players_take_last_places() {
+        let icm = Icm::new(&[50.0, 30.0, 20.0]).expect("valid");
+        let eq = icm.equity(&[100.0, 0.0, 100.0]).expect("valid");
+        assert_close(&eq, &[40.0, 20.0, 40.0], 1e-9);
+
+        assert!(icm.equity(&[]).is_err());
+        assert!(icm.equity(&[100.0, -1.0]).is_err());
+        assert!(icm.equity(&[0.0, 0.0]).is_err());
+        assert!(Icm::new(&[-5.0]).is_err());
+    }
+
+    #[test]
+    fn test_sampled_matches_exact() {
+        let payouts = [40.0, 25.0, 15.0, 10.0, 10.0];
+        let stacks = [900.0, 150.0, 700.0, 300.0, 2200.0, 500.0, 1100.0, 80.0];
+        let exact = Icm::new(&payouts)
+            .expect("valid")
+            .equity(&stacks)
+            .expect("valid");
+        let config = IcmConfig {
+            exact_max_players: 4,
+            samples: 200_000,
+            seed: 7,
+        };
+        let sampled = Icm::with_config(&payouts, config)
+            .expect("valid")
+            .equity(&stacks)
+            .expect("valid");
+        assert_close(&exact, &sampled, 0.3);
+    }
+
+    #[test]
+    fn test_bubble_factor_and_risk_premium() {
+        // Winner-take-all is linear in chips
+        let wta = Icm::new(&[100.0]).expect("valid");
+        let stacks = [1000.0, 2000.0, 3000.0];
+        let bf = wta.bubble_factor(&stacks, 0, 1).expect("valid");
+        assert!((bf - 1.0).abs() < 1e-9);
+        assert!(wta.risk_premium(&stacks, 0, 1).expect("valid").abs() < 1e-9);
+
+        // Four left, three paid: medium stacks feel the most pressure
+        let bubble = Icm::new(&[50.0, 30.0, 20.0]).expect("valid");
+        let stacks = [4000.0, 3000.0, 2000.0, 1000.0];
+        let bf = bubble.bubble_factor(&stacks, 1, 0).expect("valid");
+        assert!(bf > 1.2);
+        let rp = bubble.risk_premium(&stacks, 1, 0).expect("valid");
+        assert!((rp - (bf / (bf + 1.0) - 0.5)).abs() < 1e-12);
+        assert!(bubble.bubble_factor(&stacks, 1, 1).is_err());
+        assert!(bubble.bubble_factor(&stacks, 1, 9).is_err());
+    }
+
+    #[test]
+    fn test_chip_ev_conversion_and_outcomes() {
+        let wta = Icm::new(&[100.0]).expect("valid");
+        let stacks = [1000.0, 1000.0, 2000.0];
+        let dollars = wta.chip_ev_to_dollar_ev(&stacks, 0, 500.0).expect("valid");
+        assert!((dollars - 100.0 * 1500.0 / 4000.0).abs() < 1e-9);
+        assert!(wta.chip_ev_to_dollar_ev(&stacks, 0, -2000.0).is_err());
+
+        // A coin flip for the whole stack loses $EV under a flat payout
+        let icm = Icm::new(&[50.0, 30.0, 20.0]).expect("valid");
+        let flip = [
+            Outcome {
+                probability: 0.5,
+                stacks: vec![2000.0, 0.0, 2000.0],
+            },
+            Outcome {
+                probability: 0.5,
+                stacks: vec![0.0, 2000.0, 2000.0],
+            },
+        ];
+        let ev = icm.expected_equity(&flip).expect("valid");
+        let now = icm.equity(&stacks).expect("valid");
+        assert!(ev[0] < now[0]);
+        assert!(ev[2] > now[2]);
+    }
+}
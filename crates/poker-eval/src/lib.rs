@@ -1,17 +1,42 @@
+pub mod all_in;
+pub mod batch;
 pub mod blockers;
 pub mod draws;
 pub mod equity;
 pub mod error;
+pub mod evaluator;
 pub mod fast_hash;
 pub mod hand_rank;
+pub mod icm;
 pub mod isomorphism;
 pub mod lookup_table;
+pub mod multiway;
+pub mod range_equity;
+pub mod reference;
+pub mod river;
+pub mod seven_card;
+pub mod stats;
 pub mod table_gen;
 
+pub use all_in::{all_in_ev, AllInEv, EvHand, EvSession, PotEquity};
+pub use batch::RankedHands;
 pub use blockers::{analyze_blockers, BlockerInfo};
 pub use draws::{detect_draws, DrawType};
-pub use equity::equity_heads_up;
+pub use equity::{
+    equity_heads_up, equity_monte_carlo, range_equity_monte_carlo, MonteCarloConfig,
+    MonteCarloEquity,
+};
 pub use error::EvalError;
+pub use evaluator::Evaluator;
 pub use hand_rank::{HandCategory, HandRank};
+pub use icm::{Icm, IcmConfig, Outcome};
 pub use isomorphism::{canonicalize_board, canonicalize_flop, CanonicalBoard};
 pub use lookup_table::LookupTableEvaluator;
+pub use multiway::{equity_multiway, Holding, MultiwayConfig, MultiwayEquity, PlayerEquity};
+pub use range_equity::{equity_range_vs_range, RangeEquity};
+pub use reference::ReferenceEvaluator;
+pub use river::{RiverCache, RiverRanking, ShowdownWeights, DEFAULT_RIVER_CACHE_CAPACITY};
+pub use seven_card::{PartialHand, SevenCardEvaluator};
+pub use stats::{
+    HandRecord, Import, PlayerStats, SeatRecord, Stats, StatsCache, StatsFilter, CACHE_VERSION,
+};
@@ -1,7 +1,11 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::OnceLock;
 
-use poker_core::Card;
+use poker_core::{Card, Rank, Suit};
 
+use crate::error::EvalError;
+use crate::evaluator::Evaluator;
 use crate::fast_hash::FastLookup;
 use crate::hand_rank::HandRank;
 use crate::table_gen;
@@ -31,28 +35,148 @@ const COMBOS_7_5: [[usize; 5]; 21] = [
     [2, 3, 4, 5, 6],
 ];
 
+/// Tables generated by `build.rs` and embedded in the binary.
+pub(crate) mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/lookup_tables.rs"));
+}
+
+/// Number of non-zero flush table entries (10 straight flushes + 1277 flushes).
+const FLUSH_CLASSES: usize = 1287;
+
+static GLOBAL: OnceLock<LookupTableEvaluator> = OnceLock::new();
+
 /// Lookup-table based hand evaluator.
 /// Uses a fast custom hash table for O(1) prime product lookups.
+///
+/// Ranks follow Cactus Kev's numbering: 1 is a royal flush and 7462 the
+/// worst high card, with every distinct 5-card strength numbered in between.
+///
+/// The tables are generated at build time, so constructing an evaluator only
+/// wraps static data. Prefer [`LookupTableEvaluator::global`], which also
+/// verifies the embedded tables once per process.
 pub struct LookupTableEvaluator {
-    flush_table: Vec<u16>,
+    flush_table: Cow<'static, [u16]>,
     unique5_fast: FastLookup,
-    // Keep HashMap for compatibility with tests
-    unique5_table: HashMap<u32, u16>,
+    /// Built on first call to the deprecated `unique5_map`.
+    unique5_map: OnceLock<HashMap<u32, u16>>,
 }
 
 impl LookupTableEvaluator {
-    /// Create a new evaluator by generating lookup tables.
+    /// Create an evaluator backed by the tables embedded at build time.
     pub fn new() -> Self {
+        LookupTableEvaluator {
+            flush_table: Cow::Borrowed(&embedded::FLUSH_TABLE),
+            unique5_fast: FastLookup::from_static(
+                &embedded::UNIQUE5_KEYS,
+                &embedded::UNIQUE5_VALUES,
+            ),
+            unique5_map: OnceLock::new(),
+        }
+    }
+
+    /// Create an evaluator by generating the lookup tables at runtime.
+    /// Used as a fallback when the embedded tables fail verification.
+    pub fn generate() -> Self {
         let flush_table = table_gen::generate_flush_table();
-        let unique5_table = table_gen::generate_unique5_table();
-        let entries = table_gen::unique5_entries(&unique5_table);
+        let mut entries = table_gen::unique5_entries(&table_gen::generate_unique5_table());
+        entries.sort_unstable();
         let unique5_fast = FastLookup::from_entries(&entries);
 
         LookupTableEvaluator {
-            flush_table,
+            flush_table: Cow::Owned(flush_table),
             unique5_fast,
-            unique5_table,
+            unique5_map: OnceLock::new(),
+        }
+    }
+
+    /// The process-wide shared evaluator.
+    ///
+    /// The embedded tables are checked on first use; if they fail, the tables
+    /// are regenerated instead so evaluation is never silently wrong.
+    pub fn global() -> &'static LookupTableEvaluator {
+        GLOBAL.get_or_init(|| {
+            let eval = Self::new();
+            match eval.verify() {
+                Ok(()) => eval,
+                Err(e) => {
+                    tracing::error!("{e}; regenerating evaluator tables");
+                    Self::generate()
+                }
+            }
+        })
+    }
+
+    /// Check the tables' shape, spot-check a royal flush (1) and the worst
+    /// high card (7462), then compare against the build-time checksum.
+    ///
+    /// The checksum is compiled into the same binary as the tables, so it
+    /// only catches tables corrupted or swapped after the build. A generator
+    /// bug would produce a matching checksum; the spot checks are what catch
+    /// a table that is consistent but wrong.
+    pub fn verify(&self) -> Result<(), EvalError> {
+        if self.flush_table.len() != 8192 {
+            return Err(EvalError::CorruptTable(format!(
+                "flush table has {} slots, expected 8192",
+                self.flush_table.len()
+            )));
+        }
+        let flushes = self.flush_table.iter().filter(|&&v| v > 0).count();
+        if flushes != FLUSH_CLASSES {
+            return Err(EvalError::CorruptTable(format!(
+                "flush table has {flushes} classes, expected {FLUSH_CLASSES}"
+            )));
+        }
+        let entries = self.unique5_fast.len();
+        if entries != embedded::UNIQUE5_ENTRIES {
+            return Err(EvalError::CorruptTable(format!(
+                "prime product table has {entries} entries, expected {}",
+                embedded::UNIQUE5_ENTRIES
+            )));
+        }
+        use Rank::*;
+        use Suit::*;
+        let rank_of = |cards: [(Rank, Suit); 5]| {
+            self.evaluate_5(&cards.map(|(r, s)| Card::new(r, s)))
+                .value()
+        };
+        let royal = rank_of([
+            (Ace, Spades),
+            (King, Spades),
+            (Queen, Spades),
+            (Jack, Spades),
+            (Ten, Spades),
+        ]);
+        let worst = rank_of([
+            (Seven, Spades),
+            (Five, Hearts),
+            (Four, Diamonds),
+            (Three, Clubs),
+            (Two, Spades),
+        ]);
+        if (royal, worst) != (1, 7462) {
+            return Err(EvalError::CorruptTable(format!(
+                "royal flush ranks {royal} and 7-high ranks {worst}, expected 1 and 7462"
+            )));
         }
+        let checksum = table_gen::table_checksum(
+            &self.flush_table,
+            self.unique5_fast.keys(),
+            self.unique5_fast.values(),
+        );
+        if checksum != embedded::CHECKSUM {
+            return Err(EvalError::CorruptTable(format!(
+                "checksum {checksum:#018x} does not match {:#018x}",
+                embedded::CHECKSUM
+            )));
+        }
+        Ok(())
+    }
+
+    /// The prime product table as a map from product to rank.
+    #[deprecated(note = "use `evaluate_5`; the table is now an open-addressing `FastLookup`")]
+    pub fn unique5_map(&self) -> &HashMap<u32, u16> {
+        self.unique5_map
+            .get_or_init(|| self.unique5_fast.entries().collect())
     }
 
     /// Evaluate a 5-card hand.
@@ -137,11 +261,6 @@ impl LookupTableEvaluator {
             _ => HandRank(7462),
         }
     }
-
-    /// Access the HashMap table (for tests that need exact lookup).
-    pub fn unique5_map(&self) -> &HashMap<u32, u16> {
-        &self.unique5_table
-    }
 }
 
 impl Default for LookupTableEvaluator {
@@ -150,6 +269,18 @@ impl Default for LookupTableEvaluator {
     }
 }
 
+impl Evaluator for LookupTableEvaluator {
+    #[inline]
+    fn evaluate_5(&self, cards: &[Card; 5]) -> HandRank {
+        LookupTableEvaluator::evaluate_5(self, cards)
+    }
+
+    #[inline]
+    fn evaluate_7(&self, cards: &[Card; 7]) -> HandRank {
+        LookupTableEvaluator::evaluate_7(self, cards)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -395,6 +526,56 @@ mod tests {
         assert!(royal > full_house);
     }
 
+    #[test]
+    fn test_embedded_tables_verify() {
+        assert!(LookupTableEvaluator::new().verify().is_ok());
+        assert!(LookupTableEvaluator::generate().verify().is_ok());
+        assert!(std::ptr::eq(
+            LookupTableEvaluator::global(),
+            LookupTableEvaluator::global()
+        ));
+    }
+
+    #[test]
+    fn test_embedded_matches_generated() {
+        let embedded = LookupTableEvaluator::new();
+        let generated = LookupTableEvaluator::generate();
+        assert_eq!(embedded.flush_table, generated.flush_table);
+        assert_eq!(embedded.unique5_fast.keys(), generated.unique5_fast.keys());
+        assert_eq!(
+            embedded.unique5_fast.values(),
+            generated.unique5_fast.values()
+        );
+    }
+
+    #[test]
+    fn test_corrupt_table_detected() {
+        let mut eval = LookupTableEvaluator::new();
+        eval.flush_table.to_mut()[0x1f00] = 2;
+        assert!(matches!(eval.verify(), Err(EvalError::CorruptTable(_))));
+    }
+
+    #[test]
+    fn test_wrong_ranks_detected() {
+        // Swapping two flush entries keeps the table's shape
+        let mut eval = LookupTableEvaluator::new();
+        eval.flush_table.to_mut().swap(0x1f00, 0x0f80);
+        match eval.verify() {
+            Err(EvalError::CorruptTable(message)) => assert!(message.contains("royal flush")),
+            other => panic!("expected a corrupt table, got {other:?}"),
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_unique5_map_shim() {
+        let eval = LookupTableEvaluator::global();
+        let map = eval.unique5_map();
+        assert_eq!(map.len(), embedded::UNIQUE5_ENTRIES);
+        // 7-5-4-3-2 offsuit: primes 13 * 7 * 5 * 3 * 2
+        assert_eq!(map.get(&(13 * 7 * 5 * 3 * 2)), Some(&7462));
+    }
+
     #[test]
     fn test_all_7462_hand_classes() {
         let eval = LookupTableEvaluator::new();
@@ -0,0 +1,512 @@
+use poker_core::{Card, ComboRange, Hand, Range, Rank, Suit};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::equity::{draw_runout, run_batches, ComboSampler, MonteCarloConfig, MC_MAX_REJECTIONS};
+use crate::error::EvalError;
+use crate::evaluator::Evaluator;
+use crate::range_equity::{enumerate_runouts, validate_board};
+
+/// Most seats at a full-ring table.
+pub const MAX_PLAYERS: usize = 9;
+
+/// What one seat holds: known hole cards or a range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Holding {
+    Hand([Card; 2]),
+    Range(Range),
+    Combos(ComboRange),
+}
+
+/// Settings for `equity_multiway`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MultiwayConfig {
+    /// Enumerate exactly when the number of showdowns is at most this;
+    /// otherwise sample with `sampling`.
+    pub max_exact_showdowns: u64,
+    pub sampling: MonteCarloConfig,
+}
+
+impl Default for MultiwayConfig {
+    fn default() -> Self {
+        MultiwayConfig {
+            max_exact_showdowns: 2_000_000,
+            sampling: MonteCarloConfig::default(),
+        }
+    }
+}
+
+/// Equity of one seat in a multiway pot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlayerEquity {
+    /// Fraction of showdowns won outright.
+    pub win: f64,
+    /// Pot share from split pots: each N-way tie counts 1/N.
+    pub tie: f64,
+    /// Total equity: win + tie.
+    pub equity: f64,
+    /// Standard error of `equity`; 0 when enumerated exactly.
+    pub std_error: f64,
+}
+
+/// Result of a multiway equity calculation, one entry per seat in input order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultiwayEquity {
+    pub players: Vec<PlayerEquity>,
+    /// Whether the result was enumerated exactly rather than sampled.
+    pub exact: bool,
+    /// Showdowns enumerated or sampled.
+    pub showdowns: u64,
+}
+
+/// Calculate equity for 2 to 9 seats on a partial board with dead cards.
+///
+/// Enumerates every combination of seat combos and runouts when that is at
+/// most `config.max_exact_showdowns` showdowns, and samples otherwise.
+pub fn equity_multiway<E: Evaluator + ?Sized>(
+    eval: &E,
+    seats: &[Holding],
+    board: &[Card],
+    dead: &[Card],
+    config: &MultiwayConfig,
+) -> Result<MultiwayEquity, EvalError> {
+    if seats.len() < 2 || seats.len() > MAX_PLAYERS {
+        return Err(EvalError::InvalidPlayerCount(seats.len()));
+    }
+    let board_mask = validate_board(board)?;
+    let mut dead_mask = board_mask;
+    for c in dead {
+        if dead_mask & c.mask() != 0 {
+            return Err(EvalError::DuplicateCard);
+        }
+        dead_mask |= c.mask();
+    }
+
+    let combos: Vec<ComboRange> = seats.iter().map(to_combos).collect::<Result<_, _>>()?;
+    let live: Vec<Vec<(Hand, f64)>> = combos
+        .iter()
+        .map(|range| {
+            range
+                .iter()
+                .filter(|(hand, _)| hand.mask() & dead_mask == 0)
+                .map(|(hand, w)| (hand, w as f64))
+                .collect()
+        })
+        .collect();
+    if live.iter().any(|l| l.is_empty()) {
+        return Err(EvalError::NoLiveCombos);
+    }
+
+    let to_deal = 5 - board.len();
+    let remaining = 52 - dead_mask.count_ones() as u64 - 2 * seats.len() as u64;
+    let showdowns = live
+        .iter()
+        .fold(binomial(remaining, to_deal as u64), |acc, l| {
+            acc.saturating_mul(l.len() as u64)
+        });
+
+    if showdowns <= config.max_exact_showdowns {
+        enumerate_exact(eval, &live, board, dead_mask)
+    } else {
+        sample(eval, &combos, board, dead_mask, &config.sampling)
+    }
+}
+
+fn to_combos(holding: &Holding) -> Result<ComboRange, EvalError> {
+    Ok(match holding {
+        Holding::Hand(cards) => {
+            if cards[0] == cards[1] {
+                return Err(EvalError::DuplicateCard);
+            }
+            let mut range = ComboRange::empty();
+            range.set_weight(Hand::new(cards[0], cards[1]), 1.0);
+            range
+        }
+        Holding::Range(range) => ComboRange::from_range(range),
+        Holding::Combos(range) => range.clone(),
+    })
+}
+
+fn binomial(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1u64, |acc, i| acc.saturating_mul(n - i) / (i + 1))
+}
+
+/// Weighted win and split-pot share per seat.
+struct Tally {
+    win: Vec<f64>,
+    tie: Vec<f64>,
+    equity_sq: Vec<f64>,
+    total: f64,
+    ranks: Vec<u16>,
+}
+
+impl Tally {
+    fn new(players: usize) -> Self {
+        Tally {
+            win: vec![0.0; players],
+            tie: vec![0.0; players],
+            equity_sq: vec![0.0; players],
+            total: 0.0,
+            ranks: vec![0; players],
+        }
+    }
+
+    /// Score one showdown of `hands` on a full `runout` with the given weight.
+    fn showdown<E: Evaluator + ?Sized>(
+        &mut self,
+        eval: &E,
+        hands: &[Hand],
+        runout: &[Card; 5],
+        weight: f64,
+    ) {
+        let mut cards = [
+            runout[0], runout[0], runout[0], runout[1], runout[2], runout[3], runout[4],
+        ];
+        for (rank, hand) in self.ranks.iter_mut().zip(hands) {
+            cards[0] = hand.card1();
+            cards[1] = hand.card2();
+            *rank = eval.evaluate_7(&cards).value();
+        }
+        let best = self.ranks.iter().copied().min().unwrap_or(u16::MAX);
+        let winners = self.ranks.iter().filter(|&&r| r == best).count();
+        let share = 1.0 / winners as f64;
+        for (i, &r) in self.ranks.iter().enumerate() {
+            if r != best {
+                continue;
+            }
+            if winners == 1 {
+                self.win[i] += weight;
+            } else {
+                self.tie[i] += weight * share;
+            }
+            self.equity_sq[i] += weight * share * share;
+        }
+        self.total += weight;
+    }
+
+    /// Standard error of one seat's equity, treating `total` as the sample count.
+    fn std_error(&self, seat: usize) -> f64 {
+        let n = self.total;
+        if n < 2.0 {
+            return f64::INFINITY;
+        }
+        let mean = (self.win[seat] + self.tie[seat]) / n;
+        let variance = (self.equity_sq[seat] / n - mean * mean).max(0.0) * n / (n - 1.0);
+        (variance / n).sqrt()
+    }
+
+    fn max_std_error(&self) -> f64 {
+        (0..self.win.len())
+            .map(|i| self.std_error(i))
+            .fold(0.0, f64::max)
+    }
+
+    fn finish(&self, exact: bool, showdowns: u64) -> Result<MultiwayEquity, EvalError> {
+        if self.total <= 0.0 {
+            return Err(EvalError::NoLiveCombos);
+        }
+        let players = (0..self.win.len())
+            .map(|i| {
+                let win = self.win[i] / self.total;
+                let tie = self.tie[i] / self.total;
+                PlayerEquity {
+                    win,
+                    tie,
+                    equity: win + tie,
+                    std_error: if exact { 0.0 } else { self.std_error(i) },
+                }
+            })
+            .collect();
+        Ok(MultiwayEquity {
+            players,
+            exact,
+            showdowns,
+        })
+    }
+}
+
+/// Enumerate every non-conflicting combo assignment and every runout.
+/// Each assignment leaves the same number of runouts, so weighting each
+/// showdown by the product of combo weights is exact.
+fn enumerate_exact<E: Evaluator + ?Sized>(
+    eval: &E,
+    live: &[Vec<(Hand, f64)>],
+    board: &[Card],
+    dead_mask: u64,
+) -> Result<MultiwayEquity, EvalError> {
+    let mut tally = Tally::new(live.len());
+    let mut hands = Vec::with_capacity(live.len());
+    let mut showdowns = 0u64;
+    assign(
+        eval,
+        live,
+        board,
+        dead_mask,
+        1.0,
+        &mut hands,
+        &mut tally,
+        &mut showdowns,
+    );
+    tally.finish(true, showdowns)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn assign<E: Evaluator + ?Sized>(
+    eval: &E,
+    live: &[Vec<(Hand, f64)>],
+    board: &[Card],
+    used: u64,
+    weight: f64,
+    hands: &mut Vec<Hand>,
+    tally: &mut Tally,
+    showdowns: &mut u64,
+) {
+    let seat = hands.len();
+    if seat == live.len() {
+        let available: Vec<Card> = (0..52u8)
+            .filter_map(|i| Card::from_u8(i).ok())
+            .filter(|c| c.mask() & used == 0)
+            .collect();
+        let mut runout = [Card::new(Rank::Two, Suit::Clubs); 5];
+        runout[..board.len()].copy_from_slice(board);
+        for drawn in enumerate_runouts(&available, 5 - board.len()) {
+            runout[board.len()..].copy_from_slice(&drawn[..5 - board.len()]);
+            tally.showdown(eval, hands, &runout, weight);
+            *showdowns += 1;
+        }
+        return;
+    }
+    for &(hand, w) in &live[seat] {
+        if hand.mask() & used != 0 {
+            continue;
+        }
+        hands.push(hand);
+        assign(
+            eval,
+            live,
+            board,
+            used | hand.mask(),
+            weight * w,
+            hands,
+            tally,
+            showdowns,
+        );
+        hands.pop();
+    }
+}
+
+/// Sample a combo per seat (rejecting card conflicts) and a runout per showdown.
+fn sample<E: Evaluator + ?Sized>(
+    eval: &E,
+    combos: &[ComboRange],
+    board: &[Card],
+    dead_mask: u64,
+    config: &MonteCarloConfig,
+) -> Result<MultiwayEquity, EvalError> {
+    let samplers: Vec<ComboSampler> = combos
+        .iter()
+        .map(|r| ComboSampler::new(r, dead_mask).ok_or(EvalError::NoLiveCombos))
+        .collect::<Result<_, _>>()?;
+
+    let mut tally = Tally::new(combos.len());
+    let mut hands = Vec::with_capacity(combos.len());
+    let mut runout = [Card::new(Rank::Two, Suit::Clubs); 5];
+    let showdowns = run_batches(
+        config,
+        &mut tally,
+        |tally, rng| {
+            let used = sample_hands(&samplers, dead_mask, rng, &mut hands)?;
+            draw_runout(rng, board, used, &mut runout);
+            tally.showdown(eval, &hands, &runout, 1.0);
+            Ok(())
+        },
+        Tally::max_std_error,
+    )?;
+    tally.finish(false, showdowns)
+}
+
+/// Draw one combo per seat with no shared cards; returns the combined dead mask.
+fn sample_hands(
+    samplers: &[ComboSampler],
+    dead_mask: u64,
+    rng: &mut StdRng,
+    hands: &mut Vec<Hand>,
+) -> Result<u64, EvalError> {
+    'attempt: for _ in 0..MC_MAX_REJECTIONS {
+        hands.clear();
+        let mut used = dead_mask;
+        for sampler in samplers {
+            let hand = sampler.sample(rng);
+            if hand.mask() & used != 0 {
+                continue 'attempt;
+            }
+            used |= hand.mask();
+            hands.push(hand);
+        }
+        return Ok(used);
+    }
+    Err(EvalError::NoLiveCombos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equity::equity_heads_up;
+    use crate::lookup_table::LookupTableEvaluator;
+
+    fn cards(s: &str) -> Vec<Card> {
+        s.split_whitespace()
+            .map(|c| Card::from_str_notation(c).expect("valid card"))
+            .collect()
+    }
+
+    fn hand(s: &str) -> Holding {
+        let c = cards(s);
+        Holding::Hand([c[0], c[1]])
+    }
+
+    fn assert_sums_to_one(result: &MultiwayEquity) {
+        let total: f64 = result.players.iter().map(|p| p.equity).sum();
+        assert!((total - 1.0).abs() < 1e-9, "equities sum to {total}");
+    }
+
+    #[test]
+    fn test_heads_up_matches_existing() {
+        let eval = LookupTableEvaluator::global();
+        let board = cards("2h 7d 9c");
+        let result = equity_multiway(
+            eval,
+            &[hand("As Ks"), hand("Qh Qd")],
+            &board,
+            &[],
+            &MultiwayConfig::default(),
+        )
+        .expect("valid");
+        assert!(result.exact);
+        let (eq1, _) = equity_heads_up(
+            eval,
+            [cards("As")[0], cards("Ks")[0]],
+            [cards("Qh")[0], cards("Qd")[0]],
+            &board,
+        );
+        assert!((result.players[0].equity - eq1).abs() < 1e-9);
+        assert_sums_to_one(&result);
+    }
+
+    #[test]
+    fn test_three_way_split_pot() {
+        let eval = LookupTableEvaluator::global();
+        // Broadway on the board: everyone plays the board and splits three ways
+        let board = cards("As Kd Qh Jc Ts");
+        let seats = [hand("2c 3d"), hand("4c 5d"), hand("6c 7d")];
+        let result =
+            equity_multiway(eval, &seats, &board, &[], &MultiwayConfig::default()).expect("valid");
+        for p in &result.players {
+            assert_eq!(p.win, 0.0);
+            assert!((p.tie - 1.0 / 3.0).abs() < 1e-12);
+        }
+        assert_sums_to_one(&result);
+
+        // Two players share the nuts, the third loses
+        let board = cards("2s 3s 4s 9d Td");
+        let seats = [hand("As 5h"), hand("Ad 5c"), hand("Kc Kh")];
+        let result =
+            equity_multiway(eval, &seats, &board, &[], &MultiwayConfig::default()).expect("valid");
+        assert!((result.players[0].tie - 0.5).abs() < 1e-12);
+        assert!((result.players[1].tie - 0.5).abs() < 1e-12);
+        assert_eq!(result.players[2].equity, 0.0);
+    }
+
+    #[test]
+    fn test_dead_cards_and_ranges() {
+        let eval = LookupTableEvaluator::global();
+        let board = cards("Ah 7d 2c 9s");
+        let seats = [
+            hand("Kd Kc"),
+            Holding::Range("AA".parse().expect("valid")),
+            hand("8h 8c"),
+        ];
+        // With As dead only AdAc remains for the AA seat
+        let result = equity_multiway(
+            eval,
+            &seats,
+            &board,
+            &cards("As"),
+            &MultiwayConfig::default(),
+        )
+        .expect("valid");
+        assert!(result.exact);
+        assert!(result.players[1].equity > 0.9);
+        assert_sums_to_one(&result);
+
+        let conflict = equity_multiway(
+            eval,
+            &seats,
+            &board,
+            &cards("Ah"),
+            &MultiwayConfig::default(),
+        );
+        assert!(conflict.is_err());
+    }
+
+    #[test]
+    fn test_sampling_close_to_exact() {
+        let eval = LookupTableEvaluator::global();
+        let board = cards("Jh 8d 3c");
+        let seats = [
+            hand("Ah Kh"),
+            Holding::Combos("99,TT".parse().expect("valid")),
+            hand("Qs Js"),
+            hand("7c 6c"),
+        ];
+        let exact =
+            equity_multiway(eval, &seats, &board, &[], &MultiwayConfig::default()).expect("valid");
+        assert!(exact.exact);
+
+        let sampled_cfg = MultiwayConfig {
+            max_exact_showdowns: 0,
+            sampling: MonteCarloConfig {
+                max_iterations: 60_000,
+                seed: 5,
+                ..MonteCarloConfig::default()
+            },
+        };
+        let sampled = equity_multiway(eval, &seats, &board, &[], &sampled_cfg).expect("valid");
+        assert!(!sampled.exact);
+        assert_eq!(sampled.showdowns, 60_000);
+        for (e, s) in exact.players.iter().zip(&sampled.players) {
+            assert!(
+                (e.equity - s.equity).abs() < 5.0 * s.std_error + 1e-3,
+                "{e:?} vs {s:?}"
+            );
+        }
+        assert_sums_to_one(&sampled);
+    }
+
+    #[test]
+    fn test_nine_players_preflop_samples() {
+        let eval = LookupTableEvaluator::global();
+        let mut seats: Vec<Holding> = (0..8).map(|_| Holding::Range(Range::full())).collect();
+        seats.push(hand("As Ah"));
+        let cfg = MultiwayConfig {
+            sampling: MonteCarloConfig {
+                max_iterations: 20_000,
+                seed: 9,
+                ..MonteCarloConfig::default()
+            },
+            ..MultiwayConfig::default()
+        };
+        let result = equity_multiway(eval, &seats, &[], &[], &cfg).expect("valid");
+        assert!(!result.exact);
+        // AA vs 8 random hands wins roughly a third of the time
+        let aa = result.players[8].equity;
+        assert!(aa > 0.28 && aa < 0.40, "{aa}");
+        assert_sums_to_one(&result);
+
+        let too_many: Vec<Holding> = (0..10).map(|_| Holding::Range(Range::full())).collect();
+        assert!(equity_multiway(eval, &too_many, &[], &[], &cfg).is_err());
+    }
+}
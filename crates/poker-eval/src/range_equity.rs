@@ -0,0 +1,519 @@
+use poker_core::hand::NUM_COMBOS;
+use poker_core::{Card, ComboRange, Hand, Rank, Suit};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::equity::draw_runout;
+use crate::error::EvalError;
+use crate::evaluator::Evaluator;
+
+/// Runouts sampled by `equity_range_vs_range` on an empty board.
+pub const PREFLOP_SAMPLES: usize = 20_000;
+
+/// Preflop samples are drawn in blocks with a fixed seed each, so the
+/// estimate does not depend on the number of threads.
+const SAMPLE_BLOCK: usize = 1_000;
+
+/// Result of a range-vs-range equity calculation: exact from the flop on,
+/// estimated preflop. All figures are from range 1's point of view unless
+/// noted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeEquity {
+    /// Equity of range 1: win + tie / 2.
+    pub equity: f64,
+    /// Fraction of matchups range 1 wins outright.
+    pub win: f64,
+    /// Fraction of matchups that split the pot.
+    pub tie: f64,
+    /// Equity of each hand class (169) in range 1 against range 2.
+    /// `None` where the class has no live combos.
+    pub hand_equity1: Vec<Option<f64>>,
+    /// Equity of each hand class (169) in range 2 against range 1.
+    pub hand_equity2: Vec<Option<f64>>,
+}
+
+/// Calculate the equity of `range1` vs `range2` over the runouts of `board`.
+///
+/// Each runout is scored with a sorted sweep over both ranges, subtracting
+/// combos that share a card with the hand being scored, so card removal
+/// between the two ranges and the board is exact. Every runout costs one
+/// evaluation per live combo in each range.
+///
+/// From the flop on, every runout is enumerated (at most C(49,2) = 1176),
+/// lazily and round-robin over all available threads. Preflop, enumerating
+/// all C(52,5) ≈ 2.6M boards would take minutes for wide ranges, so
+/// [`PREFLOP_SAMPLES`] boards are sampled uniformly with fixed seeds instead:
+/// the result is a deterministic estimate, typically within a few tenths of
+/// a percent. Even that takes a noticeable fraction of a second, so callers
+/// on a UI thread should run it elsewhere.
+pub fn equity_range_vs_range<E: Evaluator + ?Sized>(
+    eval: &E,
+    range1: &ComboRange,
+    range2: &ComboRange,
+    board: &[Card],
+) -> Result<RangeEquity, EvalError> {
+    let board_mask = validate_board(board)?;
+
+    let available: Vec<Card> = (0..52u8)
+        .filter_map(|i| Card::from_u8(i).ok())
+        .filter(|c| c.mask() & board_mask == 0)
+        .collect();
+    let missing = 5 - board.len();
+    let hands1 = unblocked(range1, board_mask);
+    let hands2 = unblocked(range2, board_mask);
+    let sampled = board.is_empty();
+    let blocks = PREFLOP_SAMPLES / SAMPLE_BLOCK;
+    let jobs = if sampled {
+        blocks
+    } else {
+        binomial(available.len(), missing)
+    };
+
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(jobs.max(1));
+
+    let totals = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|worker| {
+                let (available, hands1, hands2) = (&available, &hands1, &hands2);
+                scope.spawn(move || {
+                    let mut acc = Accumulator::new();
+                    let mut full_board = [Card::new(Rank::Two, Suit::Clubs); 5];
+                    full_board[..board.len()].copy_from_slice(board);
+                    if sampled {
+                        for block in (worker..blocks).step_by(threads) {
+                            let mut rng = StdRng::seed_from_u64(block as u64);
+                            for _ in 0..SAMPLE_BLOCK {
+                                draw_runout(&mut rng, board, board_mask, &mut full_board);
+                                acc.add_runout(
+                                    eval,
+                                    (hands1, range1),
+                                    (hands2, range2),
+                                    &full_board,
+                                );
+                            }
+                        }
+                        return acc;
+                    }
+                    let runouts = enumerate_runouts(available, missing)
+                        .skip(worker)
+                        .step_by(threads);
+                    for runout in runouts {
+                        full_board[board.len()..].copy_from_slice(&runout[..missing]);
+                        acc.add_runout(eval, (hands1, range1), (hands2, range2), &full_board);
+                    }
+                    acc
+                })
+            })
+            .collect();
+        let mut total = Accumulator::new();
+        for handle in handles {
+            match handle.join() {
+                Ok(acc) => total.merge(&acc),
+                Err(panic) => std::panic::resume_unwind(panic),
+            }
+        }
+        total
+    });
+
+    totals.finish(range1, range2)
+}
+
+/// Dead-card mask of a valid board (0, 3, 4 or 5 distinct cards).
+pub(crate) fn validate_board(board: &[Card]) -> Result<u64, EvalError> {
+    if !matches!(board.len(), 0 | 3 | 4 | 5) {
+        return Err(poker_core::CoreError::InvalidBoardSize(board.len()).into());
+    }
+    let mut mask = 0u64;
+    for c in board {
+        if mask & c.mask() != 0 {
+            return Err(EvalError::DuplicateCard);
+        }
+        mask |= c.mask();
+    }
+    Ok(mask)
+}
+
+/// Every `k`-card combination of `available`, padded to 5 cards, generated
+/// lazily in lexicographic order.
+pub(crate) fn enumerate_runouts(available: &[Card], k: usize) -> Runouts<'_> {
+    Runouts {
+        available,
+        idx: (0..k).collect(),
+        done: k > available.len(),
+    }
+}
+
+/// Number of `k`-card combinations of `n` cards.
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+/// Iterator over the runouts of [`enumerate_runouts`].
+pub(crate) struct Runouts<'a> {
+    available: &'a [Card],
+    idx: Vec<usize>,
+    done: bool,
+}
+
+impl Iterator for Runouts<'_> {
+    type Item = [Card; 5];
+
+    fn next(&mut self) -> Option<[Card; 5]> {
+        if self.done {
+            return None;
+        }
+        let mut runout = [Card::new(Rank::Two, Suit::Clubs); 5];
+        for (slot, &i) in runout.iter_mut().zip(&self.idx) {
+            *slot = self.available[i];
+        }
+        // Advance to the next combination in lexicographic order
+        let (n, k) = (self.available.len(), self.idx.len());
+        let mut i = k;
+        while i > 0 && self.idx[i - 1] == n - k + i - 1 {
+            i -= 1;
+        }
+        if i == 0 {
+            self.done = true;
+        } else {
+            self.idx[i - 1] += 1;
+            for j in i..k {
+                self.idx[j] = self.idx[j - 1] + 1;
+            }
+        }
+        Some(runout)
+    }
+}
+
+/// Combos of a range with non-zero weight that the starting board does not block.
+fn unblocked(range: &ComboRange, board_mask: u64) -> Vec<(Hand, f64)> {
+    range
+        .iter()
+        .filter(|(hand, _)| hand.mask() & board_mask == 0)
+        .map(|(hand, w)| (hand, w as f64))
+        .collect()
+}
+
+/// A combo that is live on the current runout.
+#[derive(Clone, Copy)]
+struct LiveCombo {
+    combo: usize,
+    cards: [usize; 2],
+    weight: f64,
+    rank: u16,
+}
+
+/// Per-combo sums of opponent weight won, tied and faced, over all runouts.
+struct Accumulator {
+    win: [Vec<f64>; 2],
+    tie: [Vec<f64>; 2],
+    total: [Vec<f64>; 2],
+}
+
+impl Accumulator {
+    fn new() -> Self {
+        let zeros = || vec![0.0; NUM_COMBOS];
+        Accumulator {
+            win: [zeros(), zeros()],
+            tie: [zeros(), zeros()],
+            total: [zeros(), zeros()],
+        }
+    }
+
+    fn merge(&mut self, other: &Accumulator) {
+        for side in 0..2 {
+            for i in 0..NUM_COMBOS {
+                self.win[side][i] += other.win[side][i];
+                self.tie[side][i] += other.tie[side][i];
+                self.total[side][i] += other.total[side][i];
+            }
+        }
+    }
+
+    fn add_runout<E: Evaluator + ?Sized>(
+        &mut self,
+        eval: &E,
+        (hands1, range1): (&[(Hand, f64)], &ComboRange),
+        (hands2, range2): (&[(Hand, f64)], &ComboRange),
+        board: &[Card; 5],
+    ) {
+        let live1 = live_combos(eval, hands1, board);
+        let live2 = live_combos(eval, hands2, board);
+        self.sweep(0, &live1, &live2, range2);
+        self.sweep(1, &live2, &live1, range1);
+    }
+
+    /// Score every hero combo against the villain combos it does not block.
+    /// Both slices are sorted weakest first.
+    fn sweep(
+        &mut self,
+        side: usize,
+        hero: &[LiveCombo],
+        villain: &[LiveCombo],
+        villain_range: &ComboRange,
+    ) {
+        let mut all = 0.0;
+        let mut all_card = [0.0f64; 52];
+        for v in villain {
+            all += v.weight;
+            all_card[v.cards[0]] += v.weight;
+            all_card[v.cards[1]] += v.weight;
+        }
+
+        // `below` sums strictly weaker villain combos, `upto` weaker or equal
+        let (mut below, mut upto) = (0.0, 0.0);
+        let (mut below_card, mut upto_card) = ([0.0f64; 52], [0.0f64; 52]);
+        let (mut j, mut k) = (0, 0);
+
+        for h in hero {
+            while j < villain.len() && villain[j].rank > h.rank {
+                below += villain[j].weight;
+                below_card[villain[j].cards[0]] += villain[j].weight;
+                below_card[villain[j].cards[1]] += villain[j].weight;
+                j += 1;
+            }
+            while k < villain.len() && villain[k].rank >= h.rank {
+                upto += villain[k].weight;
+                upto_card[villain[k].cards[0]] += villain[k].weight;
+                upto_card[villain[k].cards[1]] += villain[k].weight;
+                k += 1;
+            }
+            // The identical combo in villain's range is subtracted once per card
+            let same = villain_range.weights()[h.combo] as f64;
+            let [a, b] = h.cards;
+            let win = below - below_card[a] - below_card[b];
+            let win_or_tie = upto - upto_card[a] - upto_card[b] + same;
+            let total = all - all_card[a] - all_card[b] + same;
+
+            self.win[side][h.combo] += win;
+            self.tie[side][h.combo] += win_or_tie - win;
+            self.total[side][h.combo] += total;
+        }
+    }
+
+    fn finish(&self, range1: &ComboRange, range2: &ComboRange) -> Result<RangeEquity, EvalError> {
+        let (mut win, mut tie, mut total) = (0.0, 0.0, 0.0);
+        for (hand, w) in range1.iter() {
+            let i = hand.combo_index();
+            let w = w as f64;
+            win += w * self.win[0][i];
+            tie += w * self.tie[0][i];
+            total += w * self.total[0][i];
+        }
+        if total <= 0.0 {
+            return Err(EvalError::NoLiveCombos);
+        }
+
+        Ok(RangeEquity {
+            equity: (win + tie / 2.0) / total,
+            win: win / total,
+            tie: tie / total,
+            hand_equity1: self.class_equity(0, range1),
+            hand_equity2: self.class_equity(1, range2),
+        })
+    }
+
+    fn class_equity(&self, side: usize, range: &ComboRange) -> Vec<Option<f64>> {
+        let mut share = vec![0.0f64; 169];
+        let mut total = vec![0.0f64; 169];
+        for (hand, w) in range.iter() {
+            let i = hand.combo_index();
+            let class = hand.canonical_index();
+            let w = w as f64;
+            share[class] += w * (self.win[side][i] + self.tie[side][i] / 2.0);
+            total[class] += w * self.total[side][i];
+        }
+        share
+            .iter()
+            .zip(&total)
+            .map(|(&s, &t)| (t > 0.0).then(|| s / t))
+            .collect()
+    }
+}
+
+/// Live combos on a full board, evaluated and sorted weakest first.
+fn live_combos<E: Evaluator + ?Sized>(
+    eval: &E,
+    hands: &[(Hand, f64)],
+    board: &[Card; 5],
+) -> Vec<LiveCombo> {
+    let board_mask = board.iter().fold(0u64, |acc, c| acc | c.mask());
+    let mut cards = [
+        board[0], board[0], board[0], board[1], board[2], board[3], board[4],
+    ];
+
+    let mut live: Vec<LiveCombo> = hands
+        .iter()
+        .filter(|(hand, _)| hand.mask() & board_mask == 0)
+        .map(|&(hand, weight)| {
+            cards[0] = hand.card1();
+            cards[1] = hand.card2();
+            LiveCombo {
+                combo: hand.combo_index(),
+                cards: [hand.card1().as_u8() as usize, hand.card2().as_u8() as usize],
+                weight,
+                rank: eval.evaluate_7(&cards).value(),
+            }
+        })
+        .collect();
+    live.sort_unstable_by_key(|c| std::cmp::Reverse(c.rank));
+    live
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equity::equity_heads_up;
+    use crate::lookup_table::LookupTableEvaluator;
+    use poker_core::Range;
+
+    fn cards(s: &str) -> Vec<Card> {
+        s.split_whitespace()
+            .map(|c| Card::from_str_notation(c).expect("valid card"))
+            .collect()
+    }
+
+    fn combos(s: &str) -> ComboRange {
+        s.parse().expect("valid range")
+    }
+
+    #[test]
+    fn test_single_combos_match_heads_up() {
+        let eval = LookupTableEvaluator::global();
+        let board = cards("2h 7d 9c");
+        let result =
+            equity_range_vs_range(eval, &combos("AsKs"), &combos("QhQd"), &board).expect("valid");
+        let hero = cards("As Ks");
+        let villain = cards("Qh Qd");
+        let (eq1, _) = equity_heads_up(eval, [hero[0], hero[1]], [villain[0], villain[1]], &board);
+        assert!(
+            (result.equity - eq1).abs() < 1e-9,
+            "{} vs {eq1}",
+            result.equity
+        );
+    }
+
+    #[test]
+    fn test_river_card_removal() {
+        let eval = LookupTableEvaluator::global();
+        // Board pairs the ace: AA has 1 live combo left, KK has 6
+        let board = cards("As Ah 7d 4c 2s");
+        let result =
+            equity_range_vs_range(eval, &combos("AA"), &combos("KK"), &board).expect("valid");
+        assert!((result.equity - 1.0).abs() < 1e-9);
+        assert_eq!(result.tie, 0.0);
+
+        // Shared combos are removed: AKs vs AKs can only meet with different suits
+        let split = equity_range_vs_range(
+            eval,
+            &combos("AKs"),
+            &combos("AKs"),
+            &cards("2c 7d 9h Th 3s"),
+        )
+        .expect("valid");
+        assert!((split.tie - 1.0).abs() < 1e-9);
+        assert!((split.equity - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_flop_symmetry_and_hand_classes() {
+        let eval = LookupTableEvaluator::global();
+        let board = cards("Kd 8s 3h");
+        let r1 = ComboRange::from_range(&"TT+,AK".parse::<Range>().expect("valid"));
+        let r2 = combos("22+,AQs+");
+        let forward = equity_range_vs_range(eval, &r1, &r2, &board).expect("valid");
+        let reverse = equity_range_vs_range(eval, &r2, &r1, &board).expect("valid");
+        assert!((forward.equity + reverse.equity - 1.0).abs() < 1e-9);
+        assert!((forward.tie - reverse.tie).abs() < 1e-9);
+
+        // KK is a set on a K-high board; 33 is the bottom set and only loses to higher sets
+        let kk = Hand::new(cards("Ks")[0], cards("Kh")[0]).canonical_index();
+        let kk_equity = forward.hand_equity1[kk].expect("KK in range");
+        assert!(kk_equity > 0.9, "{kk_equity}");
+        assert_eq!(
+            forward.hand_equity1[Hand::new(cards("2s")[0], cards("2h")[0]).canonical_index()],
+            None
+        );
+    }
+
+    #[test]
+    fn test_turn_matches_pairwise_enumeration() {
+        let eval = LookupTableEvaluator::global();
+        let board = cards("Jc Td 4h 2s");
+        let r1 = combos("QQ,KJs");
+        let r2 = combos("AhQh,98s:0.5");
+        let result = equity_range_vs_range(eval, &r1, &r2, &board).expect("valid");
+
+        let dead = board.iter().fold(0u64, |m, c| m | c.mask());
+        let (mut num, mut den) = (0.0, 0.0);
+        for (h1, w1) in r1.iter() {
+            for (h2, w2) in r2.iter() {
+                if h1.mask() & (h2.mask() | dead) != 0 || h2.mask() & dead != 0 {
+                    continue;
+                }
+                let (eq, _) = equity_heads_up(eval, h1.cards(), h2.cards(), &board);
+                let w = (w1 * w2) as f64;
+                num += w * eq;
+                den += w;
+            }
+        }
+        assert!(
+            (result.equity - num / den).abs() < 1e-9,
+            "{} vs {}",
+            result.equity,
+            num / den
+        );
+    }
+
+    #[test]
+    fn test_preflop_is_sampled() {
+        let eval = LookupTableEvaluator::global();
+        let result = equity_range_vs_range(eval, &combos("AA"), &combos("KK"), &[]).expect("valid");
+        // AA is about 82% against KK
+        assert!((result.equity - 0.82).abs() < 0.01, "{}", result.equity);
+        let again = equity_range_vs_range(eval, &combos("AA"), &combos("KK"), &[]).expect("valid");
+        assert_eq!(again.equity, result.equity);
+
+        let hero = cards("As Ks");
+        let villain = cards("Qh Qd");
+        let (exact, _) = equity_heads_up(eval, [hero[0], hero[1]], [villain[0], villain[1]], &[]);
+        let sampled =
+            equity_range_vs_range(eval, &combos("AsKs"), &combos("QhQd"), &[]).expect("valid");
+        assert!(
+            (sampled.equity - exact).abs() < 0.01,
+            "{} vs {exact}",
+            sampled.equity
+        );
+    }
+
+    #[test]
+    fn test_runouts_are_lazy_and_complete() {
+        let deck: Vec<Card> = (0..52u8)
+            .map(|i| Card::from_u8(i).expect("valid"))
+            .collect();
+        assert_eq!(enumerate_runouts(&deck[..47], 2).count(), binomial(47, 2));
+        assert_eq!(enumerate_runouts(&deck[..10], 5).count(), 252);
+        assert_eq!(enumerate_runouts(&deck[..5], 0).count(), 1);
+        assert_eq!(enumerate_runouts(&deck[..3], 5).count(), 0);
+        let first = enumerate_runouts(&deck, 5).next().expect("one runout");
+        assert_eq!(first, [deck[0], deck[1], deck[2], deck[3], deck[4]]);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let eval = LookupTableEvaluator::global();
+        let board = cards("Ah Kh");
+        assert!(equity_range_vs_range(eval, &combos("AA"), &combos("KK"), &board).is_err());
+        let dup = cards("Ah Ah 2c");
+        assert!(equity_range_vs_range(eval, &combos("AA"), &combos("KK"), &dup).is_err());
+        // Every AA combo is blocked by the board
+        let board = cards("As Ah Ad Ac 2c");
+        assert!(equity_range_vs_range(eval, &combos("AA"), &combos("KK"), &board).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! A slow, obviously-correct evaluator used to cross-check table backends.
+//!
+//! Hands are classified the textbook way: group the ranks by multiplicity,
+//! look for a flush and a straight, and compare (category, tiebreak ranks)
+//! lexicographically. The `HandRank` numbering is derived by sorting one
+//! representative of every distinct 5-card strength, so it shares no code
+//! with the prime-product tables.
+
+use std::collections::HashMap;
+
+use poker_core::Card;
+
+use crate::evaluator::Evaluator;
+use crate::hand_rank::HandRank;
+
+/// Category (8 = straight flush .. 0 = high card) then tiebreak ranks,
+/// most significant first. Greater compares stronger.
+type Strength = (u8, [u8; 5]);
+
+/// Classify a 5-card hand given as `(rank 0..13, suit 0..4)` pairs.
+fn strength(cards: [(u8, u8); 5]) -> Strength {
+    let flush = cards.iter().all(|&(_, s)| s == cards[0].1);
+
+    let mut counts = [0u8; 13];
+    for &(r, _) in &cards {
+        counts[r as usize] += 1;
+    }
+    // (count, rank), biggest groups first, then highest rank.
+    let mut groups: Vec<(u8, u8)> = (0..13u8)
+        .filter(|&r| counts[r as usize] > 0)
+        .map(|r| (counts[r as usize], r))
+        .collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+
+    let straight_high = if groups.len() == 5 {
+        let high = groups[0].1;
+        let low = groups[4].1;
+        if high - low == 4 {
+            Some(high)
+        } else if high == 12 && groups[1].1 == 3 {
+            // A-5-4-3-2: the ace plays low.
+            Some(3)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let mut tiebreak = [0u8; 5];
+    if let Some(high) = straight_high {
+        tiebreak[0] = high;
+    } else {
+        for (slot, &(_, r)) in tiebreak.iter_mut().zip(&groups) {
+            *slot = r;
+        }
+    }
+
+    let category = match (straight_high.is_some(), flush, groups[0].0, groups[1].0) {
+        (true, true, _, _) => 8,
+        (_, _, 4, _) => 7,
+        (_, _, 3, 2) => 6,
+        (_, true, _, _) => 5,
+        (true, _, _, _) => 4,
+        (_, _, 3, _) => 3,
+        (_, _, 2, 2) => 2,
+        (_, _, 2, _) => 1,
+        _ => 0,
+    };
+    (category, tiebreak)
+}
+
+fn card_strength(cards: &[Card; 5]) -> Strength {
+    strength(cards.map(|c| (c.as_u8() >> 2, c.as_u8() & 3)))
+}
+
+/// Brute-force evaluator: classifies every 5-card subset directly.
+///
+/// Strengths are numbered like the table backends, 1 (royal flush) to 7462,
+/// so the two can be compared rank for rank.
+pub struct ReferenceEvaluator {
+    ranks: HashMap<Strength, u16>,
+}
+
+impl ReferenceEvaluator {
+    /// Build the strength-to-rank numbering.
+    pub fn new() -> Self {
+        fn multisets(counts: &mut [u8; 13], rank: usize, left: u8, out: &mut Vec<[u8; 13]>) {
+            if rank == 13 {
+                if left == 0 {
+                    out.push(*counts);
+                }
+                return;
+            }
+            for q in 0..=left.min(4) {
+                counts[rank] = q;
+                multisets(counts, rank + 1, left - q, out);
+            }
+            counts[rank] = 0;
+        }
+        let mut all = Vec::new();
+        multisets(&mut [0; 13], 0, 5, &mut all);
+
+        // Every rank multiset of 5 cards, dealt off-suit...
+        let mut strengths = Vec::new();
+        for counts in &all {
+            let mut cards = [(0u8, 0u8); 5];
+            let mut n = 0;
+            for (r, &q) in counts.iter().enumerate() {
+                for _ in 0..q {
+                    cards[n] = (r as u8, n as u8 % 4);
+                    n += 1;
+                }
+            }
+            strengths.push(strength(cards));
+            // ...and, for five distinct ranks, suited as well.
+            if counts.iter().all(|&q| q <= 1) {
+                strengths.push(strength(cards.map(|(r, _)| (r, 0))));
+            }
+        }
+
+        strengths.sort_unstable_by(|a, b| b.cmp(a));
+        strengths.dedup();
+        let ranks = strengths
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| (s, i as u16 + 1))
+            .collect();
+        ReferenceEvaluator { ranks }
+    }
+
+    fn rank_of(&self, strength: Strength) -> HandRank {
+        HandRank(self.ranks[&strength])
+    }
+}
+
+impl Default for ReferenceEvaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evaluator for ReferenceEvaluator {
+    fn evaluate_5(&self, cards: &[Card; 5]) -> HandRank {
+        self.rank_of(card_strength(cards))
+    }
+
+    fn evaluate_7(&self, cards: &[Card; 7]) -> HandRank {
+        let mut best = None;
+        for skip_a in 0..7 {
+            for skip_b in (skip_a + 1)..7 {
+                let mut five = [cards[0]; 5];
+                let mut j = 0;
+                for (i, &c) in cards.iter().enumerate() {
+                    if i != skip_a && i != skip_b {
+                        five[j] = c;
+                        j += 1;
+                    }
+                }
+                best = best.max(Some(card_strength(&five)));
+            }
+        }
+        self.rank_of(best.expect("21 subsets"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_rank::HandCategory;
+    use poker_core::{Rank, Suit};
+
+    #[test]
+    fn test_numbering_covers_all_classes() {
+        let eval = ReferenceEvaluator::new();
+        assert_eq!(eval.ranks.len(), 7462);
+        let royal = [
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::King, Suit::Spades),
+            Card::new(Rank::Queen, Suit::Spades),
+            Card::new(Rank::Jack, Suit::Spades),
+            Card::new(Rank::Ten, Suit::Spades),
+        ];
+        assert_eq!(eval.evaluate_5(&royal).value(), 1);
+        let worst = [
+            Card::new(Rank::Seven, Suit::Spades),
+            Card::new(Rank::Five, Suit::Hearts),
+            Card::new(Rank::Four, Suit::Spades),
+            Card::new(Rank::Three, Suit::Spades),
+            Card::new(Rank::Two, Suit::Spades),
+        ];
+        assert_eq!(eval.evaluate_5(&worst).value(), 7462);
+    }
+
+    #[test]
+    fn test_category_boundaries_match_hand_rank() {
+        let eval = ReferenceEvaluator::new();
+        let wheel = [
+            Card::new(Rank::Five, Suit::Spades),
+            Card::new(Rank::Four, Suit::Hearts),
+            Card::new(Rank::Three, Suit::Spades),
+            Card::new(Rank::Two, Suit::Clubs),
+            Card::new(Rank::Ace, Suit::Spades),
+        ];
+        let rank = eval.evaluate_5(&wheel);
+        assert_eq!(rank.value(), 1609);
+        assert_eq!(rank.category(), HandCategory::Straight);
+    }
+}
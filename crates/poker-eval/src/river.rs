@@ -0,0 +1,662 @@
+//! Showdown ranking of all 1326 combos on a river.
+//!
+//! `RiverRanking` sorts every combo that does not touch the board into
+//! strength buckets (one bucket per tie class, weakest first) and keeps a
+//! combo-index → bucket table for O(1) lookups. Range-vs-range showdowns are
+//! then a single sweep over the buckets with running totals, subtracting the
+//! per-card totals of combos that share a card with the hand being scored.
+//!
+//! Rankings depend only on the board up to suit isomorphism, so `RiverCache`
+//! builds one ranking per canonical river and relabels it for other suits,
+//! keeping both until its capacity is reached. Entries are keyed by the set
+//! of board cards, so the order the river was dealt in doesn't matter.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use poker_core::hand::NUM_COMBOS;
+use poker_core::{Board, Card, ComboRange, CoreError, Hand};
+
+use crate::batch::RankedHands;
+use crate::error::EvalError;
+use crate::evaluator::Evaluator;
+use crate::hand_rank::HandRank;
+use crate::isomorphism::{canonicalize_board, remap_hand};
+
+/// Bucket value for combos that use a board card.
+const BLOCKED: u16 = u16::MAX;
+
+/// Villain weight a hero combo beats, ties and loses to, net of card removal.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ShowdownWeights {
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+}
+
+impl ShowdownWeights {
+    /// Villain weight that can be dealt against this combo.
+    pub fn total(&self) -> f64 {
+        self.win + self.tie + self.lose
+    }
+
+    /// Showdown equity (win + tie / 2), or `None` with no live villain combos.
+    pub fn equity(&self) -> Option<f64> {
+        let total = self.total();
+        (total > 0.0).then(|| (self.win + self.tie / 2.0) / total)
+    }
+}
+
+/// All live combos on one river, grouped into strength buckets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiverRanking {
+    board: [Card; 5],
+    /// Bucket per combo index (0 = weakest), `BLOCKED` for board conflicts.
+    buckets: Vec<u16>,
+    /// Live combo indices, weakest first.
+    order: Vec<u16>,
+    /// Card indices of each entry in `order`.
+    cards: Vec<[u8; 2]>,
+    /// Offset in `order` where each bucket starts, plus a trailing end.
+    bucket_starts: Vec<u16>,
+    /// Hand rank shared by each bucket.
+    ranks: Vec<HandRank>,
+}
+
+impl RiverRanking {
+    /// Rank every combo on a 5-card board.
+    pub fn new<E: Evaluator + ?Sized>(eval: &E, board: &Board) -> Result<Self, EvalError> {
+        let board = river_cards(board)?;
+        let hands: Vec<Hand> = (0..NUM_COMBOS).filter_map(Hand::from_combo_index).collect();
+        let ranked = eval.evaluate_many_sorted(&board, &hands);
+        Ok(Self::from_ranked(board, &ranked))
+    }
+
+    /// Flip a strongest-first `RankedHands` into weakest-first buckets.
+    fn from_ranked(board: [Card; 5], ranked: &RankedHands) -> Self {
+        let classes = ranked.class_count();
+        let mut buckets = vec![BLOCKED; NUM_COMBOS];
+        let mut order = Vec::with_capacity(ranked.len());
+        let mut cards = Vec::with_capacity(ranked.len());
+        let mut bucket_starts = Vec::with_capacity(classes + 1);
+        let mut ranks = Vec::with_capacity(classes);
+        for class in (0..classes).rev() {
+            let bucket = ranks.len() as u16;
+            bucket_starts.push(order.len() as u16);
+            ranks.push(ranked.class_rank(class));
+            for &combo in ranked.class(class) {
+                buckets[combo as usize] = bucket;
+                order.push(combo as u16);
+                cards.push(combo_cards(combo as usize));
+            }
+        }
+        bucket_starts.push(order.len() as u16);
+
+        RiverRanking {
+            board,
+            buckets,
+            order,
+            cards,
+            bucket_starts,
+            ranks,
+        }
+    }
+
+    /// The same ranking seen through a suit relabelling.
+    ///
+    /// `mapping[suit of board]` gives the suit used by `self`, as returned in
+    /// `CanonicalBoard::suit_mapping`.
+    fn relabel(&self, board: [Card; 5], mapping: &[u8; 4]) -> Self {
+        let mut buckets = vec![BLOCKED; NUM_COMBOS];
+        for (combo, bucket) in buckets.iter_mut().enumerate() {
+            let Some(hand) = Hand::from_combo_index(combo) else {
+                continue;
+            };
+            let [c0, c1] = remap_hand(&hand.cards(), mapping);
+            *bucket = self.buckets[Hand::new(c0, c1).combo_index()];
+        }
+
+        // Counting sort by bucket; sizes match the original's.
+        let mut next = self.bucket_starts[..self.ranks.len()].to_vec();
+        let mut order = vec![0u16; self.order.len()];
+        let mut cards = vec![[0u8; 2]; self.order.len()];
+        for (combo, &bucket) in buckets.iter().enumerate() {
+            if bucket != BLOCKED {
+                let slot = next[bucket as usize] as usize;
+                order[slot] = combo as u16;
+                cards[slot] = combo_cards(combo);
+                next[bucket as usize] += 1;
+            }
+        }
+
+        RiverRanking {
+            board,
+            buckets,
+            order,
+            cards,
+            bucket_starts: self.bucket_starts.clone(),
+            ranks: self.ranks.clone(),
+        }
+    }
+
+    /// The board this ranking was built for.
+    pub fn board(&self) -> &[Card; 5] {
+        &self.board
+    }
+
+    /// Strength bucket of a combo (higher is stronger), `None` if it uses a
+    /// board card.
+    #[inline]
+    pub fn bucket(&self, hand: Hand) -> Option<u16> {
+        let bucket = self.buckets[hand.combo_index()];
+        (bucket != BLOCKED).then_some(bucket)
+    }
+
+    /// Number of distinct strength buckets.
+    pub fn bucket_count(&self) -> usize {
+        self.ranks.len()
+    }
+
+    /// Hand rank of every combo in `bucket`.
+    pub fn bucket_rank(&self, bucket: u16) -> HandRank {
+        self.ranks[bucket as usize]
+    }
+
+    /// Combo indices in `bucket`.
+    pub fn bucket_combos(&self, bucket: u16) -> &[u16] {
+        let start = self.bucket_starts[bucket as usize] as usize;
+        let end = self.bucket_starts[bucket as usize + 1] as usize;
+        &self.order[start..end]
+    }
+
+    /// All live combo indices, weakest first.
+    pub fn live_combos(&self) -> &[u16] {
+        &self.order
+    }
+
+    /// Cumulative villain weight strictly below each bucket, plus the total.
+    /// `weights` is indexed by combo index (see `ComboRange::weights`).
+    pub fn prefix_sums(&self, weights: &[f32]) -> Vec<f64> {
+        assert_eq!(weights.len(), NUM_COMBOS, "weights are per combo index");
+        let mut sums = Vec::with_capacity(self.ranks.len() + 1);
+        let mut total = 0.0f64;
+        sums.push(0.0);
+        for bucket in 0..self.ranks.len() {
+            for &combo in self.bucket_combos(bucket as u16) {
+                total += weights[combo as usize] as f64;
+            }
+            sums.push(total);
+        }
+        sums
+    }
+
+    /// Win, tie and lose weight of every combo against a villain range.
+    ///
+    /// `villain` is indexed by combo index; the result is too, with zeros for
+    /// combos that use a board card. Villain combos sharing a card with the
+    /// hero combo are excluded exactly.
+    pub fn showdown_weights(&self, villain: &[f32]) -> Vec<ShowdownWeights> {
+        assert_eq!(villain.len(), NUM_COMBOS, "weights are per combo index");
+        let weight = |pos: usize| villain[self.order[pos] as usize] as f64;
+
+        // Totals over every live villain combo, for the "lose" remainder.
+        let mut all_total = 0.0f64;
+        let mut all_card = [0.0f64; 52];
+        for (pos, &[a, b]) in self.cards.iter().enumerate() {
+            let w = weight(pos);
+            all_total += w;
+            all_card[a as usize] += w;
+            all_card[b as usize] += w;
+        }
+
+        let mut out = vec![ShowdownWeights::default(); NUM_COMBOS];
+        // Running totals up to the start (`below`) and end (`upto`) of the
+        // current bucket; their difference is the bucket itself.
+        let mut below_total = 0.0f64;
+        let mut below_card = [0.0f64; 52];
+        for bucket in 0..self.ranks.len() {
+            let start = self.bucket_starts[bucket] as usize;
+            let end = self.bucket_starts[bucket + 1] as usize;
+            let mut upto_total = below_total;
+            let mut upto_card = below_card;
+            for pos in start..end {
+                let w = weight(pos);
+                let [a, b] = self.cards[pos];
+                upto_total += w;
+                upto_card[a as usize] += w;
+                upto_card[b as usize] += w;
+            }
+
+            for pos in start..end {
+                let own = weight(pos);
+                let [a, b] = self.cards[pos].map(|c| c as usize);
+                let win = below_total - below_card[a] - below_card[b];
+                // The identical combo was subtracted once per card.
+                let not_above = upto_total - upto_card[a] - upto_card[b] + own;
+                let live = all_total - all_card[a] - all_card[b] + own;
+                out[self.order[pos] as usize] = ShowdownWeights {
+                    win,
+                    tie: not_above - win,
+                    lose: live - not_above,
+                };
+            }
+
+            below_total = upto_total;
+            below_card = upto_card;
+        }
+        out
+    }
+
+    /// Showdown equity of `hero` against `villain`, weighting every matchup
+    /// by both combos' weights. `None` if no live matchup exists.
+    pub fn equity(&self, hero: &ComboRange, villain: &ComboRange) -> Option<f64> {
+        let weights = self.showdown_weights(villain.weights());
+        let mut won = 0.0f64;
+        let mut total = 0.0f64;
+        for &combo in &self.order {
+            let h = hero.weights()[combo as usize] as f64;
+            if h > 0.0 {
+                let sw = &weights[combo as usize];
+                won += h * (sw.win + sw.tie / 2.0);
+                total += h * sw.total();
+            }
+        }
+        (total > 0.0).then(|| won / total)
+    }
+}
+
+/// Rankings kept by `RiverCache::new`, roughly 8 KB each.
+pub const DEFAULT_RIVER_CACHE_CAPACITY: usize = 4096;
+
+/// River rankings shared per canonical board.
+///
+/// Relabelled rankings for non-canonical boards are cached too, so repeated
+/// lookups of any board cost a map hit. At most `capacity` rankings are kept;
+/// the least recently used one is dropped to make room.
+pub struct RiverCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// Ranking and last use per board, keyed by the board's card mask.
+    rankings: HashMap<u64, (Arc<RiverRanking>, u64)>,
+    /// Board mask per last use, oldest first.
+    by_use: BTreeMap<u64, u64>,
+    clock: u64,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: u64) -> Option<Arc<RiverRanking>> {
+        let (ranking, used) = self.rankings.get_mut(&key)?;
+        self.by_use.remove(used);
+        self.clock += 1;
+        *used = self.clock;
+        self.by_use.insert(self.clock, key);
+        Some(Arc::clone(ranking))
+    }
+
+    /// Cache `ranking` unless another thread got there first, evicting the
+    /// least recently used entry when full. Returns the cached ranking.
+    fn insert(
+        &mut self,
+        key: u64,
+        ranking: Arc<RiverRanking>,
+        capacity: usize,
+    ) -> Arc<RiverRanking> {
+        if let Some(existing) = self.touch(key) {
+            return existing;
+        }
+        if self.rankings.len() >= capacity {
+            if let Some((_, oldest)) = self.by_use.pop_first() {
+                self.rankings.remove(&oldest);
+            }
+        }
+        self.clock += 1;
+        self.by_use.insert(self.clock, key);
+        self.rankings
+            .insert(key, (Arc::clone(&ranking), self.clock));
+        ranking
+    }
+
+    fn clear(&mut self) {
+        self.rankings.clear();
+        self.by_use.clear();
+    }
+}
+
+impl RiverCache {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_RIVER_CACHE_CAPACITY)
+    }
+
+    /// A cache holding at most `capacity` rankings (at least one).
+    pub fn with_capacity(capacity: usize) -> Self {
+        RiverCache {
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Ranking for `board`, building and caching its canonical form on a miss.
+    /// Isomorphic boards share one evaluation and are relabelled on the way
+    /// out; the relabelled ranking is cached under the board's card set, so
+    /// any order of the same five cards is a hit. Cached rankings list their
+    /// board highest rank first.
+    pub fn get<E: Evaluator + ?Sized>(
+        &self,
+        eval: &E,
+        board: &Board,
+    ) -> Result<Arc<RiverRanking>, EvalError> {
+        let mut cards = river_cards(board)?;
+        let key = board_mask(&cards);
+        if let Some(ranking) = self.lock().touch(key) {
+            return Ok(ranking);
+        }
+        cards.sort_by(|a, b| b.rank().cmp(&a.rank()).then(a.suit().cmp(&b.suit())));
+
+        let canonical = canonicalize_board(&cards);
+        let canon_cards: [Card; 5] = [
+            canonical.cards[0],
+            canonical.cards[1],
+            canonical.cards[2],
+            canonical.cards[3],
+            canonical.cards[4],
+        ];
+        let canon_key = board_mask(&canon_cards);
+        let cached = self.lock().touch(canon_key);
+        let ranking = match cached {
+            Some(ranking) => ranking,
+            None => {
+                let canon_board = Board::from_cards(&canon_cards)?;
+                let ranking = Arc::new(RiverRanking::new(eval, &canon_board)?);
+                self.lock().insert(canon_key, ranking, self.capacity)
+            }
+        };
+
+        if key == canon_key {
+            return Ok(ranking);
+        }
+        let relabelled = Arc::new(ranking.relabel(cards, &canonical.suit_mapping));
+        Ok(self.lock().insert(key, relabelled, self.capacity))
+    }
+
+    /// Most rankings kept at once.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of rankings cached, canonical and relabelled.
+    pub fn len(&self) -> usize {
+        self.lock().rankings.len()
+    }
+
+    /// Whether nothing has been cached yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop every cached ranking. Rankings already handed out stay valid.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        // A panic while holding the lock cannot leave the map inconsistent.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for RiverCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Card indices of a combo.
+fn combo_cards(combo: usize) -> [u8; 2] {
+    let hand = Hand::from_combo_index(combo).expect("combo index below NUM_COMBOS");
+    [hand.card1().as_u8(), hand.card2().as_u8()]
+}
+
+/// The five cards of a complete, duplicate-free river board.
+fn river_cards(board: &Board) -> Result<[Card; 5], EvalError> {
+    let cards = board.cards();
+    if cards.len() != 5 {
+        return Err(CoreError::InvalidBoardSize(cards.len()).into());
+    }
+    let cards = [cards[0], cards[1], cards[2], cards[3], cards[4]];
+    if board_mask(&cards).count_ones() != 5 {
+        return Err(EvalError::DuplicateCard);
+    }
+    Ok(cards)
+}
+
+fn board_mask(cards: &[Card; 5]) -> u64 {
+    cards.iter().fold(0u64, |m, c| m | c.mask())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range_equity::equity_range_vs_range;
+    use crate::seven_card::SevenCardEvaluator;
+    use poker_core::{Range, Rank, Suit};
+
+    fn board(cards: &[(Rank, Suit)]) -> Board {
+        let cards: Vec<Card> = cards.iter().map(|&(r, s)| Card::new(r, s)).collect();
+        Board::from_cards(&cards).expect("valid board")
+    }
+
+    fn dry_river() -> Board {
+        board(&[
+            (Rank::King, Suit::Spades),
+            (Rank::Nine, Suit::Hearts),
+            (Rank::Seven, Suit::Diamonds),
+            (Rank::Four, Suit::Clubs),
+            (Rank::Two, Suit::Spades),
+        ])
+    }
+
+    fn range(notation: &str) -> ComboRange {
+        ComboRange::from_range(&notation.parse::<Range>().expect("valid range"))
+    }
+
+    #[test]
+    fn test_buckets_follow_hand_ranks() {
+        let eval = SevenCardEvaluator::global();
+        let river = RiverRanking::new(eval, &dry_river()).expect("river");
+        assert_eq!(river.live_combos().len(), 1081);
+        for bucket in 1..river.bucket_count() as u16 {
+            assert!(river.bucket_rank(bucket) > river.bucket_rank(bucket - 1));
+        }
+
+        let blocked = Hand::new(
+            Card::new(Rank::King, Suit::Spades),
+            Card::new(Rank::Ace, Suit::Hearts),
+        );
+        assert_eq!(river.bucket(blocked), None);
+
+        // Every combo's bucket agrees with a direct 7-card evaluation.
+        let cards = river.board();
+        for &combo in river.live_combos() {
+            let hand = Hand::from_combo_index(combo as usize).expect("valid");
+            let [c0, c1] = hand.cards();
+            let rank = eval.evaluate_7(&[c0, c1, cards[0], cards[1], cards[2], cards[3], cards[4]]);
+            let bucket = river.bucket(hand).expect("live");
+            assert_eq!(river.bucket_rank(bucket), rank);
+            assert!(river.bucket_combos(bucket).contains(&combo));
+        }
+    }
+
+    #[test]
+    fn test_showdown_weights_match_brute_force() {
+        let eval = SevenCardEvaluator::global();
+        let river = RiverRanking::new(eval, &dry_river()).expect("river");
+        let villain = range("TT+,AK,K9s,72o");
+        let weights = river.showdown_weights(villain.weights());
+
+        for &combo in river.live_combos().iter().step_by(17) {
+            let hero = Hand::from_combo_index(combo as usize).expect("valid");
+            let mut expected = ShowdownWeights::default();
+            for (other, w) in villain.iter() {
+                if other.mask() & hero.mask() != 0 {
+                    continue;
+                }
+                let (Some(mine), Some(theirs)) = (river.bucket(hero), river.bucket(other)) else {
+                    continue;
+                };
+                let w = w as f64;
+                match mine.cmp(&theirs) {
+                    std::cmp::Ordering::Greater => expected.win += w,
+                    std::cmp::Ordering::Equal => expected.tie += w,
+                    std::cmp::Ordering::Less => expected.lose += w,
+                }
+            }
+            let got = weights[combo as usize];
+            assert!((got.win - expected.win).abs() < 1e-9, "{hero}");
+            assert!((got.tie - expected.tie).abs() < 1e-9, "{hero}");
+            assert!((got.lose - expected.lose).abs() < 1e-9, "{hero}");
+        }
+
+        let sums = river.prefix_sums(villain.weights());
+        assert_eq!(sums.len(), river.bucket_count() + 1);
+        let live: f64 = river
+            .live_combos()
+            .iter()
+            .map(|&c| villain.weights()[c as usize] as f64)
+            .sum();
+        assert!((sums[river.bucket_count()] - live).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_equity_matches_range_vs_range() {
+        let eval = SevenCardEvaluator::global();
+        let board = dry_river();
+        let river = RiverRanking::new(eval, &board).expect("river");
+        let hero = range("AA,KQs,98s,55");
+        let villain = range("TT+,AK,K9s,72o");
+
+        let fast = river.equity(&hero, &villain).expect("live matchups");
+        let exact = equity_range_vs_range(eval, &hero, &villain, board.cards()).expect("valid");
+        assert!(
+            (fast - exact.equity).abs() < 1e-9,
+            "{fast} vs {}",
+            exact.equity
+        );
+    }
+
+    #[test]
+    fn test_cache_shares_isomorphic_rivers() {
+        let eval = SevenCardEvaluator::global();
+        let cache = RiverCache::new();
+        let spades = dry_river();
+        // Same river with spades and hearts swapped.
+        let swapped = board(&[
+            (Rank::King, Suit::Hearts),
+            (Rank::Nine, Suit::Spades),
+            (Rank::Seven, Suit::Diamonds),
+            (Rank::Four, Suit::Clubs),
+            (Rank::Two, Suit::Hearts),
+        ]);
+
+        let a = cache.get(eval, &spades).expect("river");
+        let b = cache.get(eval, &swapped).expect("river");
+        assert_eq!(*a, RiverRanking::new(eval, &spades).expect("river"));
+        assert_eq!(*b, RiverRanking::new(eval, &swapped).expect("river"));
+        // One canonical ranking plus at most one relabelled copy, and a
+        // repeated lookup of either board is a plain hit.
+        let cached = cache.len();
+        assert!((1..=3).contains(&cached));
+        assert!(Arc::ptr_eq(&a, &cache.get(eval, &spades).expect("river")));
+        assert!(Arc::ptr_eq(&b, &cache.get(eval, &swapped).expect("river")));
+        assert_eq!(cache.len(), cached);
+        let flop = board(&[
+            (Rank::Ace, Suit::Spades),
+            (Rank::Ace, Suit::Hearts),
+            (Rank::Two, Suit::Clubs),
+        ]);
+        assert!(cache.get(eval, &flop).is_err());
+    }
+
+    #[test]
+    fn test_cache_ignores_card_order() {
+        let eval = SevenCardEvaluator::global();
+        let cache = RiverCache::new();
+        let river = dry_river();
+        let mut cards = river.cards().to_vec();
+        let first = cache.get(eval, &river).expect("river");
+        let cached = cache.len();
+        for _ in 0..4 {
+            cards.rotate_left(1);
+            cards.swap(0, 2);
+            let reordered = Board::from_cards(&cards).expect("board");
+            let ranking = cache.get(eval, &reordered).expect("river");
+            assert!(Arc::ptr_eq(&first, &ranking));
+        }
+        assert_eq!(cache.len(), cached);
+
+        // A relabelled river is shared across orders too
+        let hearts: Vec<Card> = cards
+            .iter()
+            .rev()
+            .map(|c| match c.suit() {
+                Suit::Spades => Card::new(c.rank(), Suit::Hearts),
+                Suit::Hearts => Card::new(c.rank(), Suit::Spades),
+                _ => *c,
+            })
+            .collect();
+        let swapped = Board::from_cards(&hearts).expect("board");
+        let a = cache.get(eval, &swapped).expect("river");
+        let cached = cache.len();
+        let mut reversed = hearts.clone();
+        reversed.reverse();
+        let b = cache
+            .get(eval, &Board::from_cards(&reversed).expect("board"))
+            .expect("river");
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(cache.len(), cached);
+        let expected = RiverRanking::new(eval, &swapped).expect("river");
+        for hand in (0..NUM_COMBOS).filter_map(Hand::from_combo_index) {
+            assert_eq!(a.bucket(hand), expected.bucket(hand));
+        }
+    }
+
+    #[test]
+    fn test_cache_is_bounded() {
+        let eval = SevenCardEvaluator::global();
+        let cache = RiverCache::with_capacity(2);
+        let rivers: Vec<Board> = [Rank::Two, Rank::Three, Rank::Five]
+            .into_iter()
+            .map(|low| {
+                board(&[
+                    (Rank::Ace, Suit::Clubs),
+                    (Rank::Ace, Suit::Diamonds),
+                    (Rank::Ace, Suit::Hearts),
+                    (Rank::Ace, Suit::Spades),
+                    (low, Suit::Clubs),
+                ])
+            })
+            .collect();
+        let first = cache.get(eval, &rivers[0]).expect("river");
+        // Quads in every suit are canonical as given: one entry per river
+        assert_eq!(cache.len(), 1);
+        cache.get(eval, &rivers[1]).expect("river");
+        // Touch the first river so the second is the one evicted
+        cache.get(eval, &rivers[0]).expect("river");
+        cache.get(eval, &rivers[2]).expect("river");
+        assert_eq!(cache.len(), 2);
+        assert!(Arc::ptr_eq(
+            &first,
+            &cache.get(eval, &rivers[0]).expect("river")
+        ));
+
+        cache.clear();
+        assert!(cache.is_empty());
+        // Handed-out rankings outlive the cache entry
+        assert_eq!(*first, RiverRanking::new(eval, &rivers[0]).expect("river"));
+    }
+}
@@ -0,0 +1,325 @@
+//! Direct 7-card evaluation.
+//!
+//! Instead of scoring the 21 five-card subsets, a hand is reduced to its rank
+//! multiset and per-suit rank masks. If one suit holds five or more cards the
+//! flush table answers directly (a full house or quads cannot coexist with a
+//! flush in seven cards); otherwise the rank multiset is mapped to a dense
+//! index by a quinary perfect hash and looked up. Both tables are generated at
+//! build time and return the same `HandRank` values as `LookupTableEvaluator`.
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use poker_core::{Card, Hand};
+
+use crate::batch::{board_mask, is_live};
+use crate::error::EvalError;
+use crate::evaluator::Evaluator;
+use crate::hand_rank::HandRank;
+use crate::lookup_table::{embedded, LookupTableEvaluator};
+use crate::table_gen;
+
+static GLOBAL: OnceLock<SevenCardEvaluator> = OnceLock::new();
+
+/// A partially dealt hand: rank counts plus per-suit rank masks.
+///
+/// Fix the hole cards and the known board once, then fold in the remaining
+/// cards with [`PartialHand::with`]. The state is `Copy` and small, so it is
+/// cheap to carry through each level of an enumeration loop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PartialHand {
+    counts: [u8; 13],
+    suit_counts: [u8; 4],
+    suit_masks: [u16; 4],
+    len: u8,
+}
+
+impl PartialHand {
+    /// Build from a set of known cards (at most 7).
+    pub fn new(cards: &[Card]) -> Self {
+        cards.iter().fold(Self::default(), |hand, &c| hand.with(c))
+    }
+
+    /// Return this hand with one more card added.
+    #[inline(always)]
+    pub fn with(self, card: Card) -> Self {
+        let c = card.as_u8();
+        let rank = (c >> 2) as usize;
+        let suit = (c & 3) as usize;
+        debug_assert!(self.len < 7, "a hand holds at most 7 cards");
+        debug_assert!(self.suit_masks[suit] & (1 << rank) == 0, "duplicate card");
+
+        let mut next = self;
+        next.counts[rank] += 1;
+        next.suit_counts[suit] += 1;
+        next.suit_masks[suit] |= 1 << rank;
+        next.len += 1;
+        next
+    }
+
+    /// Number of cards held.
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Whether no cards have been added yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Hand evaluator that ranks 7 cards with a single table lookup.
+///
+/// The tables are derived from `LookupTableEvaluator`'s, so ranks use the
+/// same Cactus Kev numbering (1 to 7462).
+pub struct SevenCardEvaluator {
+    flush7: Cow<'static, [u16]>,
+    noflush7: Cow<'static, [u16]>,
+}
+
+impl SevenCardEvaluator {
+    /// Create an evaluator backed by the tables embedded at build time.
+    pub fn new() -> Self {
+        SevenCardEvaluator {
+            flush7: Cow::Borrowed(&embedded::FLUSH7_TABLE),
+            noflush7: Cow::Borrowed(&embedded::NOFLUSH7_TABLE),
+        }
+    }
+
+    /// Create an evaluator by generating the tables at runtime.
+    /// Used as a fallback when the embedded tables fail verification.
+    pub fn generate() -> Self {
+        let flush_table = table_gen::generate_flush_table();
+        let unique5 = table_gen::generate_unique5_table();
+        SevenCardEvaluator {
+            flush7: Cow::Owned(table_gen::generate_flush7_table(&flush_table)),
+            noflush7: Cow::Owned(table_gen::generate_noflush7_table(&flush_table, &unique5)),
+        }
+    }
+
+    /// The process-wide shared evaluator, verified on first use.
+    pub fn global() -> &'static SevenCardEvaluator {
+        GLOBAL.get_or_init(|| {
+            let eval = Self::new();
+            match eval.verify() {
+                Ok(()) => eval,
+                Err(e) => {
+                    tracing::error!("{e}; regenerating 7-card evaluator tables");
+                    Self::generate()
+                }
+            }
+        })
+    }
+
+    /// Check the tables against the build-time checksum and expected shape.
+    pub fn verify(&self) -> Result<(), EvalError> {
+        if self.flush7.len() != 8192 || self.noflush7.len() != table_gen::NOFLUSH7_SIZE {
+            return Err(EvalError::CorruptTable(format!(
+                "7-card tables have {} and {} slots, expected 8192 and {}",
+                self.flush7.len(),
+                self.noflush7.len(),
+                table_gen::NOFLUSH7_SIZE
+            )));
+        }
+        let checksum = table_gen::table_checksum(&self.flush7, &[], &self.noflush7);
+        if checksum != embedded::CHECKSUM7 {
+            return Err(EvalError::CorruptTable(format!(
+                "7-card checksum {checksum:#018x} does not match {:#018x}",
+                embedded::CHECKSUM7
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rank a complete 7-card hand.
+    #[inline(always)]
+    pub fn rank(&self, hand: &PartialHand) -> HandRank {
+        debug_assert_eq!(hand.len, 7, "rank() needs exactly 7 cards");
+        for suit in 0..4 {
+            if hand.suit_counts[suit] >= 5 {
+                return HandRank(self.flush7[hand.suit_masks[suit] as usize]);
+            }
+        }
+        HandRank(self.noflush7[table_gen::quinary_index(&hand.counts)])
+    }
+
+    /// Rank a partial hand completed by `rest`, which must bring it to 7 cards.
+    #[inline]
+    pub fn evaluate_with(&self, hand: &PartialHand, rest: &[Card]) -> HandRank {
+        let full = rest.iter().fold(*hand, |h, &c| h.with(c));
+        self.rank(&full)
+    }
+
+    /// Evaluate the best 5-card hand from 7 cards.
+    #[inline]
+    pub fn evaluate_7(&self, cards: &[Card; 7]) -> HandRank {
+        self.rank(&PartialHand::new(cards))
+    }
+}
+
+impl Default for SevenCardEvaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evaluator for SevenCardEvaluator {
+    /// Five-card hands have no direct table; they go to the shared
+    /// `LookupTableEvaluator`.
+    #[inline]
+    fn evaluate_5(&self, cards: &[Card; 5]) -> HandRank {
+        LookupTableEvaluator::global().evaluate_5(cards)
+    }
+
+    #[inline]
+    fn evaluate_7(&self, cards: &[Card; 7]) -> HandRank {
+        SevenCardEvaluator::evaluate_7(self, cards)
+    }
+
+    /// Folds the board in once and adds only the two hole cards per combo.
+    fn evaluate_many(&self, board: &[Card; 5], hands: &[Hand]) -> Vec<HandRank> {
+        let Some(mask) = board_mask(board) else {
+            return vec![HandRank::INVALID; hands.len()];
+        };
+        let base = PartialHand::new(board);
+        hands
+            .iter()
+            .map(|&hand| {
+                if !is_live(hand, mask) {
+                    return HandRank::INVALID;
+                }
+                let [c0, c1] = hand.cards();
+                self.rank(&base.with(c0).with(c1))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::{Rank, Suit};
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    fn deck() -> Vec<Card> {
+        (0..52u8)
+            .map(|i| Card::from_u8(i).expect("valid"))
+            .collect()
+    }
+
+    #[test]
+    fn test_tables_verify() {
+        assert!(SevenCardEvaluator::new().verify().is_ok());
+        let generated = SevenCardEvaluator::generate();
+        assert!(generated.verify().is_ok());
+        assert_eq!(generated.noflush7, SevenCardEvaluator::new().noflush7);
+    }
+
+    #[test]
+    fn test_royal_flush_and_wheel() {
+        let eval = SevenCardEvaluator::global();
+        let royal = [
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::King, Suit::Spades),
+            Card::new(Rank::Queen, Suit::Spades),
+            Card::new(Rank::Jack, Suit::Spades),
+            Card::new(Rank::Ten, Suit::Spades),
+            Card::new(Rank::Two, Suit::Hearts),
+            Card::new(Rank::Three, Suit::Clubs),
+        ];
+        assert_eq!(eval.evaluate_7(&royal).value(), 1);
+
+        let wheel = [
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::Two, Suit::Hearts),
+            Card::new(Rank::Three, Suit::Diamonds),
+            Card::new(Rank::Four, Suit::Clubs),
+            Card::new(Rank::Five, Suit::Spades),
+            Card::new(Rank::King, Suit::Hearts),
+            Card::new(Rank::Nine, Suit::Clubs),
+        ];
+        assert_eq!(eval.evaluate_7(&wheel).value(), 1609);
+    }
+
+    #[test]
+    fn test_matches_lookup_evaluator_random() {
+        let fast = SevenCardEvaluator::global();
+        let reference = LookupTableEvaluator::global();
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut cards = deck();
+        for _ in 0..200_000 {
+            cards.shuffle(&mut rng);
+            let hand = [
+                cards[0], cards[1], cards[2], cards[3], cards[4], cards[5], cards[6],
+            ];
+            assert_eq!(
+                fast.evaluate_7(&hand),
+                reference.evaluate_7(&hand),
+                "{hand:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_incremental_matches_full() {
+        let eval = SevenCardEvaluator::global();
+        let cards = deck();
+        let base = PartialHand::new(&cards[..5]);
+        assert_eq!(base.len(), 5);
+        for i in 5..52 {
+            for j in (i + 1)..52 {
+                let full = [
+                    cards[0], cards[1], cards[2], cards[3], cards[4], cards[i], cards[j],
+                ];
+                assert_eq!(
+                    eval.evaluate_with(&base, &[cards[i], cards[j]]),
+                    eval.evaluate_7(&full)
+                );
+            }
+        }
+    }
+
+    /// Exhaustive cross-check over all C(52, 7) = 133,784,560 hands.
+    /// Run with `cargo test -p poker-eval --release --features exhaustive-tests`.
+    #[cfg(feature = "exhaustive-tests")]
+    #[test]
+    fn test_matches_lookup_evaluator_exhaustive() {
+        let fast = SevenCardEvaluator::global();
+        let reference = LookupTableEvaluator::global();
+        let cards = deck();
+        let mut checked = 0u64;
+        for a in 0..52 {
+            let ha = PartialHand::default().with(cards[a]);
+            for b in (a + 1)..52 {
+                let hb = ha.with(cards[b]);
+                for c in (b + 1)..52 {
+                    let hc = hb.with(cards[c]);
+                    for d in (c + 1)..52 {
+                        let hd = hc.with(cards[d]);
+                        for e in (d + 1)..52 {
+                            let he = hd.with(cards[e]);
+                            for f in (e + 1)..52 {
+                                let hf = he.with(cards[f]);
+                                for g in (f + 1)..52 {
+                                    let hand = [
+                                        cards[a], cards[b], cards[c], cards[d], cards[e], cards[f],
+                                        cards[g],
+                                    ];
+                                    assert_eq!(
+                                        fast.rank(&hf.with(cards[g])),
+                                        reference.evaluate_7(&hand),
+                                        "{hand:?}"
+                                    );
+                                    checked += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(checked, 133_784_560);
+    }
+}
@@ -0,0 +1,660 @@
+//! Player statistics aggregated over hand histories, as shown in a HUD.
+//!
+//! Each hand is first reduced to a [`HandRecord`]: per seat, the counters
+//! that hand adds. [`Stats`] sums the records that pass its
+//! [`StatsFilter`], per player name. Records are small and filter-agnostic,
+//! so [`StatsCache`] keeps them on disk per imported file and re-imports of
+//! an unchanged file skip parsing and equity calculations entirely.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use poker_core::{Action, GameType, HandHistory, HandHistoryReader, Position, Street};
+use serde::{Deserialize, Serialize};
+
+use crate::all_in::all_in_ev;
+use crate::error::EvalError;
+use crate::evaluator::Evaluator;
+
+/// Bump when the meaning of a counter changes, so old caches are rebuilt.
+pub const CACHE_VERSION: u32 = 2;
+
+/// Counters for one player. Amounts are in big blinds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub hands: u32,
+    /// Hands with a voluntary preflop call, bet or raise.
+    pub vpip: u32,
+    /// Hands with a preflop raise.
+    pub pfr: u32,
+    /// Times facing exactly one preflop raise, and times re-raising it.
+    pub three_bet_chances: u32,
+    pub three_bets: u32,
+    /// Times the preflop opener faced a 3-bet, and times folding to it.
+    pub fold_to_three_bet_chances: u32,
+    pub folds_to_three_bet: u32,
+    /// Per flop, turn and river: times the previous street's last aggressor
+    /// was first to bet, and times they bet.
+    pub cbet_chances: [u32; 3],
+    pub cbets: [u32; 3],
+    pub saw_flop: u32,
+    pub showdowns: u32,
+    pub showdowns_won: u32,
+    /// Postflop bets and raises, and postflop calls.
+    pub aggressive: u32,
+    pub calls: u32,
+    pub net: f64,
+    /// `net` with all-in hands counted at their expected value, pot by pot
+    /// when there are side pots.
+    pub ev_net: f64,
+}
+
+impl PlayerStats {
+    pub fn merge(&mut self, other: &PlayerStats) {
+        self.hands += other.hands;
+        self.vpip += other.vpip;
+        self.pfr += other.pfr;
+        self.three_bet_chances += other.three_bet_chances;
+        self.three_bets += other.three_bets;
+        self.fold_to_three_bet_chances += other.fold_to_three_bet_chances;
+        self.folds_to_three_bet += other.folds_to_three_bet;
+        for i in 0..3 {
+            self.cbet_chances[i] += other.cbet_chances[i];
+            self.cbets[i] += other.cbets[i];
+        }
+        self.saw_flop += other.saw_flop;
+        self.showdowns += other.showdowns;
+        self.showdowns_won += other.showdowns_won;
+        self.aggressive += other.aggressive;
+        self.calls += other.calls;
+        self.net += other.net;
+        self.ev_net += other.ev_net;
+    }
+
+    /// Percentages are `None` until there is at least one chance.
+    pub fn vpip_percent(&self) -> Option<f64> {
+        percent(self.vpip, self.hands)
+    }
+
+    pub fn pfr_percent(&self) -> Option<f64> {
+        percent(self.pfr, self.hands)
+    }
+
+    pub fn three_bet_percent(&self) -> Option<f64> {
+        percent(self.three_bets, self.three_bet_chances)
+    }
+
+    pub fn fold_to_three_bet_percent(&self) -> Option<f64> {
+        percent(self.folds_to_three_bet, self.fold_to_three_bet_chances)
+    }
+
+    /// C-bet percentage on the flop, turn or river.
+    pub fn cbet_percent(&self, street: Street) -> Option<f64> {
+        let i = match street {
+            Street::Preflop => return None,
+            Street::Flop => 0,
+            Street::Turn => 1,
+            Street::River => 2,
+        };
+        percent(self.cbets[i], self.cbet_chances[i])
+    }
+
+    /// Went to showdown, as a percentage of flops seen.
+    pub fn wtsd_percent(&self) -> Option<f64> {
+        percent(self.showdowns, self.saw_flop)
+    }
+
+    /// Won money at showdown, as a percentage of showdowns.
+    pub fn wsd_percent(&self) -> Option<f64> {
+        percent(self.showdowns_won, self.showdowns)
+    }
+
+    /// Postflop bets and raises per call. Infinite with no calls.
+    pub fn aggression_factor(&self) -> Option<f64> {
+        match (self.aggressive, self.calls) {
+            (0, 0) => None,
+            (_, 0) => Some(f64::INFINITY),
+            (a, c) => Some(a as f64 / c as f64),
+        }
+    }
+
+    /// Big blinds won per 100 hands.
+    pub fn bb_per_100(&self) -> Option<f64> {
+        per_100(self.net, self.hands)
+    }
+
+    /// All-in adjusted big blinds won per 100 hands.
+    pub fn ev_bb_per_100(&self) -> Option<f64> {
+        per_100(self.ev_net, self.hands)
+    }
+}
+
+fn percent(count: u32, chances: u32) -> Option<f64> {
+    (chances > 0).then(|| count as f64 / chances as f64 * 100.0)
+}
+
+fn per_100(amount: f64, hands: u32) -> Option<f64> {
+    (hands > 0).then(|| amount / hands as f64 * 100.0)
+}
+
+/// One seat's contribution from one hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeatRecord {
+    pub player: String,
+    pub position: Option<Position>,
+    /// Starting stack in big blinds.
+    pub stack: f64,
+    pub stats: PlayerStats,
+}
+
+/// What one hand adds to each dealt-in player's stats.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandRecord {
+    pub site: String,
+    pub hand_id: String,
+    pub game_type: GameType,
+    pub seats: Vec<SeatRecord>,
+}
+
+impl HandRecord {
+    pub fn new<E: Evaluator + ?Sized>(eval: &E, hand: &HandHistory) -> Result<Self, EvalError> {
+        let n = hand.seats.len();
+        let mut stats = vec![PlayerStats::default(); n];
+        let folded_preflop = |seat: usize| {
+            hand.street_actions(Street::Preflop)
+                .any(|a| a.seat == seat && a.action == Action::Fold)
+        };
+
+        // Preflop: count raises to find 3-bet and fold to 3-bet chances
+        let mut raises = 0;
+        let mut opener = None;
+        for a in hand.street_actions(Street::Preflop) {
+            let s = &mut stats[a.seat];
+            let aggressive = is_aggressive(a.action);
+            if a.action != Action::Fold && a.action != Action::Check {
+                s.vpip = 1;
+            }
+            if aggressive {
+                s.pfr = 1;
+            }
+            if raises == 1 && opener != Some(a.seat) {
+                s.three_bet_chances = 1;
+                s.three_bets |= aggressive as u32;
+            }
+            if raises == 2 && opener == Some(a.seat) {
+                s.fold_to_three_bet_chances = 1;
+                s.folds_to_three_bet |= (a.action == Action::Fold) as u32;
+            }
+            if aggressive {
+                raises += 1;
+                if raises == 1 {
+                    opener = Some(a.seat);
+                }
+            }
+        }
+
+        // Postflop: c-bets and aggression
+        let mut aggressor = last_aggressor(hand, Street::Preflop);
+        for (i, street) in [Street::Flop, Street::Turn, Street::River]
+            .into_iter()
+            .enumerate()
+        {
+            let mut bet = false;
+            for a in hand.street_actions(street) {
+                let aggressive = is_aggressive(a.action);
+                if !bet && aggressor == Some(a.seat) {
+                    stats[a.seat].cbet_chances[i] += 1;
+                    stats[a.seat].cbets[i] += aggressive as u32;
+                }
+                bet |= aggressive;
+                if aggressive {
+                    stats[a.seat].aggressive += 1;
+                } else if a.action == Action::Call {
+                    stats[a.seat].calls += 1;
+                }
+            }
+            aggressor = last_aggressor(hand, street);
+        }
+
+        let live: Vec<usize> = (0..n)
+            .filter(|&s| !hand.seats[s].sitting_out)
+            .filter(|&s| {
+                !hand
+                    .actions
+                    .iter()
+                    .any(|a| a.seat == s && a.action == Action::Fold)
+            })
+            .collect();
+        let showdown = live.len() >= 2;
+        let all_in = all_in_ev(eval, hand)?;
+        let mut seats = Vec::with_capacity(n);
+        for (seat, mut s) in stats.into_iter().enumerate() {
+            let info = &hand.seats[seat];
+            if info.sitting_out {
+                continue;
+            }
+            s.hands = 1;
+            if hand.board.len() >= 3 && !folded_preflop(seat) {
+                s.saw_flop = 1;
+            }
+            if showdown && live.contains(&seat) {
+                s.showdowns = 1;
+                s.showdowns_won = hand.collected.iter().any(|c| c.seat == seat) as u32;
+            }
+            s.net = hand.in_bb(hand.net(seat));
+            s.ev_net = match &all_in {
+                Some(ev) => ev.expected_net(hand, seat) / hand.big_blind,
+                None => s.net,
+            };
+            seats.push(SeatRecord {
+                player: info.player.clone(),
+                position: info.position,
+                stack: hand.in_bb(info.stack),
+                stats: s,
+            });
+        }
+        Ok(HandRecord {
+            site: hand.site.clone(),
+            hand_id: hand.hand_id.clone(),
+            game_type: hand.game_type,
+            seats,
+        })
+    }
+}
+
+fn is_aggressive(action: Action) -> bool {
+    matches!(action, Action::Bet(_) | Action::Raise(_) | Action::AllIn(_))
+}
+
+fn last_aggressor(hand: &HandHistory, street: Street) -> Option<usize> {
+    hand.street_actions(street)
+        .filter(|a| is_aggressive(a.action))
+        .last()
+        .map(|a| a.seat)
+}
+
+/// Which seats count towards the stats. Empty lists allow everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatsFilter {
+    pub positions: Vec<Position>,
+    pub game_types: Vec<GameType>,
+    /// Starting stack bounds in big blinds, inclusive.
+    pub min_stack: Option<f64>,
+    pub max_stack: Option<f64>,
+}
+
+impl StatsFilter {
+    pub fn matches(&self, game_type: GameType, seat: &SeatRecord) -> bool {
+        let position =
+            self.positions.is_empty() || seat.position.is_some_and(|p| self.positions.contains(&p));
+        let game = self.game_types.is_empty() || self.game_types.contains(&game_type);
+        let deep_enough = self.min_stack.is_none_or(|min| seat.stack >= min);
+        let shallow_enough = self.max_stack.is_none_or(|max| seat.stack <= max);
+        position && game && deep_enough && shallow_enough
+    }
+}
+
+/// Per-player stats over a stream of hands.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    filter: StatsFilter,
+    players: BTreeMap<String, PlayerStats>,
+    /// `(site, hand id)` of every hand added, so re-imports do not count
+    /// a hand twice.
+    seen: HashSet<(String, String)>,
+}
+
+impl Stats {
+    pub fn new(filter: StatsFilter) -> Self {
+        Stats {
+            filter,
+            ..Stats::default()
+        }
+    }
+
+    pub fn filter(&self) -> &StatsFilter {
+        &self.filter
+    }
+
+    /// Add a hand. Returns whether it was new.
+    pub fn add_hand<E: Evaluator + ?Sized>(
+        &mut self,
+        eval: &E,
+        hand: &HandHistory,
+    ) -> Result<bool, EvalError> {
+        if self
+            .seen
+            .contains(&(hand.site.clone(), hand.hand_id.clone()))
+        {
+            return Ok(false);
+        }
+        Ok(self.add_record(&HandRecord::new(eval, hand)?))
+    }
+
+    /// Add a hand's record. Returns whether it was new.
+    pub fn add_record(&mut self, record: &HandRecord) -> bool {
+        if !self
+            .seen
+            .insert((record.site.clone(), record.hand_id.clone()))
+        {
+            return false;
+        }
+        for seat in &record.seats {
+            if self.filter.matches(record.game_type, seat) {
+                self.players
+                    .entry(seat.player.clone())
+                    .or_default()
+                    .merge(&seat.stats);
+            }
+        }
+        true
+    }
+
+    /// Distinct hands added, whether or not any seat passed the filter.
+    pub fn hand_count(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn player(&self, name: &str) -> Option<&PlayerStats> {
+        self.players.get(name)
+    }
+
+    /// Every player with at least one matching hand, by name.
+    pub fn players(&self) -> impl Iterator<Item = (&str, &PlayerStats)> {
+        self.players.iter().map(|(name, s)| (name.as_str(), s))
+    }
+}
+
+/// Records of one imported file, valid while its size and modification
+/// time are unchanged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CachedFile {
+    len: u64,
+    modified: u128,
+    records: Vec<HandRecord>,
+    /// Hands that failed to parse.
+    errors: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CacheContents {
+    version: u32,
+    files: BTreeMap<PathBuf, CachedFile>,
+}
+
+/// On-disk cache of hand records per hand history file, stored as JSON.
+#[derive(Debug, Clone)]
+pub struct StatsCache {
+    path: PathBuf,
+    contents: CacheContents,
+    dirty: bool,
+}
+
+/// Outcome of importing one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Import {
+    pub hands: usize,
+    /// Hands skipped because they failed to parse.
+    pub errors: usize,
+    /// Whether the records came from the cache.
+    pub cached: bool,
+}
+
+impl StatsCache {
+    /// Open a cache file. A missing, unreadable or outdated cache starts
+    /// empty; it is only a cache.
+    pub fn open(path: &Path) -> Self {
+        let contents = File::open(path)
+            .ok()
+            .and_then(|f| serde_json::from_reader::<_, CacheContents>(BufReader::new(f)).ok())
+            .filter(|c| c.version == CACHE_VERSION)
+            .unwrap_or(CacheContents {
+                version: CACHE_VERSION,
+                files: BTreeMap::new(),
+            });
+        StatsCache {
+            path: path.to_path_buf(),
+            contents,
+            dirty: false,
+        }
+    }
+
+    /// Import a hand history file into `stats`, parsing it only if it
+    /// changed since it was cached. Hands that fail to parse are skipped.
+    pub fn import<E: Evaluator + ?Sized>(
+        &mut self,
+        eval: &E,
+        file: &Path,
+        stats: &mut Stats,
+    ) -> Result<Import, EvalError> {
+        let meta = std::fs::metadata(file).map_err(io_error)?;
+        let len = meta.len();
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos());
+        let key = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+
+        let fresh = self
+            .contents
+            .files
+            .get(&key)
+            .is_some_and(|c| c.len == len && c.modified == modified);
+        if !fresh {
+            let reader = BufReader::new(File::open(file).map_err(io_error)?);
+            let mut records = Vec::new();
+            let mut errors = 0;
+            for hand in HandHistoryReader::new(reader) {
+                match hand {
+                    Ok(hand) => records.push(HandRecord::new(eval, &hand)?),
+                    Err(e) => {
+                        tracing::warn!(file = %file.display(), error = %e, "skipping hand");
+                        errors += 1;
+                    }
+                }
+            }
+            let cached = CachedFile {
+                len,
+                modified,
+                records,
+                errors,
+            };
+            self.contents.files.insert(key.clone(), cached);
+            self.dirty = true;
+        }
+
+        let cached = &self.contents.files[&key];
+        for record in &cached.records {
+            stats.add_record(record);
+        }
+        Ok(Import {
+            hands: cached.records.len(),
+            errors: cached.errors,
+            cached: fresh,
+        })
+    }
+
+    /// Write the cache back if anything changed.
+    pub fn save(&mut self) -> Result<(), EvalError> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let file = File::create(&self.path).map_err(io_error)?;
+        serde_json::to_writer(BufWriter::new(file), &self.contents)
+            .map_err(|e| EvalError::Io(e.to_string()))?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+fn io_error(e: std::io::Error) -> EvalError {
+    EvalError::Io(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup_table::LookupTableEvaluator;
+
+    const CASH: &str =
+        include_str!("../../poker-core/tests/fixtures/hand_history/pokerstars_cash.txt");
+    const SIDE_POT: &str =
+        include_str!("../../poker-core/tests/fixtures/hand_history/pokerstars_side_pot.txt");
+
+    fn hands() -> Vec<HandHistory> {
+        HandHistoryReader::new(CASH.as_bytes())
+            .collect::<Result<_, _>>()
+            .expect("valid hands")
+    }
+
+    #[test]
+    fn test_player_stats() {
+        let eval = LookupTableEvaluator::global();
+        let mut stats = Stats::new(StatsFilter::default());
+        for hand in &hands() {
+            assert!(stats.add_hand(eval, hand).expect("valid"));
+        }
+        assert!(!stats.add_hand(eval, &hands()[0]).expect("valid"));
+        assert_eq!(stats.hand_count(), 2);
+
+        let hero = stats.player("Hero").expect("Hero");
+        assert_eq!(hero.hands, 2);
+        assert_eq!(hero.vpip_percent(), Some(100.0));
+        assert_eq!(hero.pfr_percent(), Some(100.0));
+        assert_eq!((hero.three_bets, hero.three_bet_chances), (1, 1));
+        assert_eq!(hero.cbet_percent(Street::Flop), Some(100.0));
+        assert_eq!(hero.cbet_percent(Street::Turn), Some(100.0));
+        assert_eq!(hero.cbet_percent(Street::River), None);
+        assert_eq!(hero.wtsd_percent(), Some(100.0));
+        assert_eq!(hero.wsd_percent(), Some(100.0));
+        assert_eq!(hero.aggression_factor(), Some(2.0));
+        // +91.5bb and +1bb; the turn all-in was 42/44 to win 191.9bb
+        assert!((hero.net - 92.5).abs() < 1e-9);
+        let hero_invested = 100.4;
+        let expected = 191.9 * 42.0 / 44.0 - hero_invested + 1.0;
+        assert!((hero.ev_net - expected).abs() < 0.02);
+        assert!((hero.bb_per_100().expect("hands") - 4625.0).abs() < 1e-6);
+
+        let bob = stats.player("Bob").expect("Bob");
+        assert_eq!(bob.pfr, 1);
+        assert_eq!(bob.three_bet_chances, 0);
+        assert_eq!(bob.fold_to_three_bet_percent(), Some(0.0));
+        assert_eq!(bob.aggression_factor(), Some(1.0));
+        assert_eq!(bob.wsd_percent(), Some(0.0));
+
+        let frank = stats.player("Frank").expect("Frank");
+        assert_eq!(frank.vpip, 0);
+        assert_eq!(frank.three_bet_percent(), Some(0.0));
+        assert_eq!(frank.wtsd_percent(), None);
+        assert_eq!(frank.net, -1.0);
+
+        let eve = stats.player("Eve").expect("Eve");
+        assert_eq!((eve.three_bets, eve.three_bet_chances), (0, 1));
+        // Carol was sitting out
+        assert!(stats.player("Carol").is_none());
+    }
+
+    #[test]
+    fn test_side_pot_ev() {
+        let eval = LookupTableEvaluator::global();
+        let hand = HandHistory::parse(SIDE_POT).expect("valid hand");
+        let ev = all_in_ev(eval, &hand).expect("valid").expect("all-in");
+        assert_eq!(ev.pots.len(), 2);
+        let mut stats = Stats::new(StatsFilter::default());
+        assert!(stats.add_hand(eval, &hand).expect("valid"));
+
+        // Alice can only win the main pot; her expectation is not a share
+        // of the whole 250bb
+        let mut total = 0.0;
+        for player in ["Alice", "Bob", "Hero"] {
+            let seat = hand.seat_of(player).expect("seated");
+            let ev_net = stats.player(player).expect("player").ev_net;
+            assert_eq!(
+                ev_net,
+                ev.expected_net(&hand, seat) / hand.big_blind,
+                "{player}"
+            );
+            total += ev_net;
+        }
+        let alice = hand.seat_of("Alice").expect("Alice");
+        assert!(ev.expected[alice] <= 0.95 * 15.0);
+        // Everyone together loses the rake
+        assert!((total + 12.5).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_filters() {
+        let eval = LookupTableEvaluator::global();
+        let records: Vec<HandRecord> = hands()
+            .iter()
+            .map(|h| HandRecord::new(eval, h).expect("valid"))
+            .collect();
+
+        let mut blinds = Stats::new(StatsFilter {
+            positions: vec![Position::BB],
+            ..StatsFilter::default()
+        });
+        let mut mtt = Stats::new(StatsFilter {
+            game_types: vec![GameType::MTT],
+            ..StatsFilter::default()
+        });
+        let mut deep = Stats::new(StatsFilter {
+            min_stack: Some(110.0),
+            ..StatsFilter::default()
+        });
+        for record in &records {
+            blinds.add_record(record);
+            mtt.add_record(record);
+            deep.add_record(record);
+        }
+        assert_eq!(blinds.player("Hero").map(|s| s.hands), Some(1));
+        assert_eq!(blinds.player("Frank").map(|s| s.hands), Some(1));
+        assert!(blinds.player("Bob").is_none());
+        assert_eq!(mtt.players().count(), 0);
+        assert_eq!(mtt.hand_count(), 2);
+        // Hero had 120bb in the first hand and 100bb in the second
+        assert_eq!(deep.player("Hero").map(|s| s.hands), Some(1));
+        assert_eq!(deep.players().count(), 1);
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let eval = LookupTableEvaluator::global();
+        let dir = std::env::temp_dir().join(format!("stats-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("created");
+        let history = dir.join("hands.txt");
+        std::fs::write(&history, CASH).expect("written");
+        let cache_path = dir.join("cache").join("stats.json");
+
+        let mut cache = StatsCache::open(&cache_path);
+        let mut first = Stats::default();
+        let import = cache.import(eval, &history, &mut first).expect("imported");
+        assert_eq!((import.hands, import.errors, import.cached), (2, 0, false));
+        cache.save().expect("saved");
+
+        let mut cache = StatsCache::open(&cache_path);
+        let mut second = Stats::default();
+        let import = cache.import(eval, &history, &mut second).expect("imported");
+        assert!(import.cached);
+        assert_eq!(second.player("Hero"), first.player("Hero"));
+
+        // A changed file is parsed again
+        std::fs::write(
+            &history,
+            &CASH[..CASH.find("PokerStars Hand #243920174401").expect("second")],
+        )
+        .expect("written");
+        let mut third = Stats::default();
+        let import = cache.import(eval, &history, &mut third).expect("imported");
+        assert_eq!((import.hands, import.cached), (1, false));
+
+        std::fs::remove_dir_all(&dir).expect("removed");
+    }
+}
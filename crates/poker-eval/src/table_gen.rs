@@ -125,9 +125,8 @@ pub fn generate_unique5_table() -> HashMap<u32, u16> {
                 if k2 == trips {
                     continue;
                 }
-                let prod = PRIMES[trips as usize].pow(3)
-                    * PRIMES[k1 as usize]
-                    * PRIMES[k2 as usize];
+                let prod =
+                    PRIMES[trips as usize].pow(3) * PRIMES[k1 as usize] * PRIMES[k2 as usize];
                 table.insert(prod, rank_val);
                 rank_val += 1;
             }
@@ -272,6 +271,187 @@ pub fn unique5_entries(table: &HashMap<u32, u16>) -> Vec<(u32, u16)> {
     table.iter().map(|(&k, &v)| (k, v)).collect()
 }
 
+/// Number of 7-card rank multisets (each rank used at most four times).
+pub const NOFLUSH7_SIZE: usize = 49205;
+
+/// Offsets for the quinary perfect hash of a 7-card rank multiset.
+///
+/// `QUINARY_OFFSETS[i][k][q]` is the number of multisets that sort before one
+/// holding `q` cards of rank `i` when `k` cards remain to be placed in ranks
+/// `i..13`. Summing the entries along a hand's rank counts yields a dense
+/// index in `0..NOFLUSH7_SIZE`.
+pub const QUINARY_OFFSETS: [[[u16; 5]; 8]; 13] = quinary_offsets();
+
+const fn quinary_offsets() -> [[[u16; 5]; 8]; 13] {
+    // ways[l][k]: count vectors of length l with entries 0..=4 summing to k.
+    let mut ways = [[0u32; 8]; 14];
+    ways[0][0] = 1;
+    let mut l = 1;
+    while l <= 13 {
+        let mut k = 0;
+        while k < 8 {
+            let mut c = 0;
+            while c <= 4 && c <= k {
+                ways[l][k] += ways[l - 1][k - c];
+                c += 1;
+            }
+            k += 1;
+        }
+        l += 1;
+    }
+
+    let mut offsets = [[[0u16; 5]; 8]; 13];
+    let mut i = 0;
+    while i < 13 {
+        let rest = 12 - i;
+        let mut k = 0;
+        while k < 8 {
+            let mut q = 1;
+            while q < 5 {
+                let prev = offsets[i][k][q - 1] as u32;
+                let add = if q - 1 <= k {
+                    ways[rest][k - (q - 1)]
+                } else {
+                    0
+                };
+                offsets[i][k][q] = (prev + add) as u16;
+                q += 1;
+            }
+            k += 1;
+        }
+        i += 1;
+    }
+    offsets
+}
+
+/// Dense index of a 7-card rank multiset given per-rank counts.
+#[inline(always)]
+pub fn quinary_index(counts: &[u8; 13]) -> usize {
+    let mut index = 0usize;
+    let mut left = 7usize;
+    for (i, &q) in counts.iter().enumerate() {
+        index += QUINARY_OFFSETS[i][left][q as usize] as usize;
+        left -= q as usize;
+    }
+    index
+}
+
+/// Best flush or straight flush for every suited rank mask with 5 to 7 bits.
+/// Index: 13-bit rank mask of the cards in the flush suit. Zero elsewhere.
+pub fn generate_flush7_table(flush_table: &[u16]) -> Vec<u16> {
+    let mut table = vec![0u16; 8192];
+    for mask in 0..8192u16 {
+        if mask.count_ones() < 5 {
+            continue;
+        }
+        let mut best = u16::MAX;
+        // Walk every submask, keeping the 5-bit ones.
+        let mut sub = mask;
+        while sub != 0 {
+            if sub.count_ones() == 5 {
+                best = best.min(flush_table[sub as usize]);
+            }
+            sub = (sub - 1) & mask;
+        }
+        table[mask as usize] = best;
+    }
+    table
+}
+
+/// Best 5-card rank for every 7-card rank multiset, ignoring flushes.
+/// Index: `quinary_index` of the rank counts.
+pub fn generate_noflush7_table(flush_table: &[u16], unique5_table: &HashMap<u32, u16>) -> Vec<u16> {
+    fn fill(
+        counts: &mut [u8; 13],
+        rank: usize,
+        left: u8,
+        flush_table: &[u16],
+        unique5_table: &HashMap<u32, u16>,
+        table: &mut [u16],
+    ) {
+        if rank == 13 {
+            if left == 0 {
+                // Deal suits round-robin so no suit holds more than two cards.
+                let mut cards = [0u8; 7];
+                let mut n = 0;
+                for (r, &q) in counts.iter().enumerate() {
+                    for _ in 0..q {
+                        cards[n] = (r as u8) * 4 + (n % 4) as u8;
+                        n += 1;
+                    }
+                }
+                let mut best = u16::MAX;
+                for skip_a in 0..7 {
+                    for skip_b in (skip_a + 1)..7 {
+                        let mut five = [0u8; 5];
+                        let mut j = 0;
+                        for (i, &c) in cards.iter().enumerate() {
+                            if i != skip_a && i != skip_b {
+                                five[j] = c;
+                                j += 1;
+                            }
+                        }
+                        let rank = evaluate_5cards(
+                            flush_table,
+                            unique5_table,
+                            five[0],
+                            five[1],
+                            five[2],
+                            five[3],
+                            five[4],
+                        );
+                        best = best.min(rank.0);
+                    }
+                }
+                table[quinary_index(counts)] = best;
+            }
+            return;
+        }
+        for q in 0..=left.min(4) {
+            counts[rank] = q;
+            fill(
+                counts,
+                rank + 1,
+                left - q,
+                flush_table,
+                unique5_table,
+                table,
+            );
+        }
+        counts[rank] = 0;
+    }
+
+    let mut table = vec![0u16; NOFLUSH7_SIZE];
+    fill(&mut [0u8; 13], 0, 7, flush_table, unique5_table, &mut table);
+    table
+}
+
+/// FNV-1a checksum over the flush table and the FastLookup slot arrays.
+/// Computed by the build script when embedding the tables and re-checked at
+/// runtime before the embedded copy is trusted.
+pub fn table_checksum(flush_table: &[u16], keys: &[u32], values: &[u16]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for &b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+    for &v in flush_table {
+        feed(&v.to_le_bytes());
+    }
+    for &k in keys {
+        feed(&k.to_le_bytes());
+    }
+    for &v in values {
+        feed(&v.to_le_bytes());
+    }
+    hash
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,4 +497,28 @@ mod tests {
         // Total: 1287 + 6175 = 7462
         assert_eq!(flush_count + unique5_count, 7462);
     }
+
+    #[test]
+    fn test_quinary_index_is_dense() {
+        let table = generate_noflush7_table(&generate_flush_table(), &generate_unique5_table());
+        // Every multiset maps to its own slot, so no slot is left at zero.
+        assert!(table.iter().all(|&v| v > 0 && v <= 7462));
+        // Four aces with a king and queen: best is quad aces, king kicker.
+        let mut counts = [0u8; 13];
+        counts[12] = 4;
+        counts[11] = 2;
+        counts[10] = 1;
+        assert_eq!(table[quinary_index(&counts)], 11);
+    }
+
+    #[test]
+    fn test_flush7_table() {
+        let flush7 = generate_flush7_table(&generate_flush_table());
+        // A-K-Q-J-T-9-8 suited: royal flush.
+        assert_eq!(flush7[0b1_1111_1100_0000], 1);
+        // A-5-4-3-2 plus 7-6: the 7-high straight flush beats the wheel.
+        let mask = 0b1_0000_0011_1111u16;
+        assert_eq!(flush7[mask as usize], 8);
+        assert_eq!(flush7[0b1_1111], 9);
+    }
 }
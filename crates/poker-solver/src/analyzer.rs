@@ -0,0 +1,992 @@
+//! Hand history analysis against solved spots.
+//!
+//! Each hand is matched to the solution in a [`SpotLibrary`] with the same
+//! starting board whose pot and effective stack, in big blinds, are closest
+//! to the hand's. Both players' actions are then followed down the betting
+//! tree; bet sizes the tree does not offer are mapped to a neighbouring size
+//! with the pseudo-harmonic action translation of Ganzfried & Sandholm
+//! (2013). Every hero decision is scored by the EV it gives up against the
+//! best action at that node, for hero's exact combo.
+//!
+//! Boards are matched exactly, with the flop cards in any order; suit
+//! isomorphic boards need their own solutions.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use poker_core::game_tree::{GameTree, NodeId, NodeKind, TreeNode};
+use poker_core::{Action, Card, Chips, ComboRange, Hand, HandHistory, Street};
+use poker_eval::Evaluator;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolverError;
+use crate::solution::SolutionReader;
+
+/// EV losses, in big blinds, from which a decision is graded worse than
+/// perfect.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Thresholds {
+    pub inaccuracy: f64,
+    pub mistake: f64,
+    pub blunder: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            inaccuracy: 0.1,
+            mistake: 0.5,
+            blunder: 2.0,
+        }
+    }
+}
+
+/// Grade of a decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Classification {
+    Perfect,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Classification {
+    pub const ALL: [Classification; 4] = [
+        Classification::Perfect,
+        Classification::Inaccuracy,
+        Classification::Mistake,
+        Classification::Blunder,
+    ];
+
+    /// Grade an EV loss in big blinds.
+    pub fn from_ev_loss(ev_loss: f64, thresholds: &Thresholds) -> Self {
+        if ev_loss >= thresholds.blunder {
+            Classification::Blunder
+        } else if ev_loss >= thresholds.mistake {
+            Classification::Mistake
+        } else if ev_loss >= thresholds.inaccuracy {
+            Classification::Inaccuracy
+        } else {
+            Classification::Perfect
+        }
+    }
+}
+
+/// Analyzer settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyzerConfig {
+    pub thresholds: Thresholds,
+    /// Largest factor by which a spot's starting pot or effective stack may
+    /// differ from the hand's for the spot to be used.
+    pub max_size_ratio: f64,
+}
+
+impl Default for AnalyzerConfig {
+    fn default() -> Self {
+        AnalyzerConfig {
+            thresholds: Thresholds::default(),
+            max_size_ratio: 2.0,
+        }
+    }
+}
+
+/// Solved spots to analyze hands against.
+pub struct SpotLibrary<R> {
+    spots: Vec<SolutionReader<R>>,
+}
+
+impl<R> SpotLibrary<R> {
+    pub fn new() -> Self {
+        SpotLibrary { spots: Vec::new() }
+    }
+
+    /// Add a spot. Spots are numbered in the order they are added.
+    pub fn add(&mut self, spot: SolutionReader<R>) {
+        self.spots.push(spot);
+    }
+
+    pub fn spots(&self) -> &[SolutionReader<R>] {
+        &self.spots
+    }
+
+    pub fn len(&self) -> usize {
+        self.spots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spots.is_empty()
+    }
+}
+
+impl<R> Default for SpotLibrary<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpotLibrary<BufReader<File>> {
+    /// Open every `.pks` file in a directory, in file name order.
+    pub fn open_dir(dir: &Path) -> Result<Self, SolverError> {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "pks") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        let mut library = SpotLibrary::new();
+        for path in paths {
+            library.add(SolutionReader::open(&path)?);
+        }
+        Ok(library)
+    }
+}
+
+/// One action available at a decision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionEv {
+    /// The tree action, with amounts in big blinds.
+    pub action: Action,
+    /// How often the solution takes it with hero's combo.
+    pub frequency: f64,
+    /// Hero's EV in big blinds after taking it, counting hero's share of
+    /// the pot at the start of the spot.
+    pub ev: f64,
+}
+
+/// One of hero's decisions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub street: Street,
+    /// The tree node the decision was mapped to.
+    pub node: NodeId,
+    /// The action as played, in big blinds.
+    pub played: Action,
+    /// Index into `options` of the tree action `played` was mapped to.
+    pub chosen: usize,
+    pub options: Vec<ActionEv>,
+    /// How often the solution takes the chosen action with hero's combo.
+    pub frequency: f64,
+    /// EV of the best action minus EV of the chosen one, in big blinds.
+    pub ev_loss: f64,
+    pub classification: Classification,
+}
+
+/// Analysis of one hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandReport {
+    pub hand_id: String,
+    /// Index of the matched spot in the library.
+    pub spot: Option<usize>,
+    pub decisions: Vec<Decision>,
+    /// Why the analysis ended before the hand did, if it did.
+    pub stopped: Option<String>,
+}
+
+impl HandReport {
+    /// Total EV loss over the hand's decisions, in big blinds.
+    pub fn ev_loss(&self) -> f64 {
+        self.decisions.iter().map(|d| d.ev_loss).sum()
+    }
+
+    fn stopped(hand: &HandHistory, reason: impl Into<String>) -> Self {
+        HandReport {
+            hand_id: hand.hand_id.clone(),
+            spot: None,
+            decisions: Vec::new(),
+            stopped: Some(reason.into()),
+        }
+    }
+}
+
+/// Analysis of a session of hands.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionReport {
+    pub hands: Vec<HandReport>,
+}
+
+impl SessionReport {
+    /// Hands with at least one analyzed decision.
+    pub fn analyzed_hands(&self) -> usize {
+        self.hands
+            .iter()
+            .filter(|h| !h.decisions.is_empty())
+            .count()
+    }
+
+    pub fn decisions(&self) -> impl Iterator<Item = &Decision> {
+        self.hands.iter().flat_map(|h| &h.decisions)
+    }
+
+    /// Number of decisions with a given grade.
+    pub fn count(&self, classification: Classification) -> usize {
+        self.decisions()
+            .filter(|d| d.classification == classification)
+            .count()
+    }
+
+    /// Total EV loss in big blinds.
+    pub fn ev_loss(&self) -> f64 {
+        self.hands.iter().map(HandReport::ev_loss).sum()
+    }
+
+    /// EV loss in big blinds per 100 analyzed hands.
+    pub fn ev_loss_per_100(&self) -> f64 {
+        match self.analyzed_hands() {
+            0 => 0.0,
+            n => self.ev_loss() / n as f64 * 100.0,
+        }
+    }
+}
+
+/// Scores hero's decisions in hand histories against a library of spots.
+pub struct Analyzer<'a, R> {
+    library: SpotLibrary<R>,
+    evaluator: &'a dyn Evaluator,
+    config: AnalyzerConfig,
+}
+
+impl<'a, R: Read + Seek> Analyzer<'a, R> {
+    pub fn new(
+        library: SpotLibrary<R>,
+        evaluator: &'a dyn Evaluator,
+        config: AnalyzerConfig,
+    ) -> Self {
+        Analyzer {
+            library,
+            evaluator,
+            config,
+        }
+    }
+
+    pub fn library(&self) -> &SpotLibrary<R> {
+        &self.library
+    }
+
+    pub fn config(&self) -> &AnalyzerConfig {
+        &self.config
+    }
+
+    /// Analyze every hand. Hands that cannot be matched to a spot are
+    /// reported with the reason and no decisions.
+    pub fn analyze_session<'h>(
+        &mut self,
+        hands: impl IntoIterator<Item = &'h HandHistory>,
+    ) -> Result<SessionReport, SolverError> {
+        let hands = hands
+            .into_iter()
+            .map(|hand| self.analyze_hand(hand))
+            .collect::<Result<_, _>>()?;
+        Ok(SessionReport { hands })
+    }
+
+    /// Analyze hero's decisions in one hand. Errors are only returned for
+    /// unreadable solution files.
+    pub fn analyze_hand(&mut self, hand: &HandHistory) -> Result<HandReport, SolverError> {
+        let Some(hero) = hand.hero else {
+            return Ok(HandReport::stopped(hand, "hand has no hero"));
+        };
+        let Some(cards) = hand.seats[hero].cards else {
+            return Ok(HandReport::stopped(hand, "hero's cards are unknown"));
+        };
+        let (spot, start) = match self.find_spot(hand, hero, cards) {
+            Ok(found) => found,
+            Err(reason) => return Ok(HandReport::stopped(hand, reason)),
+        };
+        let mut report = HandReport {
+            hand_id: hand.hand_id.clone(),
+            spot: Some(spot),
+            decisions: Vec::new(),
+            stopped: None,
+        };
+        self.walk(hand, &mut report, spot, &start, cards)?;
+        Ok(report)
+    }
+
+    /// The closest spot for a hand, with the hand's state where it starts.
+    fn find_spot(
+        &self,
+        hand: &HandHistory,
+        hero: usize,
+        cards: Hand,
+    ) -> Result<(usize, Start), String> {
+        let board = hand.board.cards();
+        let mut best: Option<(f64, usize, Start)> = None;
+        let mut reason = "no spot with this board".to_string();
+        for (i, spot) in self.library.spots.iter().enumerate() {
+            let header = spot.header();
+            let n = header.board.len();
+            if board.len() < n || mask(&board[..n]) != mask(&header.board) {
+                continue;
+            }
+            let config = &header.tree_config;
+            if !config.big_blind.is_positive() {
+                continue;
+            }
+            let start = match Start::new(hand, config.starting_street) {
+                Ok(start) => start,
+                Err(e) => {
+                    reason = e;
+                    continue;
+                }
+            };
+            let Some(role) = start.players.iter().position(|&s| s == hero) else {
+                reason = format!("hero folded before the {:?}", config.starting_street);
+                continue;
+            };
+            if !header.hands[role].contains(&cards) {
+                reason = format!("{cards} is not in the solved range");
+                continue;
+            }
+            let pot = spread(config.starting_pot.ratio(config.big_blind), start.pot);
+            let stack = spread(config.effective_stack.ratio(config.big_blind), start.stack);
+            if pot.max(stack) > self.config.max_size_ratio.ln() {
+                reason = "no spot with a similar pot and stack".to_string();
+                continue;
+            }
+            if best.as_ref().is_none_or(|(d, _, _)| pot + stack < *d) {
+                best = Some((pot + stack, i, start));
+            }
+        }
+        best.map(|(_, i, start)| (i, start)).ok_or(reason)
+    }
+
+    /// Follow the hand down the spot's tree, scoring hero's decisions.
+    fn walk(
+        &mut self,
+        hand: &HandHistory,
+        report: &mut HandReport,
+        spot: usize,
+        start: &Start,
+        cards: Hand,
+    ) -> Result<(), SolverError> {
+        let reader = &mut self.library.spots[spot];
+        let tree = reader.tree().clone();
+        let header = reader.header().clone();
+        let hero = hand.hero.unwrap_or_default();
+        let role = start.players.iter().position(|&s| s == hero).unwrap_or(0);
+        let opp = 1 - role;
+        let bb = tree.config.big_blind.to_f64();
+
+        // The opponent's combos, weighted as they reach the current node
+        let combos = ComboRange::from_range(&header.ranges[opp]);
+        let mut reach: Vec<f32> = header.hands[opp]
+            .iter()
+            .map(|&h| {
+                if h.mask() & cards.mask() == 0 {
+                    combos.weight(h)
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let mut spot = Spot {
+            reader,
+            tree,
+            board: header.board.clone(),
+            opp_hands: header.hands[opp].clone(),
+            evaluator: self.evaluator,
+            hero: role,
+            hand: cards,
+            index: header.hands[role]
+                .iter()
+                .position(|&h| h == cards)
+                .unwrap_or_default(),
+        };
+
+        let board = hand.board.cards();
+        let mut runout = Vec::new();
+        let mut id = 0;
+        let mut real = Betting {
+            street: start.street,
+            pot: start.pot,
+            bets: [0.0; 2],
+        };
+        let from = street_index(start.street);
+        for act in hand
+            .actions
+            .iter()
+            .filter(|a| street_index(a.street) >= from)
+        {
+            let Some(actor) = start.players.iter().position(|&s| s == act.seat) else {
+                continue;
+            };
+            if act.street != real.street {
+                real.street = act.street;
+                real.bets = [0.0; 2];
+            }
+            // Deal the hand's turn and river at chance nodes
+            while spot.tree.node(id).kind == NodeKind::Chance {
+                let Some(&card) = board.get(spot.board.len() + runout.len()) else {
+                    report.stopped = Some("the board is incomplete".to_string());
+                    return Ok(());
+                };
+                runout.push(card);
+                deal(&spot.opp_hands, &mut reach, card);
+                id = spot.tree.node(id).children[0];
+            }
+            let node = spot.tree.node(id).clone();
+            let name = &hand.seats[act.seat].player;
+            if node.street != act.street || node.player() != Some(actor) {
+                report.stopped = Some(format!(
+                    "{name}'s {} on the {:?} is not in the tree",
+                    act.action, act.street
+                ));
+                return Ok(());
+            }
+            let amount = hand.in_bb(act.amount);
+            let Some(chosen) = translate(&node, act.action, real.fraction(actor, amount)) else {
+                report.stopped = Some(format!(
+                    "no tree action for {name}'s {} on the {:?}",
+                    act.action, act.street
+                ));
+                return Ok(());
+            };
+
+            let data = spot.reader.node(id, &runout)?.ok_or_else(|| missing(id))?;
+            if actor == role {
+                let n = header.hands[role].len();
+                let mut options = Vec::with_capacity(node.children.len());
+                for (a, &child) in node.children.iter().enumerate() {
+                    let ev = spot.value(child, &mut runout, &reach)?;
+                    options.push(ActionEv {
+                        action: in_big_blinds(node.actions[a], bb),
+                        frequency: data.strategy[a * n + spot.index] as f64,
+                        ev: ev / bb,
+                    });
+                }
+                let best = options.iter().map(|o| o.ev).fold(f64::MIN, f64::max);
+                let ev_loss = (best - options[chosen].ev).max(0.0);
+                report.decisions.push(Decision {
+                    street: act.street,
+                    node: id,
+                    played: in_big_blinds(act.action, hand.big_blind),
+                    chosen,
+                    frequency: options[chosen].frequency,
+                    ev_loss,
+                    classification: Classification::from_ev_loss(ev_loss, &self.config.thresholds),
+                    options,
+                });
+            } else {
+                let m = reach.len();
+                for (o, r) in reach.iter_mut().enumerate() {
+                    *r *= data.strategy[chosen * m + o];
+                }
+            }
+            real.apply(actor, amount);
+            id = node.children[chosen];
+        }
+        Ok(())
+    }
+}
+
+/// The hand at the start of a spot's street, in big blinds.
+#[derive(Debug, Clone)]
+struct Start {
+    street: Street,
+    pot: f64,
+    stack: f64,
+    /// Seats of the out-of-position and in-position players.
+    players: [usize; 2],
+}
+
+impl Start {
+    fn new(hand: &HandHistory, street: Street) -> Result<Self, String> {
+        let from = street_index(street);
+        let before = || {
+            hand.actions
+                .iter()
+                .filter(move |a| street_index(a.street) < from)
+        };
+        let live: Vec<usize> = (0..hand.seats.len())
+            .filter(|&s| !hand.seats[s].sitting_out)
+            .filter(|&s| !before().any(|a| a.seat == s && a.action == Action::Fold))
+            .collect();
+        if live.len() != 2 {
+            return Err(format!("{} players saw the {street:?}", live.len()));
+        }
+        let first = hand
+            .actions
+            .iter()
+            .find(|a| street_index(a.street) >= from && live.contains(&a.seat))
+            .ok_or_else(|| format!("no action from the {street:?} on"))?;
+        let players = if first.seat == live[0] {
+            [live[0], live[1]]
+        } else {
+            [live[1], live[0]]
+        };
+
+        let posted = |seat: usize| -> Chips {
+            let posts: Chips = hand
+                .posts
+                .iter()
+                .filter(|p| p.seat == seat)
+                .map(|p| p.amount)
+                .sum();
+            let acted: Chips = before().filter(|a| a.seat == seat).map(|a| a.amount).sum();
+            posts + acted
+        };
+        let pot: Chips = (0..hand.seats.len()).map(posted).sum();
+        let stack = players
+            .iter()
+            .map(|&s| hand.in_bb(hand.seats[s].stack - posted(s)))
+            .fold(f64::MAX, f64::min);
+        Ok(Start {
+            street,
+            pot: hand.in_bb(pot),
+            stack,
+            players,
+        })
+    }
+}
+
+/// Betting on the hand's current street, in big blinds.
+struct Betting {
+    street: Street,
+    /// Including bets on this street.
+    pot: f64,
+    bets: [f64; 2],
+}
+
+impl Betting {
+    /// Size of a bet or raise adding `amount`, as a fraction of the pot after
+    /// calling.
+    fn fraction(&self, player: usize, amount: f64) -> f64 {
+        let facing = self.bets[1 - player];
+        let committed = self.bets[player];
+        (committed + amount - facing) / (self.pot + facing - committed)
+    }
+
+    fn apply(&mut self, player: usize, amount: f64) {
+        self.bets[player] += amount;
+        self.pot += amount;
+    }
+}
+
+/// A spot's tree and data, for valuing hero's options with one combo.
+struct Spot<'s, R> {
+    reader: &'s mut SolutionReader<R>,
+    tree: GameTree,
+    board: Vec<Card>,
+    opp_hands: Vec<Hand>,
+    evaluator: &'s dyn Evaluator,
+    /// Hero's player in the tree.
+    hero: usize,
+    hand: Hand,
+    /// Index of `hand` in hero's stored combos.
+    index: usize,
+}
+
+impl<R: Read + Seek> Spot<'_, R> {
+    /// Hero's EV in chips at a node, with the opponent's combos reaching it
+    /// as weighted by `reach`.
+    fn value(
+        &mut self,
+        id: NodeId,
+        runout: &mut Vec<Card>,
+        reach: &[f32],
+    ) -> Result<f64, SolverError> {
+        let node = self.tree.node(id);
+        let invested = (self.tree.config.effective_stack - node.stacks[self.hero]).to_f64();
+        let pot = node.pot.to_f64();
+        match node.kind {
+            NodeKind::Player(_) => {
+                let data = self.reader.node(id, runout)?.ok_or_else(|| missing(id))?;
+                Ok(data.ev[self.hero][self.index] as f64)
+            }
+            NodeKind::Fold { winner } if winner == self.hero => Ok(pot - invested),
+            NodeKind::Fold { .. } => Ok(-invested),
+            NodeKind::Showdown => Ok(self.showdown(runout, reach, pot, invested)),
+            NodeKind::Chance => {
+                let child = node.children[0];
+                let dead = mask(&self.board) | mask(runout) | self.hand.mask();
+                let (mut total, mut mass) = (0.0, 0.0);
+                for card in (0..52u8).filter_map(|i| Card::from_u8(i).ok()) {
+                    if card.mask() & dead != 0 {
+                        continue;
+                    }
+                    let mut dealt = reach.to_vec();
+                    deal(&self.opp_hands, &mut dealt, card);
+                    let weight: f64 = dealt.iter().map(|&r| r as f64).sum();
+                    if weight == 0.0 {
+                        continue;
+                    }
+                    runout.push(card);
+                    let value = self.value(child, runout, &dealt);
+                    runout.pop();
+                    total += weight * value?;
+                    mass += weight;
+                }
+                Ok(if mass > 0.0 { total / mass } else { 0.0 })
+            }
+        }
+    }
+
+    fn showdown(&self, runout: &[Card], reach: &[f32], pot: f64, invested: f64) -> f64 {
+        let mut cards = [self.hand.card1(); 7];
+        cards[1] = self.hand.card2();
+        for (slot, &card) in cards[2..].iter_mut().zip(self.board.iter().chain(runout)) {
+            *slot = card;
+        }
+        let hero = self.evaluator.evaluate_7(&cards);
+        let dead = mask(runout);
+        let (mut total, mut mass) = (0.0, 0.0);
+        for (hand, &r) in self.opp_hands.iter().zip(reach) {
+            if r == 0.0 || hand.mask() & dead != 0 {
+                continue;
+            }
+            cards[0] = hand.card1();
+            cards[1] = hand.card2();
+            let share = match hero.cmp(&self.evaluator.evaluate_7(&cards)) {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Equal => 0.5,
+                std::cmp::Ordering::Less => 0.0,
+            };
+            total += r as f64 * share;
+            mass += r as f64;
+        }
+        if mass > 0.0 {
+            pot * total / mass - invested
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Map a played action to one of a tree node's actions. Bets and raises use
+/// `fraction`, their size as a fraction of the pot after calling.
+fn translate(node: &TreeNode, played: Action, fraction: f64) -> Option<usize> {
+    let position = |want: &[Action]| node.actions.iter().position(|a| want.contains(a));
+    match played {
+        Action::Fold => position(&[Action::Fold]),
+        Action::Check | Action::Call => position(&[Action::Check, Action::Call]),
+        // An all-in for no more than a call is a call
+        _ if fraction <= 0.0 => position(&[Action::Check, Action::Call]),
+        Action::Bet(_) | Action::Raise(_) | Action::AllIn(_) => {
+            let p = node.player()?;
+            let facing = node.street_bets[1 - p];
+            let after_call = node.pot + facing - node.street_bets[p];
+            let sizes: Vec<(usize, f64)> = node
+                .actions
+                .iter()
+                .enumerate()
+                .filter_map(|(i, a)| match a {
+                    Action::Bet(t) | Action::Raise(t) | Action::AllIn(t) => {
+                        Some((i, (*t - facing).ratio(after_call)))
+                    }
+                    _ => None,
+                })
+                .collect();
+            nearest_size(&sizes, fraction)
+        }
+    }
+}
+
+/// Pseudo-harmonic mapping of a pot fraction `x` onto sorted `(index,
+/// fraction)` sizes: between sizes `a < b`, `x` maps to `a` when
+/// `(b - x)(1 + a) / ((b - a)(1 + x))` is at least one half.
+fn nearest_size(sizes: &[(usize, f64)], x: f64) -> Option<usize> {
+    let (first, last) = (sizes.first()?, sizes.last()?);
+    if x <= first.1 {
+        return Some(first.0);
+    }
+    if x >= last.1 {
+        return Some(last.0);
+    }
+    let pair = sizes.windows(2).find(|w| x <= w[1].1)?;
+    let (a, b) = (pair[0].1, pair[1].1);
+    let to_smaller = (b - x) * (1.0 + a) / ((b - a) * (1.0 + x));
+    Some(if to_smaller >= 0.5 {
+        pair[0].0
+    } else {
+        pair[1].0
+    })
+}
+
+/// Zero the reach of combos holding a dealt card.
+fn deal(hands: &[Hand], reach: &mut [f32], card: Card) {
+    for (hand, r) in hands.iter().zip(reach) {
+        if hand.mask() & card.mask() != 0 {
+            *r = 0.0;
+        }
+    }
+}
+
+fn mask(cards: &[Card]) -> u64 {
+    cards.iter().fold(0, |m, c| m | c.mask())
+}
+
+fn missing(id: NodeId) -> SolverError {
+    SolverError::Corrupt(format!("no data for node {id}"))
+}
+
+/// Distance between two positive sizes, as the log of their ratio.
+fn spread(a: f64, b: f64) -> f64 {
+    if a == b {
+        0.0
+    } else if a <= 0.0 || b <= 0.0 {
+        f64::INFINITY
+    } else {
+        (a / b).ln().abs()
+    }
+}
+
+fn street_index(street: Street) -> usize {
+    match street {
+        Street::Preflop => 0,
+        Street::Flop => 1,
+        Street::Turn => 2,
+        Street::River => 3,
+    }
+}
+
+fn in_big_blinds(action: Action, bb: f64) -> Action {
+    let scale = |t: Chips| Chips::from_f64(t.to_f64() / bb);
+    match action {
+        Action::Bet(t) => Action::Bet(scale(t)),
+        Action::Raise(t) => Action::Raise(scale(t)),
+        Action::AllIn(t) => Action::AllIn(scale(t)),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::write_solution;
+    use crate::solver::{Solver, SolverConfig};
+    use poker_core::game_tree::{StreetConfig, TreeConfig};
+    use poker_core::{BetSize, Board, Range};
+    use poker_eval::LookupTableEvaluator;
+    use std::io::Cursor;
+
+    const HAND: &str = "\
+PokerStars Hand #1001:  Hold'em No Limit ($0.50/$1 USD) - 2023/03/12 14:31:07 ET
+Table 'Test' 2-max Seat #1 is the button
+Seat 1: Villain ($50 in chips)
+Seat 2: Hero ($50 in chips)
+Villain: posts small blind $0.50
+Hero: posts big blind $1
+*** HOLE CARDS ***
+Dealt to Hero [7s 7h]
+Villain: raises $2 to $3
+Hero: calls $2
+*** FLOP *** [Ah Kd 7c]
+Hero: checks
+Villain: bets $2
+Hero: calls $2
+*** TURN *** [Ah Kd 7c] [4s]
+Hero: checks
+Villain: bets $6
+Hero: calls $6
+*** RIVER *** [Ah Kd 7c 4s] [2c]
+Hero: checks
+Villain: bets $10
+Hero: calls $10
+*** SHOW DOWN ***
+Hero: shows [7s 7h] (three of a kind, Sevens)
+Villain: shows [Kh Qh] (a pair of Kings)
+Hero collected $42 from pot
+*** SUMMARY ***
+Total pot $42 | Rake $0
+Board [Ah Kd 7c 4s 2c]
+Seat 1: Villain (button) (small blind) showed [Kh Qh] and lost with a pair of Kings
+Seat 2: Hero (big blind) showed [7s 7h] and won ($42) with three of a kind, Sevens
+";
+
+    fn card(s: &str) -> Card {
+        Card::from_str_notation(s).expect("valid card")
+    }
+
+    /// A turn spot after a 10bb flop pot with 40bb behind.
+    fn turn_spot(eval: &LookupTableEvaluator) -> SolutionReader<Cursor<Vec<u8>>> {
+        let sizes = StreetConfig {
+            bet_sizes: vec![BetSize::PotFraction(0.5), BetSize::PotFraction(1.0)],
+            raise_sizes: Vec::new(),
+            raise_cap: 0,
+        };
+        let tree = TreeConfig {
+            starting_pot: Chips::from_units(10),
+            effective_stack: Chips::from_units(40),
+            big_blind: Chips::from_units(1),
+            starting_street: Street::Turn,
+            flop: sizes.clone(),
+            turn: sizes.clone(),
+            river: sizes,
+            all_in_threshold: 1.0,
+            force_all_in_spr: 0.0,
+        };
+        let cards: Vec<Card> = ["Kd", "Ah", "7c", "4s"].iter().map(|c| card(c)).collect();
+        let board = Board::from_cards(&cards).expect("valid board");
+        let oop: Range = "77,A5s,98s".parse().expect("valid");
+        let ip: Range = "KQs,44".parse().expect("valid");
+        let config = SolverConfig {
+            iterations: 50,
+            threads: 1,
+            ..SolverConfig::default()
+        };
+        let mut solver = Solver::new(eval, [&oop, &ip], &board, &tree, config).expect("valid");
+        solver.solve();
+        let mut buf = Cursor::new(Vec::new());
+        write_solution(&solver, &mut buf).expect("written");
+        SolutionReader::new(Cursor::new(buf.into_inner())).expect("valid file")
+    }
+
+    fn analyzer(eval: &LookupTableEvaluator) -> Analyzer<'_, Cursor<Vec<u8>>> {
+        let mut library = SpotLibrary::new();
+        library.add(turn_spot(eval));
+        Analyzer::new(library, eval, AnalyzerConfig::default())
+    }
+
+    #[test]
+    fn test_analyze_hand() {
+        let eval = LookupTableEvaluator::global();
+        let mut analyzer = analyzer(eval);
+        let hand = HandHistory::parse(HAND).expect("valid hand");
+        let report = analyzer.analyze_hand(&hand).expect("readable");
+        assert_eq!(report.spot, Some(0));
+        assert_eq!(report.stopped, None);
+        let streets: Vec<_> = report.decisions.iter().map(|d| d.street).collect();
+        assert_eq!(
+            streets,
+            [Street::Turn, Street::Turn, Street::River, Street::River]
+        );
+
+        for d in &report.decisions {
+            let total: f64 = d.options.iter().map(|o| o.frequency).sum();
+            assert!((total - 1.0).abs() < 1e-4);
+            assert_eq!(d.frequency, d.options[d.chosen].frequency);
+            let best = d.options.iter().map(|o| o.ev).fold(f64::MIN, f64::max);
+            assert!((best - d.options[d.chosen].ev - d.ev_loss).abs() < 1e-9);
+            let thresholds = &analyzer.config().thresholds;
+            assert_eq!(
+                d.classification,
+                Classification::from_ev_loss(d.ev_loss, thresholds)
+            );
+        }
+
+        // Mixing the options by the solution gives back the stored node EV
+        let root = &report.decisions[0];
+        assert_eq!(root.played, Action::Check);
+        assert_eq!(root.options[root.chosen].action, Action::Check);
+        let reader = &mut analyzer.library.spots[0];
+        let index = reader.header().hands[0]
+            .iter()
+            .position(|&h| h == Hand::new(card("7s"), card("7h")))
+            .expect("in range");
+        let stored = reader.node(0, &[]).expect("readable").expect("stored");
+        let mixed: f64 = root.options.iter().map(|o| o.frequency * o.ev).sum();
+        assert!((mixed - stored.ev[0][index] as f64).abs() < 1e-2);
+
+        // Villain's 0.6 pot bet was translated to the half-pot bet
+        let tree = reader.tree();
+        let ip = tree.node(tree.root().children[0]);
+        assert_eq!(ip.actions[1], Action::Bet(Chips::from_units(5)));
+        let facing = &report.decisions[1];
+        assert_eq!(facing.node, ip.children[1]);
+        assert_eq!(facing.options[facing.chosen].action, Action::Call);
+        assert_eq!(facing.options[0].ev, 0.0);
+
+        // The river call shows down the nuts
+        let call = &report.decisions[3];
+        assert_eq!(call.played, Action::Call);
+        assert_eq!(call.options[0].ev, -5.0);
+        assert_eq!(call.ev_loss, 0.0);
+        assert_eq!(call.classification, Classification::Perfect);
+    }
+
+    #[test]
+    fn test_session_report_and_unmatched_hands() {
+        let eval = LookupTableEvaluator::global();
+        let mut analyzer = analyzer(eval);
+        let hand = HandHistory::parse(HAND).expect("valid hand");
+        let other_board = HandHistory::parse(&HAND.replace("4s", "5s")).expect("valid hand");
+        let mut no_hero = hand.clone();
+        no_hero.hero = None;
+        let mut deep = hand.clone();
+        for seat in &mut deep.seats {
+            seat.stack = Chips::from_units(500);
+        }
+
+        let session = analyzer
+            .analyze_session([&hand, &other_board, &no_hero, &deep])
+            .expect("readable");
+        assert_eq!(session.hands.len(), 4);
+        assert_eq!(
+            session.hands[1].stopped.as_deref(),
+            Some("no spot with this board")
+        );
+        assert_eq!(
+            session.hands[2].stopped.as_deref(),
+            Some("hand has no hero")
+        );
+        assert_eq!(
+            session.hands[3].stopped.as_deref(),
+            Some("no spot with a similar pot and stack")
+        );
+        assert_eq!(session.analyzed_hands(), 1);
+        let counted: usize = Classification::ALL.iter().map(|&c| session.count(c)).sum();
+        assert_eq!(counted, 4);
+        assert_eq!(session.ev_loss(), session.hands[0].ev_loss());
+        assert_eq!(session.ev_loss_per_100(), session.ev_loss() * 100.0);
+    }
+
+    #[test]
+    fn test_off_tree_raise_stops_the_walk() {
+        let eval = LookupTableEvaluator::global();
+        let mut analyzer = analyzer(eval);
+        let turn = HAND.find("Hero: calls $6").expect("turn call");
+        let text = format!(
+            "{}Hero: raises $12 to $18\n\
+             Villain: folds\n\
+             Uncalled bet ($12) returned to Hero\n\
+             Hero collected $22 from pot\n\
+             *** SUMMARY ***\n\
+             Total pot $22 | Rake $0\n\
+             Board [Ah Kd 7c 4s]\n",
+            &HAND[..turn]
+        );
+        let hand = HandHistory::parse(&text).expect("valid hand");
+        let report = analyzer.analyze_hand(&hand).expect("readable");
+        assert_eq!(report.decisions.len(), 1);
+        assert_eq!(
+            report.stopped.as_deref(),
+            Some("no tree action for Hero's Raise 18.00 on the Turn")
+        );
+    }
+
+    #[test]
+    fn test_pseudo_harmonic_translation() {
+        let sizes = [(1, 0.5), (2, 1.0)];
+        assert_eq!(nearest_size(&sizes, 0.2), Some(1));
+        assert_eq!(nearest_size(&sizes, 0.6), Some(1));
+        // The split between half pot and pot is at 5/7 of the pot
+        assert_eq!(nearest_size(&sizes, 0.71), Some(1));
+        assert_eq!(nearest_size(&sizes, 0.72), Some(2));
+        assert_eq!(nearest_size(&sizes, 3.0), Some(2));
+        assert_eq!(nearest_size(&[], 0.5), None);
+    }
+
+    #[test]
+    fn test_classification_thresholds() {
+        let t = Thresholds::default();
+        assert_eq!(
+            Classification::from_ev_loss(0.0, &t),
+            Classification::Perfect
+        );
+        assert_eq!(
+            Classification::from_ev_loss(0.1, &t),
+            Classification::Inaccuracy
+        );
+        assert_eq!(
+            Classification::from_ev_loss(1.0, &t),
+            Classification::Mistake
+        );
+        assert_eq!(
+            Classification::from_ev_loss(5.0, &t),
+            Classification::Blunder
+        );
+    }
+}
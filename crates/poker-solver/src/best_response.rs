@@ -0,0 +1,287 @@
+use poker_core::game_tree::NodeId;
+use poker_core::{Action, Card, Hand};
+use serde::{Deserialize, Serialize};
+
+use crate::game::{
+    chance_values, compatible_reach, deal_card, terminal_values, Game, SolverKind, SolverNode,
+};
+use crate::solver::{map_children, normalize, sum_values, Solver};
+
+/// How far the average strategy profile is from equilibrium.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Exploitability {
+    /// EV in chips of OOP's and IP's best response against the other
+    /// player's average strategy, counting their share of the pot.
+    pub best_response_ev: [f64; 2],
+    /// Mean amount the two best responses gain over the game value, in chips.
+    /// Zero at a Nash equilibrium.
+    pub chips: f64,
+    /// `chips` as a percentage of the starting pot.
+    pub pot_percent: f64,
+}
+
+/// Best response of the acting player at one node, against the opponent's
+/// average strategy everywhere.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeBestResponse {
+    pub player: usize,
+    pub actions: Vec<Action>,
+    pub hands: Vec<Hand>,
+    /// EV in chips of each action, laid out `[action * hands.len() + hand]`,
+    /// playing a best response afterwards. Zero for combos that cannot
+    /// reach the node.
+    pub values: Vec<f64>,
+    /// Index into `actions` of each combo's best action.
+    pub best: Vec<usize>,
+}
+
+impl Solver {
+    /// Best-response EVs and exploitability of the current average strategies.
+    pub fn exploitability(&self) -> Exploitability {
+        let game = self.game();
+        let mut best_response_ev = [0.0f64; 2];
+        for (player, ev) in best_response_ev.iter_mut().enumerate() {
+            let reach = &game.weights[1 - player];
+            let values = best_response(game, self.root(), player, reach, self.threads());
+            let pairs = compatible_reach(game, player, reach);
+            let weights = &game.weights[player];
+            let total: f64 = weights
+                .iter()
+                .zip(&values)
+                .map(|(&w, &v)| w as f64 * v as f64)
+                .sum();
+            let mass: f64 = weights
+                .iter()
+                .zip(&pairs)
+                .map(|(&w, &p)| w as f64 * p)
+                .sum();
+            *ev = if mass > 0.0 { total / mass } else { 0.0 };
+        }
+        // Payoffs always sum to the starting pot
+        let pot = self.tree().config.starting_pot.to_f64();
+        let chips = (best_response_ev[0] + best_response_ev[1] - pot) / 2.0;
+        Exploitability {
+            best_response_ev,
+            chips,
+            pot_percent: chips / pot * 100.0,
+        }
+    }
+
+    /// Best response of the acting player at a player node, given the
+    /// opponent's average strategy on the path there and below.
+    pub fn best_response(&self, node: NodeId, runout: &[Card]) -> Option<NodeBestResponse> {
+        let player = self.find(node, runout)?.player()?;
+        let game = self.game();
+        let opp = 1 - player;
+
+        let mut reach = game.weights[opp].clone();
+        let mut dead = 0u64;
+        let found = self.walk(node, runout, |passed, child| match &passed.kind {
+            SolverKind::Player {
+                player: actor,
+                strategy_sum,
+                ..
+            } if *actor == opp => {
+                let strategy = normalize(strategy_sum, passed.children.len());
+                let m = reach.len();
+                for (o, r) in reach.iter_mut().enumerate() {
+                    *r *= strategy[child * m + o];
+                }
+            }
+            SolverKind::Chance { cards } => {
+                reach = deal_card(game, player, &reach, cards[child]);
+                dead |= cards[child].mask();
+            }
+            _ => {}
+        })?;
+
+        let hands = game.hands[player].clone();
+        let n = hands.len();
+        let pairs = compatible_reach(game, player, &reach);
+        let mut values = vec![0.0f64; found.children.len() * n];
+        for (a, child) in found.children.iter().enumerate() {
+            let v = best_response(game, child, player, &reach, self.threads());
+            for (h, hand) in hands.iter().enumerate() {
+                if hand.mask() & dead == 0 && pairs[h] > 0.0 {
+                    values[a * n + h] = v[h] as f64 / pairs[h];
+                }
+            }
+        }
+        let best = (0..n)
+            .map(|h| {
+                (0..found.children.len())
+                    .max_by(|&a, &b| values[a * n + h].total_cmp(&values[b * n + h]))
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        Some(NodeBestResponse {
+            player,
+            actions: self.tree().node(node).actions.clone(),
+            hands,
+            values,
+            best,
+        })
+    }
+}
+
+/// Counterfactual values of `player`'s combos when `player` best-responds
+/// below `node` and the opponent plays their average strategy.
+fn best_response(
+    game: &Game,
+    node: &SolverNode,
+    player: usize,
+    reach: &[f32],
+    threads: usize,
+) -> Vec<f32> {
+    let n = game.hands[player].len();
+    if reach.iter().all(|&r| r == 0.0) {
+        return vec![0.0; n];
+    }
+    if let Some(values) = terminal_values(game, node, player, reach) {
+        return values;
+    }
+    let children = node.children.iter().collect();
+
+    match &node.kind {
+        SolverKind::Chance { cards } => {
+            let values = map_children(children, threads, |i, child, t| {
+                let dealt = deal_card(game, player, reach, cards[i]);
+                best_response(game, child, player, &dealt, t)
+            });
+            chance_values(game, player, cards, values)
+        }
+        SolverKind::Player {
+            player: actor,
+            strategy_sum,
+            ..
+        } => {
+            if *actor == player {
+                let values = map_children(children, threads, |_, child, t| {
+                    best_response(game, child, player, reach, t)
+                });
+                (0..n)
+                    .map(|h| {
+                        values
+                            .iter()
+                            .map(|v| v[h])
+                            .fold(f32::NEG_INFINITY, f32::max)
+                    })
+                    .collect()
+            } else {
+                let strategy = normalize(strategy_sum, node.children.len());
+                let m = reach.len();
+                let values = map_children(children, threads, |a, child, t| {
+                    let next: Vec<f32> = reach
+                        .iter()
+                        .enumerate()
+                        .map(|(o, &r)| r * strategy[a * m + o])
+                        .collect();
+                    best_response(game, child, player, &next, t)
+                });
+                sum_values(n, values)
+            }
+        }
+        SolverKind::Fold { .. } | SolverKind::Showdown(_) => unreachable!("handled above"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{Discount, SolverConfig};
+    use poker_core::game_tree::{StreetConfig, TreeConfig, IP};
+    use poker_core::{BetSize, Board, Chips, Range, Street};
+    use poker_eval::LookupTableEvaluator;
+
+    fn no_bets() -> StreetConfig {
+        StreetConfig {
+            bet_sizes: Vec::new(),
+            raise_sizes: Vec::new(),
+            raise_cap: 0,
+        }
+    }
+
+    /// OOP has a set or air and may bet pot; IP holds a bluff-catcher.
+    fn polarized_river(config: SolverConfig) -> Solver {
+        let tree = TreeConfig {
+            starting_pot: Chips::from_units(100),
+            effective_stack: Chips::from_units(100),
+            big_blind: Chips::from_units(1),
+            starting_street: Street::River,
+            flop: no_bets(),
+            turn: no_bets(),
+            river: StreetConfig {
+                bet_sizes: vec![BetSize::PotFraction(1.0)],
+                ..no_bets()
+            },
+            all_in_threshold: 1.0,
+            force_all_in_spr: 0.0,
+        };
+        let cards: Vec<Card> = ["Ah", "Kd", "7c", "4s", "2h"]
+            .iter()
+            .map(|c| Card::from_str_notation(c).expect("valid card"))
+            .collect();
+        let board = Board::from_cards(&cards).expect("valid board");
+        let oop: Range = "77,98s".parse().expect("valid");
+        let ip: Range = "KQ".parse().expect("valid");
+        let eval = LookupTableEvaluator::global();
+        Solver::new(eval, [&oop, &ip], &board, &tree, config).expect("valid spot")
+    }
+
+    #[test]
+    fn test_uniform_strategy_best_response() {
+        let solver = polarized_river(SolverConfig::default());
+        // Unsolved, OOP bets half of 3 sets and half of 4 air combos.
+        // Calling the 100 bet: lose 100 to 1.5 sets, win 200 from 2 bluffs.
+        let facing = solver.tree().root().children[1];
+        let br = solver.best_response(facing, &[]).expect("player node");
+        assert_eq!(br.player, IP);
+        assert_eq!(br.actions, vec![Action::Fold, Action::Call]);
+        let n = br.hands.len();
+        for h in 0..n {
+            assert_eq!(br.best[h], 1);
+            assert!(br.values[h].abs() < 1e-6);
+            assert!((br.values[n + h] - 250.0 / 3.5).abs() < 1e-3);
+        }
+        assert!(solver.best_response(0, &[]).is_some());
+        assert!(solver.best_response(facing + 1, &[]).is_none());
+
+        let start = solver.exploitability();
+        assert!(start.pot_percent > 10.0);
+    }
+
+    #[test]
+    fn test_exploitability_converges() {
+        let mut solver = polarized_river(SolverConfig {
+            discount: Discount::vanilla(),
+            iterations: 3000,
+            threads: 1,
+            ..SolverConfig::default()
+        });
+        let before = solver.exploitability();
+        solver.solve();
+        let after = solver.exploitability();
+        assert!(after.chips < before.chips);
+        assert!(after.chips >= -1e-3);
+        assert!(after.pot_percent < 1.0);
+        // At equilibrium the best responses split the starting pot
+        let total = after.best_response_ev[0] + after.best_response_ev[1];
+        assert!((total - 100.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_solve_stops_at_target() {
+        let mut solver = polarized_river(SolverConfig {
+            discount: Discount::dcfr(),
+            iterations: 10_000,
+            threads: 1,
+            target_exploitability: Some(0.5),
+            check_every: 10,
+        });
+        let reached = solver.solve().expect("checked at least once");
+        assert!(reached.pot_percent <= 0.5);
+        assert!(solver.iterations() < 10_000);
+        assert_eq!(solver.iterations() % 10, 0);
+    }
+}
@@ -0,0 +1,40 @@
+use poker_core::Street;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Solver errors.
+#[derive(Debug, Error, Clone, Serialize, Deserialize)]
+pub enum SolverError {
+    #[error("board has {board} cards but the tree starts on the {street:?}")]
+    BoardStreetMismatch { board: usize, street: Street },
+
+    #[error("range for player {0} has no combos that avoid the board")]
+    EmptyRange(usize),
+
+    #[error("not a solution file")]
+    BadMagic,
+
+    #[error("unsupported solution file version {0}")]
+    UnsupportedVersion(u16),
+
+    #[error("solution file is truncated")]
+    Truncated,
+
+    #[error("solution file is corrupt: {0}")]
+    Corrupt(String),
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    #[error("core error: {0}")]
+    Core(#[from] poker_core::CoreError),
+}
+
+impl From<std::io::Error> for SolverError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => SolverError::Truncated,
+            _ => SolverError::Io(e.to_string()),
+        }
+    }
+}
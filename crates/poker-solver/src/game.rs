@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use poker_core::game_tree::{GameTree, NodeId, NodeKind};
+use poker_core::{Card, ComboRange, Hand, Range};
+use poker_eval::Evaluator;
+
+/// Per-player combo lists shared by every node of the solve.
+pub(crate) struct Game {
+    /// Live combos of OOP and IP, in `Hand::combo_index` order.
+    pub hands: [Vec<Hand>; 2],
+    /// Starting weight of each combo.
+    pub weights: [Vec<f32>; 2],
+    /// For each combo, the index of the identical combo in the other player's list.
+    pub same: [Vec<Option<usize>>; 2],
+}
+
+impl Game {
+    pub fn new(ranges: [&Range; 2], board_mask: u64) -> Self {
+        let live = |range: &Range| -> (Vec<Hand>, Vec<f32>) {
+            let mut combos = ComboRange::from_range(range);
+            combos.remove_dead_cards(board_mask);
+            combos.iter().unzip()
+        };
+        let (hands0, weights0) = live(ranges[0]);
+        let (hands1, weights1) = live(ranges[1]);
+        let same_in = |mine: &[Hand], theirs: &[Hand]| -> Vec<Option<usize>> {
+            mine.iter()
+                .map(|h| {
+                    theirs
+                        .binary_search_by_key(&h.combo_index(), |t| t.combo_index())
+                        .ok()
+                })
+                .collect()
+        };
+        let same = [same_in(&hands0, &hands1), same_in(&hands1, &hands0)];
+        Game {
+            hands: [hands0, hands1],
+            weights: [weights0, weights1],
+            same,
+        }
+    }
+}
+
+/// Showdown order of both players' combos on one complete board.
+pub(crate) struct Showdown {
+    /// `(rank value, combo index)` for every combo not blocked by the board,
+    /// weakest first.
+    pub order: [Vec<(u16, usize)>; 2],
+}
+
+/// What a solver node does, with the regret storage of player nodes.
+pub(crate) enum SolverKind {
+    /// Regrets and strategy sums are laid out `[action * hands + hand]`.
+    Player {
+        player: usize,
+        regrets: Vec<f32>,
+        strategy_sum: Vec<f32>,
+    },
+    /// One child per card in `cards`.
+    Chance {
+        cards: Vec<Card>,
+    },
+    Fold {
+        winner: usize,
+    },
+    Showdown(Arc<Showdown>),
+}
+
+/// A node of the game tree with every chance card expanded.
+pub(crate) struct SolverNode {
+    /// The `GameTree` node this was expanded from.
+    pub tree_id: NodeId,
+    pub kind: SolverKind,
+    pub children: Vec<SolverNode>,
+    pub pot: f64,
+    /// Chips each player has put in since the root.
+    pub invested: [f64; 2],
+}
+
+impl SolverNode {
+    /// The acting player at a player node.
+    pub fn player(&self) -> Option<usize> {
+        match self.kind {
+            SolverKind::Player { player, .. } => Some(player),
+            _ => None,
+        }
+    }
+}
+
+/// Expands a `GameTree` for one starting board.
+pub(crate) struct Expander<'a> {
+    pub tree: &'a GameTree,
+    pub game: &'a Game,
+    pub evaluator: &'a dyn Evaluator,
+    /// Showdown orders keyed by board mask, shared across deal orders.
+    pub showdowns: HashMap<u64, Arc<Showdown>>,
+}
+
+impl Expander<'_> {
+    pub fn expand(&mut self, id: NodeId, board: &mut Vec<Card>) -> SolverNode {
+        let node = self.tree.node(id);
+        let stack = self.tree.config.effective_stack;
+        let kind = match node.kind {
+            NodeKind::Player(player) => {
+                let size = node.actions.len() * self.game.hands[player].len();
+                SolverKind::Player {
+                    player,
+                    regrets: vec![0.0; size],
+                    strategy_sum: vec![0.0; size],
+                }
+            }
+            NodeKind::Chance => {
+                let mask = board_mask(board);
+                let cards = (0..52u8)
+                    .filter_map(|i| Card::from_u8(i).ok())
+                    .filter(|c| c.mask() & mask == 0)
+                    .collect();
+                SolverKind::Chance { cards }
+            }
+            NodeKind::Fold { winner } => SolverKind::Fold { winner },
+            NodeKind::Showdown => SolverKind::Showdown(self.showdown(board)),
+        };
+
+        let children = match &kind {
+            SolverKind::Chance { cards } => cards
+                .iter()
+                .map(|&card| {
+                    board.push(card);
+                    let child = self.expand(node.children[0], board);
+                    board.pop();
+                    child
+                })
+                .collect(),
+            _ => node
+                .children
+                .iter()
+                .map(|&child| self.expand(child, board))
+                .collect(),
+        };
+
+        SolverNode {
+            tree_id: id,
+            kind,
+            children,
+            pot: node.pot.to_f64(),
+            invested: [
+                (stack - node.stacks[0]).to_f64(),
+                (stack - node.stacks[1]).to_f64(),
+            ],
+        }
+    }
+
+    fn showdown(&mut self, board: &[Card]) -> Arc<Showdown> {
+        let mask = board_mask(board);
+        if let Some(sd) = self.showdowns.get(&mask) {
+            return Arc::clone(sd);
+        }
+        let mut cards = [board[0]; 7];
+        cards[2..].copy_from_slice(&board[..5]);
+        let mut rank_all = |hands: &[Hand]| -> Vec<(u16, usize)> {
+            let mut order: Vec<(u16, usize)> = hands
+                .iter()
+                .enumerate()
+                .filter(|(_, h)| h.mask() & mask == 0)
+                .map(|(i, h)| {
+                    cards[0] = h.card1();
+                    cards[1] = h.card2();
+                    (self.evaluator.evaluate_7(&cards).value(), i)
+                })
+                .collect();
+            // Higher rank values are weaker hands
+            order.sort_unstable_by_key(|&(rank, _)| std::cmp::Reverse(rank));
+            order
+        };
+        let order = [rank_all(&self.game.hands[0]), rank_all(&self.game.hands[1])];
+        let sd = Arc::new(Showdown { order });
+        self.showdowns.insert(mask, Arc::clone(&sd));
+        sd
+    }
+}
+
+pub(crate) fn board_mask(board: &[Card]) -> u64 {
+    board.iter().fold(0, |m, c| m | c.mask())
+}
+
+/// Reach of the opponent's combos compatible with each of `player`'s combos.
+///
+/// Uses per-card sums so blocking costs O(n) instead of O(n^2).
+pub(crate) fn compatible_reach(game: &Game, player: usize, reach: &[f32]) -> Vec<f64> {
+    let opp = 1 - player;
+    let mut total = 0.0f64;
+    let mut per_card = [0.0f64; 52];
+    for (hand, &r) in game.hands[opp].iter().zip(reach) {
+        if r > 0.0 {
+            total += r as f64;
+            per_card[hand.card1().as_u8() as usize] += r as f64;
+            per_card[hand.card2().as_u8() as usize] += r as f64;
+        }
+    }
+    game.hands[player]
+        .iter()
+        .zip(&game.same[player])
+        .map(|(hand, same)| {
+            let blocked =
+                per_card[hand.card1().as_u8() as usize] + per_card[hand.card2().as_u8() as usize];
+            // The identical combo was subtracted once per card
+            let identical = same.map_or(0.0, |o| reach[o] as f64);
+            total - blocked + identical
+        })
+        .collect()
+}
+
+/// Counterfactual values of `player`'s combos at a fold terminal.
+pub(crate) fn fold_values(game: &Game, player: usize, reach: &[f32], payoff: f64) -> Vec<f32> {
+    compatible_reach(game, player, reach)
+        .into_iter()
+        .map(|r| (payoff * r) as f32)
+        .collect()
+}
+
+/// Counterfactual values of `player`'s combos at a showdown terminal.
+pub(crate) fn showdown_values(
+    game: &Game,
+    player: usize,
+    reach: &[f32],
+    sd: &Showdown,
+    pot: f64,
+    invested: f64,
+) -> Vec<f32> {
+    let opp = 1 - player;
+    let mine = &sd.order[player];
+    let theirs = &sd.order[opp];
+    let n = game.hands[player].len();
+    let hands = &game.hands;
+
+    // Sweep opponents weaker (then stronger) than each combo, removing blockers
+    let sweep = |ascending: bool| -> Vec<f64> {
+        let mut out = vec![0.0f64; n];
+        let mut total = 0.0f64;
+        let mut per_card = [0.0f64; 52];
+        let mut j = 0;
+        let step = |k: usize, len: usize| if ascending { k } else { len - 1 - k };
+        for k in 0..mine.len() {
+            let (rank, h) = mine[step(k, mine.len())];
+            while j < theirs.len() {
+                let (their_rank, o) = theirs[step(j, theirs.len())];
+                let beaten = if ascending {
+                    their_rank > rank
+                } else {
+                    their_rank < rank
+                };
+                if !beaten {
+                    break;
+                }
+                let r = reach[o] as f64;
+                total += r;
+                per_card[hands[opp][o].card1().as_u8() as usize] += r;
+                per_card[hands[opp][o].card2().as_u8() as usize] += r;
+                j += 1;
+            }
+            let hand = hands[player][h];
+            out[h] = total
+                - per_card[hand.card1().as_u8() as usize]
+                - per_card[hand.card2().as_u8() as usize];
+        }
+        out
+    };
+    let wins = sweep(true);
+    let losses = sweep(false);
+    let compatible = compatible_reach(game, player, reach);
+
+    let mut values = vec![0.0f32; n];
+    for &(_, h) in mine {
+        let ties = compatible[h] - wins[h] - losses[h];
+        values[h] = (pot * (wins[h] + ties / 2.0) - invested * compatible[h]) as f32;
+    }
+    values
+}
+
+/// Counterfactual values of `player`'s combos at a fold or showdown node;
+/// `None` for other nodes.
+pub(crate) fn terminal_values(
+    game: &Game,
+    node: &SolverNode,
+    player: usize,
+    reach: &[f32],
+) -> Option<Vec<f32>> {
+    let invested = node.invested[player];
+    match &node.kind {
+        SolverKind::Fold { winner } => {
+            let payoff = if *winner == player {
+                node.pot - invested
+            } else {
+                -invested
+            };
+            Some(fold_values(game, player, reach, payoff))
+        }
+        SolverKind::Showdown(sd) => {
+            Some(showdown_values(game, player, reach, sd, node.pot, invested))
+        }
+        SolverKind::Player { .. } | SolverKind::Chance { .. } => None,
+    }
+}
+
+/// The opponent's reach once `card` is dealt: combos holding it drop out.
+pub(crate) fn deal_card(game: &Game, player: usize, reach: &[f32], card: Card) -> Vec<f32> {
+    game.hands[1 - player]
+        .iter()
+        .zip(reach)
+        .map(|(h, &r)| if h.mask() & card.mask() != 0 { 0.0 } else { r })
+        .collect()
+}
+
+/// Combine the values of each dealt card into the chance node's value.
+/// Combos holding a card get nothing from that card's subtree.
+pub(crate) fn chance_values(
+    game: &Game,
+    player: usize,
+    cards: &[Card],
+    values: Vec<Vec<f32>>,
+) -> Vec<f32> {
+    // Each pair of hands sees every card except its own four
+    let scale = 1.0 / (cards.len() - 4) as f32;
+    let mut out = vec![0.0f32; game.hands[player].len()];
+    for (card, v) in cards.iter().zip(values) {
+        for ((o, x), h) in out.iter_mut().zip(v).zip(&game.hands[player]) {
+            if h.mask() & card.mask() == 0 {
+                *o += x * scale;
+            }
+        }
+    }
+    out
+}
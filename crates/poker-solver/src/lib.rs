@@ -0,0 +1,17 @@
+pub mod analyzer;
+pub mod best_response;
+pub mod error;
+mod game;
+pub mod solution;
+pub mod solver;
+
+pub use analyzer::{
+    ActionEv, Analyzer, AnalyzerConfig, Classification, Decision, HandReport, SessionReport,
+    SpotLibrary, Thresholds,
+};
+pub use best_response::{Exploitability, NodeBestResponse};
+pub use error::SolverError;
+pub use solution::{
+    save_solution, write_solution, NodeData, SolutionHeader, SolutionReader, FORMAT_VERSION,
+};
+pub use solver::{Discount, NodeStrategy, Solver, SolverConfig};
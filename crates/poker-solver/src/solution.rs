@@ -0,0 +1,682 @@
+//! On-disk format for solved spots (`.pks` files).
+//!
+//! All integers are little-endian. A file is laid out as:
+//!
+//! ```text
+//! offset  size  field
+//! 0       8     magic "PKSOLN\0\0"
+//! 8       2     format version (FORMAT_VERSION)
+//! 10      2     flags, reserved (0)
+//! 12      4     compressed header length H
+//! 16      4     CRC-32 of the compressed header
+//! 20      4     node count N
+//! 24      8     index offset I
+//! 32      4     CRC-32 of the index
+//! 36      H     header: deflate-compressed JSON `SolutionHeader`
+//! 36+H    ...   node blocks: one deflate-compressed record per player node
+//! I       22*N  index: one entry per node block
+//! ```
+//!
+//! An index entry is `tree_id u32, runout [u8; 2], offset u64, length u32,
+//! crc32 u32`. `runout` holds the turn and river cards dealt after the
+//! starting board (`Card::as_u8`), padded with `0xFF`.
+//!
+//! A node record, before compression, is `player u8, actions u16,
+//! oop_hands u32, ip_hands u32`, followed by f32 values: the average
+//! strategy (`actions * hands of the acting player`, laid out
+//! `[action * hands + hand]`), then each OOP combo's EV, then each IP
+//! combo's EV. Hands are ordered as in `SolutionHeader::hands`.
+//!
+//! `SolutionReader` reads only the fixed header, the JSON header and the
+//! index when opening; node blocks are read and decompressed on demand.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use poker_core::game_tree::{GameTree, NodeId, TreeConfig};
+use poker_core::{Action, Card, Hand, Range};
+use serde::{Deserialize, Serialize};
+
+use crate::best_response::Exploitability;
+use crate::error::SolverError;
+use crate::game::{
+    chance_values, compatible_reach, deal_card, terminal_values, Game, SolverKind, SolverNode,
+};
+use crate::solver::{normalize, Solver};
+
+/// First bytes of every solution file.
+pub const MAGIC: [u8; 8] = *b"PKSOLN\0\0";
+/// Current format version. Readers reject other versions.
+pub const FORMAT_VERSION: u16 = 1;
+
+const FIXED_HEADER_LEN: u64 = 36;
+const INDEX_ENTRY_LEN: usize = 22;
+const NO_CARD: u8 = 0xFF;
+
+/// Everything about a solved spot except the per-node data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolutionHeader {
+    pub tree_config: TreeConfig,
+    /// Starting ranges of OOP and IP.
+    pub ranges: [Range; 2],
+    pub board: Vec<Card>,
+    /// Live combos of OOP and IP, in the order node data is stored.
+    pub hands: [Vec<Hand>; 2],
+    pub iterations: u32,
+    pub exploitability: Exploitability,
+}
+
+/// Stored data of one player node on one runout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeData {
+    pub tree_id: NodeId,
+    /// Turn and river cards dealt after the starting board.
+    pub runout: Vec<Card>,
+    pub player: usize,
+    pub actions: Vec<Action>,
+    /// Average strategy of the acting player, `[action * hands + hand]`.
+    pub strategy: Vec<f32>,
+    /// EV in chips of each OOP and IP combo at this node under the average
+    /// strategies. Zero for combos that cannot reach the node.
+    pub ev: [Vec<f32>; 2],
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    offset: u64,
+    len: u32,
+    crc: u32,
+}
+
+/// Write a solved spot to `path`.
+pub fn save_solution(solver: &Solver, path: &Path) -> Result<(), SolverError> {
+    let file = File::create(path)?;
+    write_solution(solver, BufWriter::new(file))
+}
+
+/// Write a solved spot. The writer must be seekable so the fixed header can
+/// be filled in once the index position is known.
+pub fn write_solution<W: Write + Seek>(solver: &Solver, mut out: W) -> Result<(), SolverError> {
+    let game = solver.game();
+    let header = SolutionHeader {
+        tree_config: solver.tree().config.clone(),
+        ranges: [solver.range(0).clone(), solver.range(1).clone()],
+        board: solver.board().to_vec(),
+        hands: game.hands.clone(),
+        iterations: solver.iterations(),
+        exploitability: solver.exploitability(),
+    };
+    let json = serde_json::to_vec(&header).map_err(|e| SolverError::Io(e.to_string()))?;
+    let header_block = compress(&json)?;
+
+    out.write_all(&[0; FIXED_HEADER_LEN as usize])?;
+    out.write_all(&header_block)?;
+    let mut offset = FIXED_HEADER_LEN + header_block.len() as u64;
+
+    let mut index = Vec::new();
+    let mut failure = None;
+    let reach = game.weights.clone();
+    let mut runout = Vec::new();
+    visit_nodes(
+        game,
+        solver.root(),
+        reach,
+        &mut runout,
+        &mut |node, runout, ev| {
+            if failure.is_some() {
+                return;
+            }
+            let result = write_node(&mut out, game, node, ev).map(|(len, crc)| {
+                index.push((
+                    node.tree_id,
+                    runout_key(runout),
+                    IndexEntry { offset, len, crc },
+                ));
+                offset += len as u64;
+            });
+            if let Err(e) = result {
+                failure = Some(e);
+            }
+        },
+    );
+    if let Some(e) = failure {
+        return Err(e);
+    }
+
+    let mut index_bytes = Vec::with_capacity(index.len() * INDEX_ENTRY_LEN);
+    for (tree_id, key, entry) in &index {
+        index_bytes.extend_from_slice(&(*tree_id as u32).to_le_bytes());
+        index_bytes.extend_from_slice(key);
+        index_bytes.extend_from_slice(&entry.offset.to_le_bytes());
+        index_bytes.extend_from_slice(&entry.len.to_le_bytes());
+        index_bytes.extend_from_slice(&entry.crc.to_le_bytes());
+    }
+    out.write_all(&index_bytes)?;
+
+    let mut fixed = Vec::with_capacity(FIXED_HEADER_LEN as usize);
+    fixed.extend_from_slice(&MAGIC);
+    fixed.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    fixed.extend_from_slice(&0u16.to_le_bytes());
+    fixed.extend_from_slice(&(header_block.len() as u32).to_le_bytes());
+    fixed.extend_from_slice(&crc32fast::hash(&header_block).to_le_bytes());
+    fixed.extend_from_slice(&(index.len() as u32).to_le_bytes());
+    fixed.extend_from_slice(&offset.to_le_bytes());
+    fixed.extend_from_slice(&crc32fast::hash(&index_bytes).to_le_bytes());
+    out.seek(SeekFrom::Start(0))?;
+    out.write_all(&fixed)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Compress and write one node record, returning its length and CRC.
+fn write_node<W: Write>(
+    out: &mut W,
+    game: &Game,
+    node: &SolverNode,
+    ev: &[Vec<f32>; 2],
+) -> Result<(u32, u32), SolverError> {
+    let SolverKind::Player {
+        player,
+        strategy_sum,
+        ..
+    } = &node.kind
+    else {
+        return Err(SolverError::Corrupt("not a player node".to_string()));
+    };
+    let strategy = normalize(strategy_sum, node.children.len());
+    let mut raw = Vec::with_capacity(11 + 4 * (strategy.len() + ev[0].len() + ev[1].len()));
+    raw.push(*player as u8);
+    raw.extend_from_slice(&(node.children.len() as u16).to_le_bytes());
+    raw.extend_from_slice(&(game.hands[0].len() as u32).to_le_bytes());
+    raw.extend_from_slice(&(game.hands[1].len() as u32).to_le_bytes());
+    for x in strategy.iter().chain(&ev[0]).chain(&ev[1]) {
+        raw.extend_from_slice(&x.to_le_bytes());
+    }
+    let block = compress(&raw)?;
+    out.write_all(&block)?;
+    Ok((block.len() as u32, crc32fast::hash(&block)))
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>, SolverError> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Decompress a block, refusing to inflate beyond `limit` bytes.
+fn decompress(data: &[u8], limit: usize) -> Result<Vec<u8>, SolverError> {
+    let mut out = Vec::new();
+    DeflateDecoder::new(data)
+        .take(limit as u64 + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| SolverError::Corrupt(format!("bad compressed data: {e}")))?;
+    if out.len() > limit {
+        return Err(SolverError::Corrupt(
+            "block larger than expected".to_string(),
+        ));
+    }
+    Ok(out)
+}
+
+fn runout_key(runout: &[Card]) -> [u8; 2] {
+    let mut key = [NO_CARD; 2];
+    for (k, card) in key.iter_mut().zip(runout) {
+        *k = card.as_u8();
+    }
+    key
+}
+
+/// Callback receiving a player node, its runout and each combo's EV.
+type NodeVisitor<'a> = dyn FnMut(&SolverNode, &[Card], &[Vec<f32>; 2]) + 'a;
+
+/// Walk every expanded node with both players' reach, calling `visit` on
+/// player nodes with each combo's EV. Returns both players' counterfactual
+/// values at `node`.
+fn visit_nodes(
+    game: &Game,
+    node: &SolverNode,
+    reach: [Vec<f32>; 2],
+    runout: &mut Vec<Card>,
+    visit: &mut NodeVisitor<'_>,
+) -> [Vec<f32>; 2] {
+    let values_for = |p: usize| terminal_values(game, node, p, &reach[1 - p]);
+    if let (Some(v0), Some(v1)) = (values_for(0), values_for(1)) {
+        return [v0, v1];
+    }
+
+    match &node.kind {
+        SolverKind::Chance { cards } => {
+            let mut per_card: [Vec<Vec<f32>>; 2] = [Vec::new(), Vec::new()];
+            for (&card, child) in cards.iter().zip(&node.children) {
+                let dealt = [
+                    deal_card(game, 1, &reach[0], card),
+                    deal_card(game, 0, &reach[1], card),
+                ];
+                runout.push(card);
+                let [v0, v1] = visit_nodes(game, child, dealt, runout, visit);
+                runout.pop();
+                per_card[0].push(v0);
+                per_card[1].push(v1);
+            }
+            let [c0, c1] = per_card;
+            [
+                chance_values(game, 0, cards, c0),
+                chance_values(game, 1, cards, c1),
+            ]
+        }
+        SolverKind::Player {
+            player: actor,
+            strategy_sum,
+            ..
+        } => {
+            let actor = *actor;
+            let strategy = normalize(strategy_sum, node.children.len());
+            let n = game.hands[actor].len();
+            let mut values = [
+                vec![0.0f32; game.hands[0].len()],
+                vec![0.0f32; game.hands[1].len()],
+            ];
+            for (a, child) in node.children.iter().enumerate() {
+                let mut next = reach.clone();
+                for (h, r) in next[actor].iter_mut().enumerate() {
+                    *r *= strategy[a * n + h];
+                }
+                let child_values = visit_nodes(game, child, next, runout, visit);
+                for (h, v) in child_values[actor].iter().enumerate() {
+                    values[actor][h] += strategy[a * n + h] * v;
+                }
+                for (o, v) in child_values[1 - actor].iter().enumerate() {
+                    values[1 - actor][o] += v;
+                }
+            }
+
+            let dead = runout.iter().fold(0u64, |m, c| m | c.mask());
+            let ev = [0, 1].map(|p| {
+                let pairs = compatible_reach(game, p, &reach[1 - p]);
+                game.hands[p]
+                    .iter()
+                    .zip(&values[p])
+                    .zip(pairs)
+                    .map(|((hand, &v), pairs)| {
+                        if hand.mask() & dead == 0 && pairs > 0.0 {
+                            (v as f64 / pairs) as f32
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect()
+            });
+            visit(node, runout, &ev);
+            values
+        }
+        SolverKind::Fold { .. } | SolverKind::Showdown(_) => unreachable!("handled above"),
+    }
+}
+
+/// Reads a solution file, loading node data on demand.
+pub struct SolutionReader<R> {
+    reader: R,
+    header: SolutionHeader,
+    tree: GameTree,
+    index: HashMap<(NodeId, [u8; 2]), IndexEntry>,
+}
+
+impl SolutionReader<BufReader<File>> {
+    /// Open a solution file, reading its header and index.
+    pub fn open(path: &Path) -> Result<Self, SolverError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> SolutionReader<R> {
+    /// Read the header and index from a seekable source.
+    pub fn new(mut reader: R) -> Result<Self, SolverError> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(SolverError::BadMagic);
+        }
+        let mut fixed = [0u8; FIXED_HEADER_LEN as usize - 8];
+        reader.read_exact(&mut fixed)?;
+        let version = u16::from_le_bytes([fixed[0], fixed[1]]);
+        if version != FORMAT_VERSION {
+            return Err(SolverError::UnsupportedVersion(version));
+        }
+        let header_len = le_u32(&fixed[4..8]) as u64;
+        let header_crc = le_u32(&fixed[8..12]);
+        let node_count = le_u32(&fixed[12..16]) as u64;
+        let index_offset = le_u64(&fixed[16..24]);
+        let index_crc = le_u32(&fixed[24..28]);
+
+        let index_len = node_count * INDEX_ENTRY_LEN as u64;
+        let data_start = FIXED_HEADER_LEN + header_len;
+        if data_start > index_offset {
+            return Err(SolverError::Corrupt(
+                "header overlaps node data".to_string(),
+            ));
+        }
+        if index_offset.saturating_add(index_len) > len {
+            return Err(SolverError::Truncated);
+        }
+
+        let mut header_block = vec![0u8; header_len as usize];
+        reader.read_exact(&mut header_block)?;
+        if crc32fast::hash(&header_block) != header_crc {
+            return Err(SolverError::Corrupt("header checksum mismatch".to_string()));
+        }
+        // Headers are small; the limit only guards against corrupt input
+        let json = decompress(&header_block, 64 << 20)?;
+        let header: SolutionHeader = serde_json::from_slice(&json)
+            .map_err(|e| SolverError::Corrupt(format!("bad header: {e}")))?;
+        let tree = GameTree::build(&header.tree_config)?;
+
+        reader.seek(SeekFrom::Start(index_offset))?;
+        let mut index_bytes = vec![0u8; index_len as usize];
+        reader.read_exact(&mut index_bytes)?;
+        if crc32fast::hash(&index_bytes) != index_crc {
+            return Err(SolverError::Corrupt("index checksum mismatch".to_string()));
+        }
+        let mut index = HashMap::with_capacity(node_count as usize);
+        for raw in index_bytes.chunks_exact(INDEX_ENTRY_LEN) {
+            let tree_id = le_u32(&raw[0..4]) as NodeId;
+            let key = [raw[4], raw[5]];
+            let entry = IndexEntry {
+                offset: le_u64(&raw[6..14]),
+                len: le_u32(&raw[14..18]),
+                crc: le_u32(&raw[18..22]),
+            };
+            let in_bounds = entry.offset >= data_start
+                && entry.offset.saturating_add(entry.len as u64) <= index_offset;
+            let is_player = tree_id < tree.node_count() && tree.node(tree_id).player().is_some();
+            if !in_bounds || !is_player {
+                return Err(SolverError::Corrupt(format!(
+                    "bad index entry for node {tree_id}"
+                )));
+            }
+            index.insert((tree_id, key), entry);
+        }
+
+        Ok(SolutionReader {
+            reader,
+            header,
+            tree,
+            index,
+        })
+    }
+
+    pub fn header(&self) -> &SolutionHeader {
+        &self.header
+    }
+
+    /// The betting tree rebuilt from the stored config.
+    pub fn tree(&self) -> &GameTree {
+        &self.tree
+    }
+
+    /// Number of stored node records (player nodes times runouts).
+    pub fn node_count(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Every stored `(tree node, runout)` pair, in no particular order.
+    pub fn entries(&self) -> impl Iterator<Item = (NodeId, Vec<Card>)> + '_ {
+        self.index.keys().map(|&(id, key)| {
+            let runout = key
+                .iter()
+                .filter(|&&c| c != NO_CARD)
+                .filter_map(|&c| Card::from_u8(c).ok())
+                .collect();
+            (id, runout)
+        })
+    }
+
+    /// Load one node's data, or `None` if the file has no such node.
+    pub fn node(
+        &mut self,
+        tree_id: NodeId,
+        runout: &[Card],
+    ) -> Result<Option<NodeData>, SolverError> {
+        let Some(entry) = self.index.get(&(tree_id, runout_key(runout))).copied() else {
+            return Ok(None);
+        };
+        let tree_node = self.tree.node(tree_id);
+        let player = tree_node.player().unwrap_or(0);
+        let actions = tree_node.actions.clone();
+        let counts = [self.header.hands[0].len(), self.header.hands[1].len()];
+        let strategy_len = actions.len() * counts[player];
+        let expected = 11 + 4 * (strategy_len + counts[0] + counts[1]);
+
+        self.reader.seek(SeekFrom::Start(entry.offset))?;
+        let mut block = vec![0u8; entry.len as usize];
+        self.reader.read_exact(&mut block)?;
+        if crc32fast::hash(&block) != entry.crc {
+            return Err(SolverError::Corrupt(format!(
+                "checksum mismatch in node {tree_id}"
+            )));
+        }
+        let raw = decompress(&block, expected)?;
+        let valid = raw.len() == expected
+            && raw[0] as usize == player
+            && u16::from_le_bytes([raw[1], raw[2]]) as usize == actions.len()
+            && le_u32(&raw[3..7]) as usize == counts[0]
+            && le_u32(&raw[7..11]) as usize == counts[1];
+        if !valid {
+            return Err(SolverError::Corrupt(format!(
+                "node {tree_id} does not match the tree"
+            )));
+        }
+
+        let mut floats = raw[11..]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+        let strategy = floats.by_ref().take(strategy_len).collect();
+        let ev0 = floats.by_ref().take(counts[0]).collect();
+        let ev1 = floats.collect();
+        Ok(Some(NodeData {
+            tree_id,
+            runout: runout.to_vec(),
+            player,
+            actions,
+            strategy,
+            ev: [ev0, ev1],
+        }))
+    }
+}
+
+fn le_u32(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn le_u64(b: &[u8]) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&b[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::SolverConfig;
+    use poker_core::game_tree::StreetConfig;
+    use poker_core::{BetSize, Board, Chips, Street};
+    use poker_eval::LookupTableEvaluator;
+    use std::io::Cursor;
+
+    fn card(s: &str) -> Card {
+        Card::from_str_notation(s).expect("valid card")
+    }
+
+    fn solved_turn() -> Solver {
+        let sizes = StreetConfig {
+            bet_sizes: vec![BetSize::PotFraction(0.75)],
+            raise_sizes: Vec::new(),
+            raise_cap: 0,
+        };
+        let tree = TreeConfig {
+            starting_pot: Chips::from_units(100),
+            effective_stack: Chips::from_units(150),
+            big_blind: Chips::from_units(1),
+            starting_street: Street::Turn,
+            flop: sizes.clone(),
+            turn: sizes.clone(),
+            river: sizes,
+            all_in_threshold: 1.0,
+            force_all_in_spr: 0.0,
+        };
+        let cards: Vec<Card> = ["Ah", "Kd", "7c", "4s"].iter().map(|c| card(c)).collect();
+        let board = Board::from_cards(&cards).expect("valid board");
+        let oop: Range = "77,A5s,98s".parse().expect("valid");
+        let ip: Range = "KQs,44".parse().expect("valid");
+        let config = SolverConfig {
+            iterations: 20,
+            threads: 1,
+            ..SolverConfig::default()
+        };
+        let eval = LookupTableEvaluator::global();
+        let mut solver = Solver::new(eval, [&oop, &ip], &board, &tree, config).expect("valid");
+        solver.solve();
+        solver
+    }
+
+    fn encode(solver: &Solver) -> Vec<u8> {
+        let mut buf = Cursor::new(Vec::new());
+        write_solution(solver, &mut buf).expect("written");
+        buf.into_inner()
+    }
+
+    #[test]
+    fn test_round_trip_and_lazy_nodes() {
+        let solver = solved_turn();
+        let bytes = encode(&solver);
+        assert_eq!(&bytes[..8], &MAGIC);
+
+        let mut reader = SolutionReader::new(Cursor::new(bytes)).expect("valid file");
+        assert_eq!(reader.header().board, solver.board());
+        assert_eq!(reader.header().ranges[1], *solver.range(1));
+        assert_eq!(reader.header().iterations, 20);
+
+        let root = reader.node(0, &[]).expect("readable").expect("stored");
+        let expected = solver.average_strategy(0, &[]).expect("player node");
+        assert_eq!(root.actions, expected.actions);
+        assert_eq!(root.strategy, expected.frequencies);
+        assert_eq!(root.ev[0].len(), solver.hands(0).len());
+
+        // A river node: check, check, deal 2c
+        let tree = reader.tree().clone();
+        let chance = tree.node(tree.root().children[0]).children[0];
+        let river = tree.node(chance).children[0];
+        let runout = [card("2c")];
+        let node = reader
+            .node(river, &runout)
+            .expect("readable")
+            .expect("stored");
+        let expected = solver
+            .average_strategy(river, &runout)
+            .expect("player node");
+        assert_eq!(node.strategy, expected.frequencies);
+        // Combos holding the 2c cannot reach this node
+        let blocked = solver
+            .hands(0)
+            .iter()
+            .position(|h| h.mask() & card("2c").mask() != 0);
+        assert!(blocked.is_none_or(|h| node.ev[0][h] == 0.0));
+
+        assert!(reader
+            .node(river, &[card("Ah")])
+            .expect("readable")
+            .is_none());
+        assert_eq!(reader.entries().count(), reader.node_count());
+    }
+
+    #[test]
+    fn test_node_evs_are_consistent() {
+        let solver = solved_turn();
+        let mut reader = SolutionReader::new(Cursor::new(encode(&solver))).expect("valid");
+        let root = reader.node(0, &[]).expect("readable").expect("stored");
+        // Weighted EVs of both players sum to the starting pot
+        let ev = |p: usize| -> f64 {
+            let game = solver.game();
+            let pairs = compatible_reach(game, p, &game.weights[1 - p]);
+            let (mut total, mut mass) = (0.0, 0.0);
+            for ((&w, &pr), &v) in game.weights[p].iter().zip(&pairs).zip(&root.ev[p]) {
+                total += w as f64 * pr * v as f64;
+                mass += w as f64 * pr;
+            }
+            total / mass
+        };
+        assert!((ev(0) + ev(1) - 100.0).abs() < 1e-2);
+        let e = reader.header().exploitability;
+        assert!(e.best_response_ev[0] >= ev(0) - 1e-2);
+        assert!(e.best_response_ev[1] >= ev(1) - 1e-2);
+    }
+
+    #[test]
+    fn test_corrupt_and_truncated_files() {
+        let bytes = encode(&solved_turn());
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(
+            SolutionReader::new(Cursor::new(bad_magic)),
+            Err(SolverError::BadMagic)
+        ));
+
+        let mut bad_version = bytes.clone();
+        bad_version[8] = 9;
+        assert!(matches!(
+            SolutionReader::new(Cursor::new(bad_version)),
+            Err(SolverError::UnsupportedVersion(9))
+        ));
+
+        for cut in [4, 30, 100, bytes.len() - 1] {
+            assert!(matches!(
+                SolutionReader::new(Cursor::new(bytes[..cut].to_vec())),
+                Err(SolverError::Truncated) | Err(SolverError::Corrupt(_))
+            ));
+        }
+        assert!(matches!(
+            SolutionReader::new(Cursor::new(bytes[..bytes.len() - 1].to_vec())),
+            Err(SolverError::Truncated)
+        ));
+
+        let mut bad_header = bytes.clone();
+        bad_header[40] ^= 0xFF;
+        assert!(matches!(
+            SolutionReader::new(Cursor::new(bad_header)),
+            Err(SolverError::Corrupt(_))
+        ));
+
+        // Flip a byte inside the first node block: opening works, loading fails
+        let header_len = le_u32(&bytes[12..16]) as usize;
+        let mut bad_node = bytes.clone();
+        bad_node[36 + header_len + 2] ^= 0xFF;
+        let mut reader = SolutionReader::new(Cursor::new(bad_node)).expect("index intact");
+        let results: Vec<_> = reader
+            .entries()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|(id, runout)| reader.node(id, &runout))
+            .collect();
+        assert!(results
+            .iter()
+            .any(|r| matches!(r, Err(SolverError::Corrupt(_)))));
+    }
+
+    #[test]
+    fn test_save_and_open_file() {
+        let solver = solved_turn();
+        let path = std::env::temp_dir().join(format!("pks-test-{}.pks", std::process::id()));
+        save_solution(&solver, &path).expect("saved");
+        let mut reader = SolutionReader::open(&path).expect("opened");
+        assert!(reader.node(0, &[]).expect("readable").is_some());
+        std::fs::remove_file(&path).expect("removed");
+    }
+}
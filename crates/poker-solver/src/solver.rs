@@ -0,0 +1,774 @@
+use std::collections::HashMap;
+
+use poker_core::game_tree::{GameTree, NodeId, TreeConfig};
+use poker_core::{Action, Board, Card, Hand, Range, Street};
+use poker_eval::Evaluator;
+use serde::{Deserialize, Serialize};
+
+use crate::best_response::Exploitability;
+use crate::error::SolverError;
+use crate::game::{
+    chance_values, deal_card, terminal_values, Expander, Game, SolverKind, SolverNode,
+};
+
+/// Discounting parameters of Discounted CFR (Brown & Sandholm, 2019).
+///
+/// After iteration `t`, accumulated positive regrets are scaled by
+/// `t^alpha / (t^alpha + 1)`, negative regrets by `t^beta / (t^beta + 1)`,
+/// and the average-strategy sum by `(t / (t + 1))^gamma`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Discount {
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+}
+
+impl Discount {
+    /// The recommended DCFR parameters: alpha 1.5, beta 0, gamma 2.
+    pub fn dcfr() -> Self {
+        Discount {
+            alpha: 1.5,
+            beta: 0.0,
+            gamma: 2.0,
+        }
+    }
+
+    /// CFR+: negative regrets are floored at zero and the average strategy
+    /// is weighted quadratically.
+    pub fn cfr_plus() -> Self {
+        Discount {
+            alpha: f64::INFINITY,
+            beta: f64::NEG_INFINITY,
+            gamma: 2.0,
+        }
+    }
+
+    /// Vanilla CFR: no discounting and a uniform average. Slow; for testing.
+    pub fn vanilla() -> Self {
+        Discount {
+            alpha: f64::INFINITY,
+            beta: f64::INFINITY,
+            gamma: 0.0,
+        }
+    }
+
+    /// Factors applied to what was accumulated through iteration `t`.
+    fn factors(self, t: u32) -> Factors {
+        let t = t as f64;
+        let regret = |exp: f64| {
+            if exp == f64::INFINITY {
+                1.0
+            } else if exp == f64::NEG_INFINITY {
+                0.0
+            } else {
+                let p = t.powf(exp);
+                p / (p + 1.0)
+            }
+        };
+        Factors {
+            positive: regret(self.alpha) as f32,
+            negative: regret(self.beta) as f32,
+            strategy: (t / (t + 1.0)).powf(self.gamma) as f32,
+        }
+    }
+}
+
+impl Default for Discount {
+    fn default() -> Self {
+        Self::dcfr()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Factors {
+    positive: f32,
+    negative: f32,
+    strategy: f32,
+}
+
+impl Factors {
+    fn regret(self, r: f32) -> f32 {
+        if r > 0.0 {
+            r * self.positive
+        } else {
+            r * self.negative
+        }
+    }
+}
+
+/// Solver settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolverConfig {
+    pub discount: Discount,
+    /// Iterations run by `Solver::solve`.
+    pub iterations: u32,
+    /// Worker threads; 0 uses every available core.
+    pub threads: usize,
+    /// Stop early once exploitability, in percent of the starting pot, is at
+    /// or below this.
+    pub target_exploitability: Option<f64>,
+    /// Iterations between exploitability checks when a target is set.
+    pub check_every: u32,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig {
+            discount: Discount::dcfr(),
+            iterations: 1000,
+            threads: 0,
+            target_exploitability: None,
+            check_every: 25,
+        }
+    }
+}
+
+/// Average strategy at one player node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeStrategy {
+    pub player: usize,
+    pub actions: Vec<Action>,
+    pub hands: Vec<Hand>,
+    /// Laid out `[action * hands.len() + hand]`; each hand's row sums to 1.
+    pub frequencies: Vec<f32>,
+}
+
+impl NodeStrategy {
+    /// Frequency of `action` (an index into `actions`) for a specific combo.
+    pub fn frequency(&self, action: usize, hand: Hand) -> Option<f32> {
+        let h = self.hands.iter().position(|&x| x == hand)?;
+        self.frequencies.get(action * self.hands.len() + h).copied()
+    }
+}
+
+/// Heads-up postflop solver using Discounted CFR over a `GameTree`.
+///
+/// Every turn and river card is expanded, so memory grows with
+/// `GameTree::stats().estimated_bytes`.
+pub struct Solver {
+    tree: GameTree,
+    board: Vec<Card>,
+    ranges: [Range; 2],
+    game: Game,
+    root: SolverNode,
+    config: SolverConfig,
+    iteration: u32,
+}
+
+impl Solver {
+    /// Build the tree and the per-runout storage for a spot.
+    /// `ranges` are OOP's then IP's; combos blocked by the board are dropped.
+    pub fn new(
+        evaluator: &dyn Evaluator,
+        ranges: [&Range; 2],
+        board: &Board,
+        tree_config: &TreeConfig,
+        config: SolverConfig,
+    ) -> Result<Self, SolverError> {
+        let street = tree_config.starting_street;
+        if street == Street::Preflop || board.street() != street {
+            return Err(SolverError::BoardStreetMismatch {
+                board: board.len(),
+                street,
+            });
+        }
+        let tree = GameTree::build(tree_config)?;
+        let game = Game::new(ranges, board.mask());
+        for (player, hands) in game.hands.iter().enumerate() {
+            if hands.is_empty() {
+                return Err(SolverError::EmptyRange(player));
+            }
+        }
+
+        let mut cards = board.cards().to_vec();
+        let root = Expander {
+            tree: &tree,
+            game: &game,
+            evaluator,
+            showdowns: HashMap::new(),
+        }
+        .expand(0, &mut cards);
+
+        Ok(Solver {
+            tree,
+            board: board.cards().to_vec(),
+            ranges: [ranges[0].clone(), ranges[1].clone()],
+            game,
+            root,
+            config,
+            iteration: 0,
+        })
+    }
+
+    pub fn tree(&self) -> &GameTree {
+        &self.tree
+    }
+
+    pub fn board(&self) -> &[Card] {
+        &self.board
+    }
+
+    /// The starting range of a player (0 = OOP, 1 = IP).
+    pub fn range(&self, player: usize) -> &Range {
+        &self.ranges[player]
+    }
+
+    pub fn config(&self) -> &SolverConfig {
+        &self.config
+    }
+
+    /// Live combos of a player, in the order used by `NodeStrategy`.
+    pub fn hands(&self, player: usize) -> &[Hand] {
+        &self.game.hands[player]
+    }
+
+    /// Starting weight of each combo in `hands(player)`.
+    pub fn weights(&self, player: usize) -> &[f32] {
+        &self.game.weights[player]
+    }
+
+    /// Iterations completed so far.
+    pub fn iterations(&self) -> u32 {
+        self.iteration
+    }
+
+    /// Run up to `config.iterations` iterations, stopping early once
+    /// `config.target_exploitability` is reached. Returns the last
+    /// exploitability measured, if any.
+    pub fn solve(&mut self) -> Option<Exploitability> {
+        let mut last = None;
+        for _ in 0..self.config.iterations {
+            self.iterate();
+            let Some(target) = self.config.target_exploitability else {
+                continue;
+            };
+            if !self
+                .iteration
+                .is_multiple_of(self.config.check_every.max(1))
+            {
+                continue;
+            }
+            let measured = self.exploitability();
+            tracing::debug!(
+                iteration = self.iteration,
+                exploitability = measured.pot_percent,
+                "solver progress"
+            );
+            let done = measured.pot_percent <= target;
+            last = Some(measured);
+            if done {
+                break;
+            }
+        }
+        last
+    }
+
+    /// Run one iteration, updating each player in turn.
+    pub fn iterate(&mut self) {
+        let factors = self.config.discount.factors(self.iteration);
+        let threads = self.threads();
+        for player in 0..2 {
+            let reach = &self.game.weights[1 - player];
+            cfr(&self.game, &mut self.root, player, reach, factors, threads);
+        }
+        self.iteration += 1;
+    }
+
+    pub(crate) fn threads(&self) -> usize {
+        match self.config.threads {
+            0 => std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            n => n,
+        }
+    }
+
+    /// Average strategy at a player node. `runout` lists the turn and river
+    /// cards dealt after the starting board, in order, as far as needed to
+    /// reach the node.
+    pub fn average_strategy(&self, node: NodeId, runout: &[Card]) -> Option<NodeStrategy> {
+        let found = self.find(node, runout)?;
+        let SolverKind::Player {
+            player,
+            strategy_sum,
+            ..
+        } = &found.kind
+        else {
+            return None;
+        };
+        Some(NodeStrategy {
+            player: *player,
+            actions: self.tree.node(node).actions.clone(),
+            hands: self.game.hands[*player].clone(),
+            frequencies: normalize(strategy_sum, found.children.len()),
+        })
+    }
+
+    /// Locate the expanded node for a tree node and runout.
+    pub(crate) fn find(&self, id: NodeId, runout: &[Card]) -> Option<&SolverNode> {
+        self.walk(id, runout, |_, _| {})
+    }
+
+    /// Locate the expanded node for a tree node and runout, calling
+    /// `visit(node, child)` for each node passed on the way.
+    pub(crate) fn walk(
+        &self,
+        id: NodeId,
+        runout: &[Card],
+        mut visit: impl FnMut(&SolverNode, usize),
+    ) -> Option<&SolverNode> {
+        let mut node = &self.root;
+        let mut dealt = runout.iter();
+        while node.tree_id != id {
+            let child = match &node.kind {
+                SolverKind::Chance { cards } => {
+                    let card = dealt.next()?;
+                    cards.iter().position(|c| c == card)?
+                }
+                // Tree ids are assigned in pre-order, so each subtree is a
+                // contiguous id range starting at its root
+                _ => node.children.iter().rposition(|c| c.tree_id <= id)?,
+            };
+            visit(node, child);
+            node = &node.children[child];
+        }
+        Some(node)
+    }
+
+    pub(crate) fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub(crate) fn root(&self) -> &SolverNode {
+        &self.root
+    }
+}
+
+/// Normalize per-hand rows of `[action * hands + hand]` values; rows with no
+/// positive mass become uniform.
+pub(crate) fn normalize(values: &[f32], actions: usize) -> Vec<f32> {
+    let hands = values.len() / actions.max(1);
+    let mut out = vec![0.0f32; values.len()];
+    for h in 0..hands {
+        let sum: f32 = (0..actions).map(|a| values[a * hands + h].max(0.0)).sum();
+        for a in 0..actions {
+            out[a * hands + h] = if sum > 0.0 {
+                values[a * hands + h].max(0.0) / sum
+            } else {
+                1.0 / actions as f32
+            };
+        }
+    }
+    out
+}
+
+/// Counterfactual values of `player`'s combos at `node`, given the
+/// opponent's reach, updating regrets and average strategies on the way.
+fn cfr(
+    game: &Game,
+    node: &mut SolverNode,
+    player: usize,
+    reach: &[f32],
+    factors: Factors,
+    threads: usize,
+) -> Vec<f32> {
+    let n = game.hands[player].len();
+    if reach.iter().all(|&r| r == 0.0) {
+        // Nothing below contributes this iteration, but the accumulated
+        // regrets and strategy sums still decay like everywhere else.
+        discount(node, player, factors);
+        return vec![0.0; n];
+    }
+    if let Some(values) = terminal_values(game, node, player, reach) {
+        return values;
+    }
+    let SolverNode { kind, children, .. } = node;
+
+    match kind {
+        SolverKind::Chance { cards } => {
+            let children = children.iter_mut().collect();
+            let values = map_children(children, threads, |i, child, t| {
+                let dealt = deal_card(game, player, reach, cards[i]);
+                cfr(game, child, player, &dealt, factors, t)
+            });
+            chance_values(game, player, cards, values)
+        }
+        SolverKind::Player {
+            player: actor,
+            regrets,
+            strategy_sum,
+        } => {
+            let actions = children.len();
+            let strategy = normalize(regrets, actions);
+            let children = children.iter_mut().collect();
+            if *actor == player {
+                let values = map_children(children, threads, |_, child, t| {
+                    cfr(game, child, player, reach, factors, t)
+                });
+                let mut out = vec![0.0f32; n];
+                for (a, v) in values.iter().enumerate() {
+                    for h in 0..n {
+                        out[h] += strategy[a * n + h] * v[h];
+                    }
+                }
+                for (a, v) in values.iter().enumerate() {
+                    for h in 0..n {
+                        let r = &mut regrets[a * n + h];
+                        *r = factors.regret(*r) + v[h] - out[h];
+                    }
+                }
+                out
+            } else {
+                let m = reach.len();
+                let values = map_children(children, threads, |a, child, t| {
+                    let next: Vec<f32> = reach
+                        .iter()
+                        .enumerate()
+                        .map(|(o, &r)| r * strategy[a * m + o])
+                        .collect();
+                    cfr(game, child, player, &next, factors, t)
+                });
+                for a in 0..actions {
+                    for (o, &r) in reach.iter().enumerate() {
+                        let s = &mut strategy_sum[a * m + o];
+                        *s = *s * factors.strategy + strategy[a * m + o] * r;
+                    }
+                }
+                sum_values(n, values)
+            }
+        }
+        SolverKind::Fold { .. } | SolverKind::Showdown(_) => unreachable!("handled above"),
+    }
+}
+
+/// Apply one iteration's discount to a subtree the opponent never reaches:
+/// `player`'s regrets and the opponent's strategy sums, as `cfr` would with
+/// all-zero values and reach.
+fn discount(node: &mut SolverNode, player: usize, factors: Factors) {
+    if let SolverKind::Player {
+        player: actor,
+        regrets,
+        strategy_sum,
+    } = &mut node.kind
+    {
+        if *actor == player {
+            for r in regrets.iter_mut() {
+                *r = factors.regret(*r);
+            }
+        } else {
+            for s in strategy_sum.iter_mut() {
+                *s *= factors.strategy;
+            }
+        }
+    }
+    for child in &mut node.children {
+        discount(child, player, factors);
+    }
+}
+
+/// Element-wise sum of per-child values.
+pub(crate) fn sum_values(n: usize, values: Vec<Vec<f32>>) -> Vec<f32> {
+    let mut out = vec![0.0f32; n];
+    for v in values {
+        for (o, x) in out.iter_mut().zip(v) {
+            *o += x;
+        }
+    }
+    out
+}
+
+/// Run `f(index, child, threads)` over every child, splitting the children
+/// across up to `threads` scoped threads. Leftover threads are handed down.
+pub(crate) fn map_children<T, F>(children: Vec<T>, threads: usize, f: F) -> Vec<Vec<f32>>
+where
+    T: Send,
+    F: Fn(usize, T, usize) -> Vec<f32> + Sync,
+{
+    if threads <= 1 || children.len() <= 1 {
+        return children
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| f(i, c, threads))
+            .collect();
+    }
+    let groups = threads.min(children.len());
+    let per_group = children.len().div_ceil(groups);
+    let inner = (threads / groups).max(1);
+    let mut chunks: Vec<Vec<(usize, T)>> = Vec::with_capacity(groups);
+    for (i, child) in children.into_iter().enumerate() {
+        if i % per_group == 0 {
+            chunks.push(Vec::with_capacity(per_group));
+        }
+        if let Some(chunk) = chunks.last_mut() {
+            chunk.push((i, child));
+        }
+    }
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .into_iter()
+                        .map(|(i, c)| f(i, c, inner))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("solver thread panicked"))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::game_tree::{StreetConfig, IP, OOP};
+    use poker_core::{BetSize, Chips};
+    use poker_eval::LookupTableEvaluator;
+
+    fn card(s: &str) -> Card {
+        Card::from_str_notation(s).expect("valid card")
+    }
+
+    fn board(cards: &[&str]) -> Board {
+        let cards: Vec<Card> = cards.iter().map(|c| card(c)).collect();
+        Board::from_cards(&cards).expect("valid board")
+    }
+
+    fn street(bets: &[f64], raises: &[f64], cap: u32) -> StreetConfig {
+        StreetConfig {
+            bet_sizes: bets.iter().map(|&f| BetSize::PotFraction(f)).collect(),
+            raise_sizes: raises.iter().map(|&f| BetSize::PotFraction(f)).collect(),
+            raise_cap: cap,
+        }
+    }
+
+    /// OOP may bet pot; IP may only check back, fold or call.
+    fn polarized_river() -> (Range, Range, Board, TreeConfig) {
+        let tree = TreeConfig {
+            starting_pot: Chips::from_units(100),
+            effective_stack: Chips::from_units(100),
+            big_blind: Chips::from_units(1),
+            starting_street: Street::River,
+            flop: street(&[], &[], 0),
+            turn: street(&[], &[], 0),
+            river: street(&[1.0], &[], 0),
+            all_in_threshold: 1.0,
+            force_all_in_spr: 0.0,
+        };
+        // 77 (a set, 3 combos) and 98s (air, 4 combos) vs a KQ bluff-catcher
+        let oop: Range = "77,98s".parse().expect("valid");
+        let ip: Range = "KQ".parse().expect("valid");
+        (oop, ip, board(&["Ah", "Kd", "7c", "4s", "2h"]), tree)
+    }
+
+    fn config(discount: Discount, iterations: u32, threads: usize) -> SolverConfig {
+        SolverConfig {
+            discount,
+            iterations,
+            threads,
+            ..SolverConfig::default()
+        }
+    }
+
+    /// Mean frequency of an action over combos, weighted by reach.
+    fn mean(strategy: &NodeStrategy, action: usize, hands: &[Hand]) -> f32 {
+        let total: f32 = hands
+            .iter()
+            .map(|&h| strategy.frequency(action, h).expect("in range"))
+            .sum();
+        total / hands.len() as f32
+    }
+
+    #[test]
+    fn test_polarized_river_equilibrium() {
+        let eval = LookupTableEvaluator::global();
+        let (oop, ip, board, tree_config) = polarized_river();
+        for discount in [Discount::dcfr(), Discount::cfr_plus()] {
+            let mut solver = Solver::new(
+                eval,
+                [&oop, &ip],
+                &board,
+                &tree_config,
+                config(discount, 2000, 1),
+            )
+            .expect("valid spot");
+            solver.solve();
+
+            let root = solver.average_strategy(0, &[]).expect("player node");
+            assert_eq!(root.player, OOP);
+            assert_eq!(
+                root.actions,
+                vec![Action::Check, Action::AllIn(Chips::from_units(100))]
+            );
+            let (sets, air): (Vec<Hand>, Vec<Hand>) = root.hands.iter().partition(|h| h.is_pair());
+            // Value always bets; air bluffs so IP is indifferent (1.5 of 4 combos)
+            assert!(mean(&root, 1, &sets) > 0.95);
+            assert!((mean(&root, 1, &air) - 0.375).abs() < 0.05);
+
+            // IP calls half the time against a pot-sized bet
+            let facing_id = solver.tree().root().children[1];
+            let facing = solver
+                .average_strategy(facing_id, &[])
+                .expect("player node");
+            assert_eq!(facing.player, IP);
+            assert!((mean(&facing, 1, &facing.hands.clone()) - 0.5).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_unreached_subtree_is_discounted() {
+        let eval = LookupTableEvaluator::global();
+        let (oop, ip, board, tree_config) = polarized_river();
+        let mut solver = Solver::new(
+            eval,
+            [&oop, &ip],
+            &board,
+            &tree_config,
+            config(Discount::dcfr(), 10, 1),
+        )
+        .expect("valid spot");
+        solver.solve();
+
+        let accumulated = |node: &SolverNode| match &node.kind {
+            SolverKind::Player {
+                regrets,
+                strategy_sum,
+                ..
+            } => (regrets.clone(), strategy_sum.clone()),
+            _ => panic!("player node"),
+        };
+        let (root_regrets, _) = accumulated(&solver.root);
+        let (_, facing_sums) = accumulated(&solver.root.children[1]);
+
+        // OOP's traversal with IP's reach at zero only decays the subtree
+        let factors = Discount::dcfr().factors(solver.iterations());
+        let zero = vec![0.0; solver.game.hands[IP].len()];
+        let values = cfr(&solver.game, &mut solver.root, OOP, &zero, factors, 1);
+        assert!(values.iter().all(|&v| v == 0.0));
+
+        let (regrets, _) = accumulated(&solver.root);
+        let (_, sums) = accumulated(&solver.root.children[1]);
+        assert!(root_regrets.iter().any(|&r| r != 0.0));
+        for (r, old) in regrets.iter().zip(&root_regrets) {
+            assert_eq!(*r, factors.regret(*old));
+        }
+        for (s, old) in sums.iter().zip(&facing_sums) {
+            assert_eq!(*s, old * factors.strategy);
+        }
+    }
+
+    #[test]
+    fn test_dominated_hand_folds() {
+        let eval = LookupTableEvaluator::global();
+        let (_, _, board, tree_config) = polarized_river();
+        let oop: Range = "77".parse().expect("valid");
+        let ip: Range = "QQ".parse().expect("valid");
+        let mut solver = Solver::new(
+            eval,
+            [&oop, &ip],
+            &board,
+            &tree_config,
+            config(Discount::vanilla(), 200, 1),
+        )
+        .expect("valid spot");
+        solver.solve();
+        let facing_id = solver.tree().root().children[1];
+        let facing = solver
+            .average_strategy(facing_id, &[])
+            .expect("player node");
+        assert!(mean(&facing, 0, &facing.hands.clone()) > 0.95);
+    }
+
+    #[test]
+    fn test_threads_match_single_threaded_turn() {
+        let eval = LookupTableEvaluator::global();
+        let tree_config = TreeConfig {
+            starting_pot: Chips::from_units(100),
+            effective_stack: Chips::from_units(200),
+            big_blind: Chips::from_units(1),
+            starting_street: Street::Turn,
+            flop: street(&[], &[], 0),
+            turn: street(&[0.5], &[1.0], 1),
+            river: street(&[1.0], &[], 0),
+            all_in_threshold: 1.0,
+            force_all_in_spr: 0.0,
+        };
+        let board = board(&["Ah", "Kd", "7c", "4s"]);
+        let oop: Range = "77,A5s,98s".parse().expect("valid");
+        let ip: Range = "KQs,44".parse().expect("valid");
+
+        let mut single = Solver::new(
+            eval,
+            [&oop, &ip],
+            &board,
+            &tree_config,
+            config(Discount::dcfr(), 5, 1),
+        )
+        .expect("valid spot");
+        let mut multi = Solver::new(
+            eval,
+            [&oop, &ip],
+            &board,
+            &tree_config,
+            config(Discount::dcfr(), 5, 4),
+        )
+        .expect("valid spot");
+        single.solve();
+        multi.solve();
+        assert_eq!(single.iterations(), 5);
+        assert_eq!(
+            single.average_strategy(0, &[]),
+            multi.average_strategy(0, &[])
+        );
+
+        // A river node is reachable through its runout card
+        let chance = solver_river_node(&single);
+        let river = single
+            .average_strategy(chance, &[card("2c")])
+            .expect("river node");
+        assert_eq!(river.player, OOP);
+        assert!(single.average_strategy(chance, &[card("Ah")]).is_none());
+    }
+
+    /// First river player node after check, check on the turn.
+    fn solver_river_node(solver: &Solver) -> NodeId {
+        let tree = solver.tree();
+        let ip = tree.root().children[0];
+        let chance = tree.node(ip).children[0];
+        tree.node(chance).children[0]
+    }
+
+    #[test]
+    fn test_invalid_spots() {
+        let eval = LookupTableEvaluator::global();
+        let (oop, ip, _, tree_config) = polarized_river();
+        let flop = board(&["Ah", "Kd", "7c"]);
+        assert!(matches!(
+            Solver::new(
+                eval,
+                [&oop, &ip],
+                &flop,
+                &tree_config,
+                SolverConfig::default()
+            ),
+            Err(SolverError::BoardStreetMismatch { board: 3, .. })
+        ));
+        let river = board(&["7h", "7d", "7s", "4s", "2h"]);
+        let sevens: Range = "77".parse().expect("valid");
+        assert!(matches!(
+            Solver::new(
+                eval,
+                [&sevens, &ip],
+                &river,
+                &tree_config,
+                SolverConfig::default()
+            ),
+            Err(SolverError::EmptyRange(0))
+        ));
+    }
+}
@@ -11,4 +11,9 @@ export interface EquityCalcRequest {
 
 export interface EquityCalcResponse {
   equity: number;
+  win: number;
+  tie: number;
+  /** Per hand class equity (169 entries, null if not in range) */
+  hand_equity1: (number | null)[];
+  hand_equity2: (number | null)[];
 }